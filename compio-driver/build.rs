@@ -11,10 +11,12 @@ fn main() {
             target_os = "netbsd"
         ) },
         gnulinux: { all(target_os = "linux", target_env = "gnu") },
+        linux_all: { any(target_os = "linux", target_os = "android") },
         freebsd: { target_os = "freebsd" },
         solarish: { any(target_os = "illumos", target_os = "solaris") },
         aio: { any(freebsd, solarish) },
         io_uring: { all(target_os = "linux", feature = "io-uring") },
-        fusion: { all(target_os = "linux", feature = "io-uring", feature = "polling") }
+        fusion: { all(target_os = "linux", feature = "io-uring", feature = "polling") },
+        linux_aio: { all(target_os = "linux", any(feature = "polling", not(feature = "io-uring"))) }
     }
 }
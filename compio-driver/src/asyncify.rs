@@ -1,13 +1,13 @@
 use std::{
+    collections::VecDeque,
     fmt,
-    sync::{
-        Arc,
-        atomic::{AtomicUsize, Ordering},
-    },
+    future::poll_fn,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Wake, Waker},
     time::Duration,
 };
 
-use crossbeam_channel::{Receiver, Sender, TrySendError, bounded};
+use crate::ProactorBuilder;
 
 /// An error that may be emitted when all worker threads are busy. It simply
 /// returns the dispatchable value with a convenient [`fmt::Debug`] and
@@ -55,80 +55,533 @@ where
     }
 }
 
-struct CounterGuard(Arc<AtomicUsize>);
+/// The priority of a dispatchable. The queued dispatchables of higher priority
+/// are run first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Run after the others, e.g., background maintenance.
+    Low,
+    /// The default priority.
+    #[default]
+    Normal,
+    /// Run before the others, e.g., latency sensitive work.
+    High,
+}
+
+impl Priority {
+    fn lane(self) -> usize {
+        match self {
+            Self::High => 0,
+            Self::Normal => 1,
+            Self::Low => 2,
+        }
+    }
+}
+
+/// The id of a dispatchable in an [`AsyncifyPool`], to cancel it before it
+/// starts, see [`AsyncifyPool::cancel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(usize);
+
+impl JobId {
+    // The dispatchables of the operations use the user data of the operations
+    // as the ids, which are aligned pointers, so the other ids are odd.
+    fn from_user_data(user_data: usize) -> Self {
+        Self(user_data)
+    }
+}
+
+struct Job {
+    id: JobId,
+    f: BoxedDispatchable,
+}
+
+struct State {
+    lanes: [VecDeque<Job>; 3],
+    threads: usize,
+    idle: usize,
+    next_id: usize,
+    // Waiting for the pool to accept new dispatchables.
+    waiters: Vec<Waker>,
+}
+
+impl State {
+    fn queued(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    fn pop(&mut self) -> Option<Job> {
+        self.lanes.iter_mut().find_map(VecDeque::pop_front)
+    }
+
+    fn wake_waiters(mut state: MutexGuard<Self>) {
+        let waiters = std::mem::take(&mut state.waiters);
+        drop(state);
+        waiters.into_iter().for_each(Waker::wake);
+    }
+}
+
+struct Shared {
+    name: Option<String>,
+    thread_limit: usize,
+    recv_timeout: Duration,
+    queue_capacity: usize,
+    state: Mutex<State>,
+    cond: Condvar,
+}
 
-impl Drop for CounterGuard {
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The dispatchables run without the lock.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether to spawn a new thread for a new dispatchable, or `None` if it
+    /// can't be accepted.
+    fn accepts(&self, state: &State) -> Option<bool> {
+        let queued = state.queued();
+        if queued < state.idle {
+            Some(false)
+        } else if state.threads < self.thread_limit {
+            Some(true)
+        } else if queued < state.idle + self.queue_capacity {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+// Decrease the thread count when the worker exits, even if a dispatchable
+// panics.
+struct WorkerGuard(Arc<Shared>);
+
+impl Drop for WorkerGuard {
     fn drop(&mut self) {
-        self.0.fetch_sub(1, Ordering::AcqRel);
+        let mut state = self.0.lock();
+        state.threads -= 1;
+        State::wake_waiters(state);
     }
 }
 
-fn worker(
-    receiver: Receiver<BoxedDispatchable>,
-    counter: Arc<AtomicUsize>,
-    timeout: Duration,
-) -> impl FnOnce() {
+fn worker(shared: Arc<Shared>) -> impl FnOnce() {
     move || {
-        counter.fetch_add(1, Ordering::AcqRel);
-        let _guard = CounterGuard(counter);
-        while let Ok(f) = receiver.recv_timeout(timeout) {
-            f.run();
+        let guard = WorkerGuard(shared);
+        let shared = &guard.0;
+        let mut state = shared.lock();
+        loop {
+            if let Some(job) = state.pop() {
+                State::wake_waiters(state);
+                job.f.run();
+                state = shared.lock();
+                continue;
+            }
+            state.idle += 1;
+            if !state.waiters.is_empty() {
+                // The worker becomes idle, and could accept a new dispatchable.
+                State::wake_waiters(state);
+                state = shared.lock();
+                if state.queued() > 0 {
+                    state.idle -= 1;
+                    continue;
+                }
+            }
+            let (new_state, res) = shared
+                .cond
+                .wait_timeout(state, shared.recv_timeout)
+                .unwrap_or_else(PoisonError::into_inner);
+            state = new_state;
+            state.idle -= 1;
+            if res.timed_out() && state.queued() == 0 {
+                break;
+            }
         }
     }
 }
 
-/// A thread pool to perform blocking operations in other threads.
+/// Builder for [`AsyncifyPool`].
 #[derive(Debug, Clone)]
-pub struct AsyncifyPool {
-    sender: Sender<BoxedDispatchable>,
-    receiver: Receiver<BoxedDispatchable>,
-    counter: Arc<AtomicUsize>,
+pub struct AsyncifyPoolBuilder {
+    name: Option<String>,
     thread_limit: usize,
     recv_timeout: Duration,
+    queue_capacity: usize,
+}
+
+impl Default for AsyncifyPoolBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncifyPoolBuilder {
+    /// Create the builder with the default config.
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            thread_limit: 256,
+            recv_timeout: Duration::from_secs(60),
+            queue_capacity: 0,
+        }
+    }
+
+    /// Set the name of the pool, which is also the name of the worker threads.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the thread number limit. The default value is 256.
+    pub fn thread_limit(&mut self, limit: usize) -> &mut Self {
+        self.thread_limit = limit;
+        self
+    }
+
+    /// Set the time an idle thread waits for new dispatchables before it
+    /// exits. The default value is 60 seconds.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.recv_timeout = timeout;
+        self
+    }
+
+    /// Set the number of dispatchables allowed to wait in the queue when all
+    /// threads are busy and the thread number limit has been reached. The
+    /// default value is 0, i.e., a dispatchable is only accepted if a thread
+    /// could run it immediately.
+    pub fn queue_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.queue_capacity = capacity;
+        self
+    }
+
+    /// Build the [`AsyncifyPool`].
+    pub fn build(&self) -> AsyncifyPool {
+        AsyncifyPool {
+            shared: Arc::new(Shared {
+                name: self.name.clone(),
+                thread_limit: self.thread_limit,
+                recv_timeout: self.recv_timeout,
+                queue_capacity: self.queue_capacity,
+                state: Mutex::new(State {
+                    lanes: Default::default(),
+                    threads: 0,
+                    idle: 0,
+                    next_id: 1,
+                    waiters: Vec::new(),
+                }),
+                cond: Condvar::new(),
+            }),
+        }
+    }
+}
+
+/// A thread pool to perform blocking operations in other threads.
+#[derive(Clone)]
+pub struct AsyncifyPool {
+    shared: Arc<Shared>,
+}
+
+impl fmt::Debug for AsyncifyPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncifyPool")
+            .field("name", &self.shared.name)
+            .field("thread_limit", &self.shared.thread_limit)
+            .field("recv_timeout", &self.shared.recv_timeout)
+            .field("queue_capacity", &self.shared.queue_capacity)
+            .finish_non_exhaustive()
+    }
 }
 
 impl AsyncifyPool {
     /// Create [`AsyncifyPool`] with thread number limit and channel receive
     /// timeout.
     pub fn new(thread_limit: usize, recv_timeout: Duration) -> Self {
-        let (sender, receiver) = bounded(0);
-        Self {
-            sender,
-            receiver,
-            counter: Arc::new(AtomicUsize::new(0)),
-            thread_limit,
-            recv_timeout,
-        }
+        AsyncifyPoolBuilder::new()
+            .thread_limit(thread_limit)
+            .recv_timeout(recv_timeout)
+            .build()
+    }
+
+    /// Create [`AsyncifyPoolBuilder`].
+    pub fn builder() -> AsyncifyPoolBuilder {
+        AsyncifyPoolBuilder::new()
+    }
+
+    /// The name of the pool, if set by [`AsyncifyPoolBuilder::name`].
+    pub fn name(&self) -> Option<&str> {
+        self.shared.name.as_deref()
+    }
+
+    /// The number of the worker threads alive.
+    pub fn thread_count(&self) -> usize {
+        self.shared.lock().threads
+    }
+
+    /// The number of the dispatchables waiting for a worker thread.
+    pub fn queue_len(&self) -> usize {
+        self.shared.lock().queued()
     }
 
     /// Send a dispatchable, usually a closure, to another thread. Usually the
-    /// user should not use it. When all threads are busy and thread number
-    /// limit has been reached, it will return an error with the original
-    /// dispatchable.
+    /// user should not use it. When all threads are busy, thread number
+    /// limit has been reached and the queue is full, it will return an error
+    /// with the original dispatchable.
     pub fn dispatch<D: Dispatchable>(&self, f: D) -> Result<(), DispatchError<D>> {
-        match self.sender.try_send(Box::new(f) as BoxedDispatchable) {
-            Ok(_) => Ok(()),
-            Err(e) => match e {
-                TrySendError::Full(f) => {
-                    if self.counter.load(Ordering::Acquire) >= self.thread_limit {
-                        // Safety: we can ensure the type
-                        Err(DispatchError(*unsafe {
-                            Box::from_raw(Box::into_raw(f).cast())
-                        }))
+        self.dispatch_with(f, Priority::Normal).map(|_| ())
+    }
+
+    /// Send a dispatchable with the priority. See [`AsyncifyPool::dispatch`].
+    pub fn dispatch_with<D: Dispatchable>(
+        &self,
+        f: D,
+        priority: Priority,
+    ) -> Result<JobId, DispatchError<D>> {
+        self.push(None, priority, Box::new(f)).map_err(|f| {
+            // Safety: we can ensure the type
+            DispatchError(*unsafe { Box::from_raw(Box::into_raw(f).cast()) })
+        })
+    }
+
+    /// Dispatch the blocking part of an operation, with the user data as the
+    /// id.
+    pub(crate) fn dispatch_op(
+        &self,
+        user_data: usize,
+        priority: Priority,
+        f: BoxedDispatchable,
+    ) -> Result<(), BoxedDispatchable> {
+        self.push(Some(JobId::from_user_data(user_data)), priority, f)
+            .map(|_| ())
+    }
+
+    fn push(
+        &self,
+        id: Option<JobId>,
+        priority: Priority,
+        f: BoxedDispatchable,
+    ) -> Result<JobId, BoxedDispatchable> {
+        let mut state = self.shared.lock();
+        let Some(spawn) = self.shared.accepts(&state) else {
+            return Err(f);
+        };
+        if spawn {
+            let mut builder = std::thread::Builder::new();
+            if let Some(name) = &self.shared.name {
+                builder = builder.name(name.clone());
+            }
+            builder
+                .spawn(worker(self.shared.clone()))
+                .expect("failed to spawn a worker thread");
+            state.threads += 1;
+        }
+        let id = id.unwrap_or_else(|| {
+            let id = JobId(state.next_id);
+            state.next_id = state.next_id.wrapping_add(2);
+            id
+        });
+        state.lanes[priority.lane()].push_back(Job { id, f });
+        drop(state);
+        self.shared.cond.notify_one();
+        Ok(id)
+    }
+
+    /// Poll whether a new dispatchable would be accepted. If not, the waker is
+    /// woken when a worker thread or a slot in the queue becomes available.
+    pub fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.shared.lock();
+        if self.shared.accepts(&state).is_some() {
+            Poll::Ready(())
+        } else {
+            if !state.waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                state.waiters.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+
+    /// Wait until a new dispatchable would be accepted, see
+    /// [`AsyncifyPool::poll_ready`]. Another dispatcher may still take the
+    /// slot before the dispatchable is sent.
+    pub async fn ready(&self) {
+        poll_fn(|cx| self.poll_ready(cx)).await
+    }
+
+    /// Cancel a dispatchable waiting in the queue, which is dropped without
+    /// running. Returns `false` if it has started or completed.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let mut state = self.shared.lock();
+        let job = state.lanes.iter_mut().find_map(|lane| {
+            let pos = lane.iter().position(|job| job.id == id)?;
+            lane.remove(pos)
+        });
+        State::wake_waiters(state);
+        job.is_some()
+    }
+
+    pub(crate) fn cancel_op(&self, user_data: usize) -> bool {
+        self.cancel(JobId::from_user_data(user_data))
+    }
+}
+
+/// Where and how the blocking part of an operation is dispatched, see
+/// [`Asyncify::with_options`](crate::op::Asyncify::with_options).
+#[derive(Debug, Clone, Default)]
+pub struct DispatchOptions {
+    pool: Option<AsyncifyPool>,
+    priority: Priority,
+    cancel_queued: bool,
+}
+
+impl DispatchOptions {
+    /// Create the default options: the pool of the proactor, and
+    /// [`Priority::Normal`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dispatch to the pool instead of the one of the proactor.
+    pub fn pool(mut self, pool: AsyncifyPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Dispatch with the priority.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Drop the function without running it if the operation is cancelled
+    /// while it is still waiting for a thread. By default, it runs to
+    /// completion anyway. The built-in operations are always dropped.
+    pub fn cancel_queued(mut self, cancel: bool) -> Self {
+        self.cancel_queued = cancel;
+        self
+    }
+
+    /// The pool to dispatch to, if not the one of the proactor.
+    pub fn get_pool(&self) -> Option<&AsyncifyPool> {
+        self.pool.as_ref()
+    }
+
+    /// The priority to dispatch with.
+    pub fn get_priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Whether the function is dropped if cancelled while queued.
+    pub fn get_cancel_queued(&self) -> bool {
+        self.cancel_queued
+    }
+}
+
+struct NotifyWaker<F>(F);
+
+impl<F: Fn() + Send + Sync + 'static> Wake for NotifyWaker<F> {
+    fn wake(self: Arc<Self>) {
+        (self.0)()
+    }
+}
+
+struct Backlogged {
+    user_data: usize,
+    options: DispatchOptions,
+    f: BoxedDispatchable,
+}
+
+/// The blocking operations of a driver. The ones not accepted by the pools are
+/// kept in the backlog, and dispatched again when the driver is notified that
+/// the pools are ready.
+pub(crate) struct BlockingOps {
+    pool: AsyncifyPool,
+    // The options of the built-in operations.
+    options: DispatchOptions,
+    waker: Waker,
+    backlog: VecDeque<Backlogged>,
+}
+
+impl BlockingOps {
+    pub fn new(builder: &ProactorBuilder, notify: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            pool: builder.create_or_get_thread_pool(),
+            options: builder.op_dispatch.clone(),
+            waker: Waker::from(Arc::new(NotifyWaker(notify))),
+            backlog: VecDeque::new(),
+        }
+    }
+
+    /// The default pool.
+    #[cfg(feature = "metrics")]
+    pub fn pool(&self) -> &AsyncifyPool {
+        &self.pool
+    }
+
+    /// Dispatch the blocking part of an operation after the backlog. Returns
+    /// the number of the operations dispatched.
+    pub fn dispatch(
+        &mut self,
+        user_data: usize,
+        options: Option<DispatchOptions>,
+        f: impl Dispatchable,
+    ) -> usize {
+        self.backlog.push_back(Backlogged {
+            user_data,
+            options: options.unwrap_or_else(|| self.options.clone()),
+            f: Box::new(f),
+        });
+        self.retry()
+    }
+
+    /// Dispatch the operations in the backlog. Returns the number dispatched.
+    /// The operations of a full pool don't block the ones of the other pools.
+    pub fn retry(&mut self) -> usize {
+        let mut dispatched = 0;
+        let mut full: Vec<AsyncifyPool> = vec![];
+        let mut backlog = VecDeque::with_capacity(self.backlog.len());
+        while let Some(op) = self.backlog.pop_front() {
+            let pool = op.options.pool.as_ref().unwrap_or(&self.pool);
+            if full.iter().any(|p| Arc::ptr_eq(&p.shared, &pool.shared)) {
+                backlog.push_back(op);
+                continue;
+            }
+            match pool.dispatch_op(op.user_data, op.options.priority, op.f) {
+                Ok(()) => dispatched += 1,
+                Err(f) => {
+                    if pool
+                        .poll_ready(&mut Context::from_waker(&self.waker))
+                        .is_pending()
+                    {
+                        full.push(pool.clone());
+                        backlog.push_back(Backlogged { f, ..op });
                     } else {
-                        std::thread::spawn(worker(
-                            self.receiver.clone(),
-                            self.counter.clone(),
-                            self.recv_timeout,
-                        ));
-                        self.sender.send(f).expect("the channel should not be full");
-                        Ok(())
+                        // A slot is just released.
+                        self.backlog.push_front(Backlogged { f, ..op });
                     }
                 }
-                TrySendError::Disconnected(_) => {
-                    unreachable!("receiver should not all disconnected")
-                }
-            },
+            }
+        }
+        self.backlog = backlog;
+        dispatched
+    }
+
+    /// Cancel the blocking part of an operation if not started. Returns
+    /// `true` if cancelled. The operations with options, i.e., the
+    /// [`Asyncify`] ones, are only cancelled with
+    /// [`DispatchOptions::cancel_queued`].
+    ///
+    /// [`Asyncify`]: crate::op::Asyncify
+    pub fn cancel(&mut self, user_data: usize, options: Option<&DispatchOptions>) -> bool {
+        if options.is_some_and(|options| !options.cancel_queued) {
+            return false;
+        }
+        if let Some(pos) = self.backlog.iter().position(|op| op.user_data == user_data) {
+            self.backlog.remove(pos);
+            return true;
         }
+        options
+            .unwrap_or(&self.options)
+            .pool
+            .as_ref()
+            .unwrap_or(&self.pool)
+            .cancel_op(user_data)
     }
 }
@@ -0,0 +1,296 @@
+use std::{fmt, io, sync::Arc, task::Poll, time::Duration};
+
+#[cfg(feature = "metrics")]
+use crate::DriverMetrics;
+use crate::{
+    AsRawFd, BufferPool, FixedFd, Key, LinkedOp, OpCode, OwnedFd, ProactorBuilder, RawFd,
+    RegisteredBuffers, RingMessage, SetupReport, sys,
+};
+
+/// A custom backend of [`Proactor`], to run the operations over something
+/// other than the driver of the platform, e.g., a simulation. It is set by
+/// [`ProactorBuilder::backend`].
+///
+/// The operations are pushed as type-erased [`Key`]s. The backend inspects
+/// them with [`Key::view`], and completes them with [`Key::complete`]. An
+/// operation described by [`OpView::Other`] is not supported by the backend,
+/// and usually fails with [`io::ErrorKind::Unsupported`].
+///
+/// [`Proactor`]: crate::Proactor
+/// [`OpView::Other`]: crate::op::OpView::Other
+pub trait Backend: AsRawFd {
+    /// Attach an fd to the backend, see [`Proactor::attach`].
+    ///
+    /// [`Proactor::attach`]: crate::Proactor::attach
+    fn attach(&mut self, fd: RawFd) -> io::Result<()> {
+        let _ = fd;
+        Ok(())
+    }
+
+    /// Start an operation. If it completes immediately, return the result and
+    /// don't keep the key. Otherwise, keep the key and complete it later with
+    /// [`Key::complete`].
+    fn push(&mut self, op: Key<dyn OpCode>) -> Poll<io::Result<usize>>;
+
+    /// Cancel a pushed operation. The operation should still be completed,
+    /// usually with an error, and it is completed at most once.
+    fn cancel(&mut self, op: &mut Key<dyn OpCode>);
+
+    /// Cancel the operations submitted against the fd, see
+    /// [`Proactor::cancel_fd`].
+    ///
+    /// [`Proactor::cancel_fd`]: crate::Proactor::cancel_fd
+    fn cancel_fd(&mut self, fd: RawFd) {
+        let _ = fd;
+    }
+
+    /// Wait for the operations to complete, at most `timeout`. It should
+    /// return an error of [`io::ErrorKind::TimedOut`] if nothing completes.
+    fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// Create a handle to interrupt [`Backend::poll`] from other threads.
+    fn handle(&self) -> NotifyHandle;
+
+    /// The duration until the next timer expires, see
+    /// [`Proactor::next_timeout`].
+    ///
+    /// [`Proactor::next_timeout`]: crate::Proactor::next_timeout
+    fn next_timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+type NotifyFn = dyn Fn() -> io::Result<()> + Send + Sync;
+
+enum NotifyHandleInner {
+    Native(sys::NotifyHandle),
+    Custom(Arc<NotifyFn>),
+}
+
+/// A notify handle to the inner driver.
+pub struct NotifyHandle {
+    inner: NotifyHandleInner,
+}
+
+impl NotifyHandle {
+    /// Create a handle for a custom [`Backend`], which calls `f` to notify it.
+    pub fn from_fn(f: impl Fn() -> io::Result<()> + Send + Sync + 'static) -> Self {
+        Self {
+            inner: NotifyHandleInner::Custom(Arc::new(f)),
+        }
+    }
+
+    /// Notify the inner driver.
+    pub fn notify(&self) -> io::Result<()> {
+        match &self.inner {
+            NotifyHandleInner::Native(handle) => handle.notify(),
+            NotifyHandleInner::Custom(f) => f(),
+        }
+    }
+}
+
+impl From<sys::NotifyHandle> for NotifyHandle {
+    fn from(handle: sys::NotifyHandle) -> Self {
+        Self {
+            inner: NotifyHandleInner::Native(handle),
+        }
+    }
+}
+
+type BackendFn = dyn Fn() -> io::Result<Box<dyn Backend>> + Send + Sync;
+
+/// The factory of a custom backend, see [`ProactorBuilder::backend`].
+#[derive(Clone)]
+pub(crate) struct BackendFactory(Arc<BackendFn>);
+
+impl BackendFactory {
+    pub fn new<B: Backend + 'static>(
+        f: impl Fn() -> io::Result<B> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(move || Ok(Box::new(f()?) as Box<dyn Backend>)))
+    }
+}
+
+impl fmt::Debug for BackendFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BackendFactory")
+    }
+}
+
+/// The driver of the platform, or a custom backend.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum AnyDriver {
+    Native(sys::Driver),
+    Custom(Box<dyn Backend>),
+}
+
+impl AnyDriver {
+    pub fn new(builder: &ProactorBuilder) -> io::Result<Self> {
+        if builder.driver_type.is_some_and(|ty| !ty.is_available()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the driver type is not available",
+            ));
+        }
+        match &builder.backend {
+            Some(BackendFactory(f)) => Ok(Self::Custom(f()?)),
+            None => Ok(Self::Native(sys::Driver::new(builder)?)),
+        }
+    }
+
+    pub fn create_op<T: OpCode + 'static>(&self, op: T) -> Key<T> {
+        match self {
+            Self::Native(driver) => driver.create_op(op),
+            Self::Custom(backend) => Key::new(backend.as_raw_fd(), op),
+        }
+    }
+
+    pub fn attach(&mut self, fd: RawFd) -> io::Result<()> {
+        match self {
+            Self::Native(driver) => driver.attach(fd),
+            Self::Custom(backend) => backend.attach(fd),
+        }
+    }
+
+    pub fn register_buffers(&mut self, buffers: &RegisteredBuffers) -> io::Result<()> {
+        match self {
+            Self::Native(driver) => driver.register_buffers(buffers),
+            Self::Custom(_) => Ok(()),
+        }
+    }
+
+    pub fn register_fd(&mut self, fd: OwnedFd) -> io::Result<FixedFd> {
+        match self {
+            Self::Native(driver) => driver.register_fd(fd),
+            Self::Custom(_) => Ok(FixedFd::from_fd(fd)),
+        }
+    }
+
+    #[cfg(unix)]
+    pub unsafe fn direct_fd(&mut self, res: usize) -> FixedFd {
+        use std::os::fd::FromRawFd;
+
+        match self {
+            Self::Native(driver) => driver.direct_fd(res),
+            Self::Custom(_) => FixedFd::from_fd(OwnedFd::from_raw_fd(res as _)),
+        }
+    }
+
+    pub fn supports(&self, opcode: u8) -> bool {
+        match self {
+            Self::Native(driver) => driver.supports(opcode),
+            Self::Custom(_) => false,
+        }
+    }
+
+    pub fn setup_report(&self) -> SetupReport {
+        match self {
+            Self::Native(driver) => driver.setup_report(),
+            Self::Custom(_) => SetupReport::default(),
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> DriverMetrics {
+        match self {
+            Self::Native(driver) => driver.metrics(),
+            Self::Custom(_) => DriverMetrics::default(),
+        }
+    }
+
+    pub fn pop_message(&mut self) -> Option<RingMessage> {
+        match self {
+            Self::Native(driver) => driver.pop_message(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    pub fn cancel(&mut self, op: &mut Key<dyn OpCode>) {
+        match self {
+            Self::Native(driver) => driver.cancel(op),
+            Self::Custom(backend) => backend.cancel(op),
+        }
+    }
+
+    pub fn cancel_fd(&mut self, fd: RawFd) {
+        match self {
+            Self::Native(driver) => driver.cancel_fd(fd),
+            Self::Custom(backend) => backend.cancel_fd(fd),
+        }
+    }
+
+    pub fn push(&mut self, op: &mut Key<dyn OpCode>) -> Poll<io::Result<usize>> {
+        match self {
+            Self::Native(driver) => driver.push(op),
+            Self::Custom(backend) => {
+                backend.push(unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data()) })
+            }
+        }
+    }
+
+    pub fn push_batch(&mut self, ops: &mut [Key<dyn OpCode>]) -> Vec<Poll<io::Result<usize>>> {
+        match self {
+            Self::Native(driver) => driver.push_batch(ops),
+            Self::Custom(_) => ops.iter_mut().map(|op| self.push(op)).collect(),
+        }
+    }
+
+    pub fn push_link(&mut self, ops: &[LinkedOp]) -> io::Result<bool> {
+        match self {
+            Self::Native(driver) => driver.push_link(ops),
+            // Linked operations are emulated by the proactor.
+            Self::Custom(_) => Ok(false),
+        }
+    }
+
+    pub unsafe fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Self::Native(driver) => driver.poll(timeout),
+            Self::Custom(backend) => backend.poll(timeout),
+        }
+    }
+
+    pub fn handle(&self) -> NotifyHandle {
+        match self {
+            Self::Native(driver) => driver.handle().into(),
+            Self::Custom(backend) => backend.handle(),
+        }
+    }
+
+    pub fn next_timeout(&self) -> Option<Duration> {
+        match self {
+            Self::Native(driver) => driver.next_timeout(),
+            Self::Custom(backend) => backend.next_timeout(),
+        }
+    }
+
+    pub fn create_buffer_pool(
+        &mut self,
+        buffer_len: u16,
+        buffer_size: usize,
+    ) -> io::Result<BufferPool> {
+        match self {
+            Self::Native(driver) => driver.create_buffer_pool(buffer_len, buffer_size),
+            Self::Custom(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "buffer pools are not supported by custom backends",
+            )),
+        }
+    }
+
+    pub unsafe fn release_buffer_pool(&mut self, buffer_pool: BufferPool) -> io::Result<()> {
+        match self {
+            Self::Native(driver) => driver.release_buffer_pool(buffer_pool),
+            Self::Custom(_) => Ok(()),
+        }
+    }
+}
+
+impl AsRawFd for AnyDriver {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::Native(driver) => driver.as_raw_fd(),
+            Self::Custom(backend) => backend.as_raw_fd(),
+        }
+    }
+}
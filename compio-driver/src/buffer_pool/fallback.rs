@@ -12,18 +12,23 @@ use std::{
     mem::ManuallyDrop,
     ops::{Deref, DerefMut},
     rc::Rc,
+    task::{Context, Poll},
 };
 
 use compio_buf::{IntoInner, IoBuf, IoBufMut, SetBufInit, Slice};
 
+use crate::buffer_pool::ReturnSignal;
+
 struct BufferPoolInner {
     buffers: RefCell<VecDeque<Vec<u8>>>,
+    returned: ReturnSignal,
 }
 
 impl BufferPoolInner {
     pub(crate) fn add_buffer(&self, mut buffer: Vec<u8>) {
         buffer.clear();
-        self.buffers.borrow_mut().push_back(buffer)
+        self.buffers.borrow_mut().push_back(buffer);
+        self.returned.notify();
     }
 }
 
@@ -51,6 +56,7 @@ impl BufferPool {
         Self {
             inner: Rc::new(BufferPoolInner {
                 buffers: RefCell::new(buffers),
+                returned: ReturnSignal::default(),
             }),
         }
     }
@@ -63,7 +69,7 @@ impl BufferPool {
             .buffers
             .borrow_mut()
             .pop_front()
-            .ok_or_else(|| io::Error::other("buffer ring has no available buffer"))?;
+            .ok_or_else(no_buffer_error)?;
         let len = if len == 0 {
             buffer.capacity()
         } else {
@@ -77,6 +83,19 @@ impl BufferPool {
         self.inner.add_buffer(buffer);
     }
 
+    /// The count of the buffers returned to the pool. It only increases.
+    pub fn returned(&self) -> u64 {
+        self.inner.returned.count()
+    }
+
+    /// Wait until a buffer is returned to the pool, after [`returned`] was
+    /// `last`.
+    ///
+    /// [`returned`]: Self::returned
+    pub fn poll_returned(&self, cx: &mut Context<'_>, last: u64) -> Poll<()> {
+        self.inner.returned.poll_changed(cx, last)
+    }
+
     /// ## Safety
     /// * `len` should be valid.
     #[doc(hidden)]
@@ -88,6 +107,18 @@ impl BufferPool {
     }
 }
 
+/// The error when the pool runs out of buffers, the same as io-uring.
+fn no_buffer_error() -> io::Error {
+    #[cfg(unix)]
+    {
+        io::Error::from_raw_os_error(libc::ENOBUFS)
+    }
+    #[cfg(windows)]
+    {
+        io::Error::from_raw_os_error(windows_sys::Win32::Networking::WinSock::WSAENOBUFS)
+    }
+}
+
 #[doc(hidden)]
 pub struct OwnedBuffer {
     buffer: ManuallyDrop<Slice<Vec<u8>>>,
@@ -2,6 +2,7 @@ use std::{
     borrow::{Borrow, BorrowMut},
     fmt::{Debug, Formatter},
     ops::{Deref, DerefMut},
+    task::{Context, Poll},
 };
 
 #[path = "fallback.rs"]
@@ -35,6 +36,10 @@ impl BufferPool {
         }
     }
 
+    pub(crate) fn is_io_uring(&self) -> bool {
+        matches!(self.inner, BufferPollInner::IoUring(_))
+    }
+
     pub(crate) fn as_io_uring(&self) -> &iour::BufferPool {
         match &self.inner {
             BufferPollInner::IoUring(inner) => inner,
@@ -55,6 +60,25 @@ impl BufferPool {
         }
     }
 
+    /// The count of the buffers returned to the pool. It only increases.
+    pub fn returned(&self) -> u64 {
+        match &self.inner {
+            BufferPollInner::IoUring(inner) => inner.returned(),
+            BufferPollInner::Poll(inner) => inner.returned(),
+        }
+    }
+
+    /// Wait until a buffer is returned to the pool, after [`returned`] was
+    /// `last`.
+    ///
+    /// [`returned`]: Self::returned
+    pub fn poll_returned(&self, cx: &mut Context<'_>, last: u64) -> Poll<()> {
+        match &self.inner {
+            BufferPollInner::IoUring(inner) => inner.poll_returned(cx, last),
+            BufferPollInner::Poll(inner) => inner.poll_returned(cx, last),
+        }
+    }
+
     pub(crate) fn into_io_uring(self) -> iour::BufferPool {
         match self.inner {
             BufferPollInner::IoUring(inner) => inner,
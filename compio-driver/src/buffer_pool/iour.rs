@@ -7,17 +7,25 @@ use std::{
     fmt::{Debug, Formatter},
     io,
     ops::{Deref, DerefMut},
+    sync::Arc,
+    task::{Context, Poll},
 };
 
+use crossbeam_queue::SegQueue;
 use io_uring::cqueue::buffer_select;
 use io_uring_buf_ring::IoUringBufRing;
 
+use crate::buffer_pool::ReturnSignal;
+
 /// Buffer pool
 ///
 /// A buffer pool to allow user no need to specify a specific buffer to do the
 /// IO operation
 pub struct BufferPool {
     buf_ring: IoUringBufRing<Vec<u8>>,
+    returned: ReturnSignal,
+    // The ids of the buffers selected but never taken, to be returned to the ring.
+    recycled: Arc<SegQueue<u16>>,
 }
 
 impl Debug for BufferPool {
@@ -28,7 +36,25 @@ impl Debug for BufferPool {
 
 impl BufferPool {
     pub(crate) fn new(buf_ring: IoUringBufRing<Vec<u8>>) -> Self {
-        Self { buf_ring }
+        Self {
+            buf_ring,
+            returned: ReturnSignal::default(),
+            recycled: Arc::new(SegQueue::new()),
+        }
+    }
+
+    /// The queue to return the buffers selected but not taken, see
+    /// [`SelectedBuffer`](crate::op::SelectedBuffer).
+    pub(crate) fn recycled(&self) -> Arc<SegQueue<u16>> {
+        self.recycled.clone()
+    }
+
+    fn recycle(&self) {
+        while let Some(buffer_id) = self.recycled.pop() {
+            // Safety: the buffer is selected, but no data is taken from it.
+            unsafe { self.buf_ring.get_buf(buffer_id, 0) };
+            self.returned.notify();
+        }
     }
 
     pub(crate) fn buffer_group(&self) -> u16 {
@@ -55,7 +81,10 @@ impl BufferPool {
 
         self.buf_ring
             .get_buf(buffer_id, available_len)
-            .map(BorrowedBuffer)
+            .map(|buffer| BorrowedBuffer {
+                buffer,
+                pool: self,
+            })
             .ok_or_else(|| io::Error::other(format!("cannot find buffer {buffer_id}")))
     }
 
@@ -65,15 +94,41 @@ impl BufferPool {
             // Safety: 0 is always valid length. We just want to get the buffer once and
             // return it immediately.
             unsafe { self.buf_ring.get_buf(buffer_id, 0) };
+            self.returned.notify();
         }
     }
+
+    /// The count of the buffers returned to the pool. It only increases.
+    pub fn returned(&self) -> u64 {
+        self.recycle();
+        self.returned.count()
+    }
+
+    /// Wait until a buffer is returned to the pool, after [`returned`] was
+    /// `last`.
+    ///
+    /// [`returned`]: Self::returned
+    pub fn poll_returned(&self, cx: &mut Context<'_>, last: u64) -> Poll<()> {
+        self.recycle();
+        self.returned.poll_changed(cx, last)
+    }
 }
 
 /// Buffer borrowed from buffer pool
 ///
 /// When IO operation finish, user will obtain a `BorrowedBuffer` to access the
 /// filled data
-pub struct BorrowedBuffer<'a>(io_uring_buf_ring::BorrowedBuffer<'a, Vec<u8>>);
+pub struct BorrowedBuffer<'a> {
+    buffer: io_uring_buf_ring::BorrowedBuffer<'a, Vec<u8>>,
+    pool: &'a BufferPool,
+}
+
+impl Drop for BorrowedBuffer<'_> {
+    fn drop(&mut self) {
+        // The buffer is returned right after, before the waiter is polled.
+        self.pool.returned.notify();
+    }
+}
 
 impl Debug for BorrowedBuffer<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -85,13 +140,13 @@ impl Deref for BorrowedBuffer<'_> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        self.0.deref()
+        self.buffer.deref()
     }
 }
 
 impl DerefMut for BorrowedBuffer<'_> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.0.deref_mut()
+        self.buffer.deref_mut()
     }
 }
 
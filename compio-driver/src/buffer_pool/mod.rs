@@ -15,6 +15,35 @@ cfg_if::cfg_if! {
     }
 }
 
+/// Notifies the waiters when a buffer is returned to the pool.
+#[derive(Default)]
+pub(crate) struct ReturnSignal {
+    count: std::cell::Cell<u64>,
+    waker: std::cell::Cell<Option<std::task::Waker>>,
+}
+
+impl ReturnSignal {
+    pub fn count(&self) -> u64 {
+        self.count.get()
+    }
+
+    pub fn notify(&self) {
+        self.count.set(self.count.get().wrapping_add(1));
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn poll_changed(&self, cx: &mut std::task::Context<'_>, last: u64) -> std::task::Poll<()> {
+        if self.count.get() != last {
+            std::task::Poll::Ready(())
+        } else {
+            self.waker.set(Some(cx.waker().clone()));
+            std::task::Poll::Pending
+        }
+    }
+}
+
 /// Trait to get the selected buffer of an io operation.
 pub trait TakeBuffer {
     /// Selected buffer type. It keeps the reference to the buffer pool and
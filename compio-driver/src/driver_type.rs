@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU8, Ordering},
+};
 
 const UNINIT: u8 = u8::MAX;
 const IO_URING: u8 = 0;
@@ -7,6 +10,10 @@ const IOCP: u8 = 2;
 
 static DRIVER_TYPE: AtomicU8 = AtomicU8::new(UNINIT);
 
+thread_local! {
+    static SCOPED_DRIVER_TYPE: Cell<Option<DriverType>> = const { Cell::new(None) };
+}
+
 /// Representing underlying driver type the fusion driver is using
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -39,7 +46,8 @@ impl DriverType {
             } else if #[cfg(fusion)] {
                 use io_uring::opcode::*;
 
-                // Add more opcodes here if used
+                // Add more opcodes here if used. The ones with a fallback, e.g.,
+                // `Socket` and `Shutdown`, are not required.
                 const USED_OP: &[u8] = &[
                     Read::CODE,
                     Readv::CODE,
@@ -53,8 +61,7 @@ impl DriverType {
                     AsyncCancel::CODE,
                     OpenAt::CODE,
                     Close::CODE,
-                    Shutdown::CODE,
-                    Socket::CODE,
+                    Timeout::CODE,
                 ];
 
                 (|| {
@@ -78,9 +85,16 @@ impl DriverType {
         }
     }
 
-    /// Get the underlying driver type and cache it. Following calls will return
-    /// the cached value.
+    /// Get the driver type of the current thread, which is set by
+    /// [`DriverType::scope`], e.g., when a runtime is entered. Otherwise, it is
+    /// the default driver type of the process.
     pub fn current() -> DriverType {
+        SCOPED_DRIVER_TYPE.get().unwrap_or_else(Self::default_type)
+    }
+
+    /// Get the default driver type of the process and cache it. Following
+    /// calls will return the cached value.
+    pub(crate) fn default_type() -> DriverType {
         match DRIVER_TYPE.load(Ordering::Acquire) {
             UNINIT => {}
             x => return DriverType::from_num(x),
@@ -92,6 +106,41 @@ impl DriverType {
         dev_ty
     }
 
+    /// Set the driver type of the current thread when calling `f`, see
+    /// [`Proactor::driver_type`].
+    ///
+    /// [`Proactor::driver_type`]: crate::Proactor::driver_type
+    pub fn scope<T>(self, f: impl FnOnce() -> T) -> T {
+        struct Guard(Option<DriverType>);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                SCOPED_DRIVER_TYPE.set(self.0);
+            }
+        }
+
+        let _guard = Guard(SCOPED_DRIVER_TYPE.replace(Some(self)));
+        f()
+    }
+
+    /// Whether the driver type is compiled in and could be selected by
+    /// [`ProactorBuilder::driver`].
+    ///
+    /// [`ProactorBuilder::driver`]: crate::ProactorBuilder::driver
+    pub fn is_available(self) -> bool {
+        cfg_if::cfg_if! {
+            if #[cfg(windows)] {
+                self == DriverType::IOCP
+            } else if #[cfg(fusion)] {
+                self != DriverType::IOCP
+            } else if #[cfg(io_uring)] {
+                self == DriverType::IoUring
+            } else {
+                self == DriverType::Poll
+            }
+        }
+    }
+
     /// Check if the current driver is `polling`
     pub fn is_polling() -> bool {
         Self::current() == DriverType::Poll
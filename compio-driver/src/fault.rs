@@ -0,0 +1,348 @@
+//! Fault injection of the operations, for testing the error paths of the code
+//! on top of the proactor. The rules are set by [`ProactorBuilder::faults`].
+//!
+//! [`ProactorBuilder::faults`]: crate::ProactorBuilder::faults
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    task::Waker,
+    time::{Duration, Instant},
+};
+
+use crate::{Key, OpCode};
+
+/// A fault injected into an operation, see [`FaultRule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+    /// Fail the operation with the raw OS error code, e.g., `ECONNRESET`,
+    /// without submitting it.
+    Error(i32),
+    /// Fail the operation with a spurious error of the kind, usually
+    /// [`io::ErrorKind::Interrupted`] or [`io::ErrorKind::WouldBlock`],
+    /// without submitting it.
+    Spurious(io::ErrorKind),
+    /// Read or write at most the bytes, from the first non-empty buffer.
+    ///
+    /// ## Platform specific
+    /// * Unix: the operations described by [`OpView::Read`] and
+    ///   [`OpView::Write`] are performed immediately with the shortened buffer.
+    ///   If the fd is not ready, the operation is submitted as usual.
+    /// * Windows & custom backends: the rule never matches.
+    ///
+    /// [`OpView::Read`]: crate::op::OpView::Read
+    /// [`OpView::Write`]: crate::op::OpView::Write
+    Short(usize),
+    /// Hold the completed operation for the duration before its result could
+    /// be popped.
+    Delay(Duration),
+}
+
+/// A rule to inject a [`Fault`] into the operations of a kind.
+#[derive(Debug, Clone)]
+pub struct FaultRule {
+    op: Cow<'static, str>,
+    fault: Fault,
+    skip: usize,
+    times: Option<usize>,
+}
+
+impl FaultRule {
+    /// Create a rule for the operations named `op`, which is the type name
+    /// without the path and generic parameters, e.g., `Recv` or `WriteAt`.
+    /// The name `*` matches all operations.
+    pub fn new(op: impl Into<Cow<'static, str>>, fault: Fault) -> Self {
+        Self {
+            op: op.into(),
+            fault,
+            skip: 0,
+            times: None,
+        }
+    }
+
+    /// Let the first `n` matching operations pass. The default value is 0.
+    pub fn skip(mut self, n: usize) -> Self {
+        self.skip = n;
+        self
+    }
+
+    /// Inject the fault at most `n` times. By default, it is injected into all
+    /// matching operations.
+    pub fn times(mut self, n: usize) -> Self {
+        self.times = Some(n);
+        self
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.op == "*" || self.op == name
+    }
+}
+
+/// The rules of fault injection of a proactor. The rules are checked in order,
+/// and the first matching one applies. An operation let pass by
+/// [`FaultRule::skip`] or [`FaultRule::times`] is checked by the next rules.
+#[derive(Debug, Clone, Default)]
+pub struct FaultRules {
+    rules: Vec<FaultRule>,
+}
+
+impl FaultRules {
+    /// Create empty rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule.
+    pub fn add(&mut self, rule: FaultRule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Whether there is no rule.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// The type name without the path and generic parameters.
+fn op_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split_once('<').map_or(name, |(name, _)| name);
+    name.rsplit_once("::").map_or(name, |(_, name)| name)
+}
+
+struct Delayed {
+    delay: Duration,
+    deadline: Option<Instant>,
+    waker: Option<Waker>,
+}
+
+/// The state of the fault injection of a proactor.
+pub(crate) struct Faults {
+    // The rules and the times they matched.
+    rules: Vec<(FaultRule, usize)>,
+    delayed: HashMap<usize, Delayed>,
+}
+
+impl Faults {
+    pub fn new(rules: &FaultRules) -> Self {
+        Self {
+            rules: rules.rules.iter().map(|rule| (rule.clone(), 0)).collect(),
+            delayed: HashMap::new(),
+        }
+    }
+
+    fn take<T: ?Sized>(&mut self, mut f: impl FnMut(&Fault) -> bool) -> Option<Fault> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let name = op_name::<T>();
+        for (rule, count) in &mut self.rules {
+            if !rule.matches(name) || !f(&rule.fault) {
+                continue;
+            }
+            let n = *count;
+            *count += 1;
+            if n < rule.skip || rule.times.is_some_and(|times| n - rule.skip >= times) {
+                continue;
+            }
+            return Some(rule.fault.clone());
+        }
+        None
+    }
+
+    /// Inject the faults before the operation is submitted. Returns the result
+    /// if the operation should not be submitted.
+    pub fn before_push<T: OpCode + ?Sized>(
+        &mut self,
+        op: &mut Key<T>,
+        native: bool,
+    ) -> Option<io::Result<usize>> {
+        #[cfg(not(unix))]
+        let _ = (&op, native);
+        let fault = self.take::<T>(|fault| match fault {
+            Fault::Error(_) | Fault::Spurious(_) => true,
+            #[cfg(unix)]
+            Fault::Short(limit) => native && short::applies(op, *limit),
+            _ => false,
+        })?;
+        match fault {
+            Fault::Error(code) => Some(Err(io::Error::from_raw_os_error(code))),
+            Fault::Spurious(kind) => Some(Err(io::Error::from(kind))),
+            #[cfg(unix)]
+            Fault::Short(limit) => short::perform(op, limit),
+            _ => None,
+        }
+    }
+
+    /// Register the delay of the pushed operation, if any.
+    pub fn after_push<T: ?Sized>(&mut self, user_data: usize) -> bool {
+        let Some(Fault::Delay(delay)) = self.take::<T>(|fault| matches!(fault, Fault::Delay(_)))
+        else {
+            return false;
+        };
+        self.delayed.insert(
+            user_data,
+            Delayed {
+                delay,
+                deadline: None,
+                waker: None,
+            },
+        );
+        true
+    }
+
+    /// Whether the completed operation should still be held. The delay starts
+    /// when the result is popped for the first time.
+    pub fn hold(&mut self, user_data: usize) -> bool {
+        let Some(delayed) = self.delayed.get_mut(&user_data) else {
+            return false;
+        };
+        let now = Instant::now();
+        let deadline = *delayed.deadline.get_or_insert(now + delayed.delay);
+        if now < deadline {
+            true
+        } else {
+            self.delayed.remove(&user_data);
+            false
+        }
+    }
+
+    pub fn set_waker(&mut self, user_data: usize, waker: &Waker) {
+        if let Some(delayed) = self.delayed.get_mut(&user_data) {
+            delayed.waker = Some(waker.clone());
+        }
+    }
+
+    pub fn remove(&mut self, user_data: usize) {
+        self.delayed.remove(&user_data);
+    }
+
+    /// The duration until the first held operation is released.
+    pub fn next_timeout(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.delayed
+            .values()
+            .filter_map(|delayed| delayed.deadline)
+            .min()
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Wake the tasks of the released operations. Returns `true` if any.
+    pub fn wake(&mut self) -> bool {
+        let now = Instant::now();
+        let mut woken = false;
+        for delayed in self.delayed.values_mut() {
+            if delayed.deadline.is_some_and(|deadline| deadline <= now) {
+                if let Some(waker) = delayed.waker.take() {
+                    waker.wake();
+                    woken = true;
+                }
+            }
+        }
+        woken
+    }
+}
+
+#[cfg(unix)]
+mod short {
+    use std::{io, mem::MaybeUninit};
+
+    use crate::{Key, OpCode, RawFd, op::OpView, syscall};
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const NOSIGNAL: i32 = libc::MSG_NOSIGNAL;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const NOSIGNAL: i32 = 0;
+
+    /// Whether the operation reads or writes more than `limit` bytes.
+    pub fn applies<T: OpCode + ?Sized>(op: &mut Key<T>, limit: usize) -> bool {
+        let len: usize = match op.view() {
+            OpView::Read { bufs, .. } => bufs.iter().map(|buf| buf.len()).sum(),
+            OpView::Write { bufs, .. } => bufs.iter().map(|buf| buf.len()).sum(),
+            _ => return false,
+        };
+        len > limit
+    }
+
+    /// Perform the operation with at most `limit` bytes. Returns `None` if the
+    /// fd is not ready.
+    pub fn perform<T: OpCode + ?Sized>(op: &mut Key<T>, limit: usize) -> Option<io::Result<usize>> {
+        let res = match op.view() {
+            OpView::Read { fd, offset, bufs } => {
+                let Some(buf) = bufs.into_iter().find(|buf| !buf.is_empty()) else {
+                    return Some(Ok(0));
+                };
+                let len = buf.len().min(limit);
+                read(fd, offset, &mut buf[..len])
+            }
+            OpView::Write { fd, offset, bufs } => {
+                let Some(buf) = bufs.into_iter().find(|buf| !buf.is_empty()) else {
+                    return Some(Ok(0));
+                };
+                let len = buf.len().min(limit);
+                write(fd, offset, &buf[..len])
+            }
+            _ => return None,
+        };
+        match res {
+            Err(e)
+                if e.kind() == io::ErrorKind::WouldBlock
+                    || e.kind() == io::ErrorKind::Interrupted =>
+            {
+                None
+            }
+            res => Some(res),
+        }
+    }
+
+    fn read(fd: RawFd, offset: Option<u64>, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        let ptr = buf.as_mut_ptr().cast();
+        if let Some(offset) = offset {
+            return syscall!(libc::pread(fd, ptr, buf.len(), offset as _)).map(|n| n as _);
+        }
+        match syscall!(libc::recv(fd, ptr, buf.len(), libc::MSG_DONTWAIT)) {
+            Err(e) if e.raw_os_error() == Some(libc::ENOTSOCK) => {
+                ready(fd, libc::POLLIN)?;
+                syscall!(libc::read(fd, ptr, buf.len())).map(|n| n as _)
+            }
+            res => res.map(|n| n as _),
+        }
+    }
+
+    fn write(fd: RawFd, offset: Option<u64>, buf: &[u8]) -> io::Result<usize> {
+        let ptr = buf.as_ptr().cast();
+        if let Some(offset) = offset {
+            return syscall!(libc::pwrite(fd, ptr, buf.len(), offset as _)).map(|n| n as _);
+        }
+        match syscall!(libc::send(
+            fd,
+            ptr,
+            buf.len(),
+            libc::MSG_DONTWAIT | NOSIGNAL
+        )) {
+            Err(e) if e.raw_os_error() == Some(libc::ENOTSOCK) => {
+                ready(fd, libc::POLLOUT)?;
+                syscall!(libc::write(fd, ptr, buf.len())).map(|n| n as _)
+            }
+            res => res.map(|n| n as _),
+        }
+    }
+
+    /// Check the readiness of an fd which is not a socket, and return
+    /// [`io::ErrorKind::WouldBlock`] if not ready.
+    fn ready(fd: RawFd, events: i16) -> io::Result<()> {
+        let mut pollfd = libc::pollfd {
+            fd,
+            events,
+            revents: 0,
+        };
+        if syscall!(libc::poll(&mut pollfd, 1, 0))? == 0 {
+            Err(io::ErrorKind::WouldBlock.into())
+        } else {
+            Ok(())
+        }
+    }
+}
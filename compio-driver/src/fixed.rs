@@ -0,0 +1,177 @@
+#[cfg(io_uring)]
+use std::sync::Arc;
+
+#[cfg(io_uring)]
+use crossbeam_queue::SegQueue;
+
+#[cfg(unix)]
+use crate::{AsFd, AsRawFd, RawFd};
+use crate::{BorrowedFd, OwnedFd, SharedFd};
+
+/// A file descriptor in the fixed file table of the driver.
+///
+/// It is created by [`Proactor::register_fd`] from a normal fd, or by
+/// [`Proactor::direct_fd`] from the result of a direct operation, e.g.,
+/// [`OpenFile::new_direct`]. It could be passed to the operations in place of
+/// a normal fd, and the kernel doesn't need to look up the fd table and
+/// refcount the file on each submission. The slot is released on dropping.
+///
+/// ## Platform specific
+/// * io-uring: the table should be enabled by [`ProactorBuilder::fixed_files`].
+///   A direct descriptor doesn't have a normal fd, and it could only be used by
+///   the operations submitted to the io-uring driver.
+/// * IOCP & polling: it is a normal fd.
+///
+/// [`Proactor::register_fd`]: crate::Proactor::register_fd
+/// [`Proactor::direct_fd`]: crate::Proactor::direct_fd
+/// [`OpenFile::new_direct`]: crate::op::OpenFile::new_direct
+/// [`ProactorBuilder::fixed_files`]: crate::ProactorBuilder::fixed_files
+#[derive(Debug)]
+pub struct FixedFd {
+    inner: FixedInner,
+}
+
+#[derive(Debug)]
+enum FixedInner {
+    Fd(OwnedFd),
+    #[cfg(io_uring)]
+    Fixed {
+        index: u32,
+        fd: Option<OwnedFd>,
+        released: Arc<SegQueue<u32>>,
+    },
+}
+
+impl FixedFd {
+    pub(crate) fn from_fd(fd: OwnedFd) -> Self {
+        Self {
+            inner: FixedInner::Fd(fd),
+        }
+    }
+
+    #[cfg(io_uring)]
+    pub(crate) fn new_fixed(index: u32, fd: Option<OwnedFd>, released: Arc<SegQueue<u32>>) -> Self {
+        Self {
+            inner: FixedInner::Fixed {
+                index,
+                fd,
+                released,
+            },
+        }
+    }
+
+    /// The index in the fixed file table, if it is registered.
+    pub fn index(&self) -> Option<u32> {
+        match &self.inner {
+            FixedInner::Fd(_) => None,
+            #[cfg(io_uring)]
+            FixedInner::Fixed { index, .. } => Some(*index),
+        }
+    }
+
+    /// The normal fd, if exists.
+    pub fn fd(&self) -> Option<BorrowedFd<'_>> {
+        match &self.inner {
+            FixedInner::Fd(fd) => Some(fd.as_fd()),
+            #[cfg(io_uring)]
+            FixedInner::Fixed { fd, .. } => fd.as_ref().map(|fd| fd.as_fd()),
+        }
+    }
+}
+
+#[cfg(io_uring)]
+impl Drop for FixedFd {
+    fn drop(&mut self) {
+        if let FixedInner::Fixed {
+            index, released, ..
+        } = &self.inner
+        {
+            released.push(*index);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl crate::AsFd for FixedFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        let FixedInner::Fd(fd) = &self.inner;
+        fd.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl crate::AsRawFd for FixedFd {
+    fn as_raw_fd(&self) -> crate::RawFd {
+        let FixedInner::Fd(fd) = &self.inner;
+        fd.as_raw_fd()
+    }
+}
+
+/// The fd of an operation, see [`AsFdTarget`].
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FdTarget {
+    /// A normal fd.
+    Fd(RawFd),
+    /// An index in the fixed file table, and the normal fd if exists.
+    Fixed(u32, Option<RawFd>),
+}
+
+#[cfg(unix)]
+impl AsRawFd for FdTarget {
+    /// Get the normal fd. A direct descriptor returns -1, and the syscalls
+    /// fail with `EBADF`.
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::Fd(fd) => *fd,
+            Self::Fixed(_, fd) => fd.unwrap_or(-1),
+        }
+    }
+}
+
+/// Types that could be used as the fd of the operations: all the types
+/// implementing [`AsFd`], and [`FixedFd`].
+#[cfg(unix)]
+pub trait AsFdTarget {
+    /// Get the target fd.
+    fn as_fd_target(&self) -> FdTarget;
+}
+
+#[cfg(unix)]
+impl<T: AsFd> AsFdTarget for T {
+    fn as_fd_target(&self) -> FdTarget {
+        FdTarget::Fd(self.as_fd().as_raw_fd())
+    }
+}
+
+#[cfg(unix)]
+impl AsFdTarget for FixedFd {
+    fn as_fd_target(&self) -> FdTarget {
+        let fd = self.fd().map(|fd| fd.as_raw_fd());
+        match self.index() {
+            Some(index) => FdTarget::Fixed(index, fd),
+            None => FdTarget::Fd(fd.expect("a normal fd should exist")),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl AsFdTarget for &FixedFd {
+    fn as_fd_target(&self) -> FdTarget {
+        (**self).as_fd_target()
+    }
+}
+
+#[cfg(unix)]
+impl AsFdTarget for SharedFd<FixedFd> {
+    fn as_fd_target(&self) -> FdTarget {
+        (**self).as_fd_target()
+    }
+}
+
+impl From<FixedFd> for SharedFd<FixedFd> {
+    fn from(fd: FixedFd) -> Self {
+        // SAFETY: the fixed fd owns the slot.
+        unsafe { Self::new_unchecked(fd) }
+    }
+}
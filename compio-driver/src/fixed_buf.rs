@@ -0,0 +1,225 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    fmt::Debug,
+    io,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    sync::Arc,
+};
+
+use compio_buf::{IoBuf, IoBufMut, SetBufInit, Slice};
+use crossbeam_queue::SegQueue;
+
+/// The alignment of the arena, suitable for `O_DIRECT`.
+const ARENA_ALIGN: usize = 4096;
+
+struct Arena {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    buf_size: usize,
+    buf_count: u16,
+    free: SegQueue<u16>,
+}
+
+// SAFETY: the arena is only a piece of memory, and each buffer is owned by at
+// most one `FixedBuf`.
+unsafe impl Send for Arena {}
+unsafe impl Sync for Arena {}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+/// The registered buffer arena of the driver, created by
+/// [`ProactorBuilder::registered_buffers`].
+///
+/// It is a contiguous piece of memory, divided into buffers of the same size.
+/// The buffers are taken as [`FixedBuf`], and returned back on dropping. The
+/// memory is freed after the arena and all the buffers are dropped.
+///
+/// ## Platform specific
+/// * io-uring: the buffers are registered with `IORING_REGISTER_BUFFERS`, and
+///   the kernel doesn't need to pin the pages on each operation.
+/// * IOCP & polling: the buffers are plain memory.
+///
+/// [`ProactorBuilder::registered_buffers`]: crate::ProactorBuilder::registered_buffers
+#[derive(Clone)]
+pub struct RegisteredBuffers {
+    arena: Arc<Arena>,
+}
+
+impl RegisteredBuffers {
+    pub(crate) fn new(buf_count: u16, buf_size: usize) -> io::Result<Self> {
+        let size = (buf_count as usize)
+            .checked_mul(buf_size)
+            .filter(|size| *size > 0)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "invalid registered buffers")
+            })?;
+        let layout = Layout::from_size_align(size, ARENA_ALIGN)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let ptr = NonNull::new(unsafe { alloc(layout) })
+            .ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))?;
+        let free = SegQueue::new();
+        (0..buf_count).for_each(|index| free.push(index));
+        Ok(Self {
+            arena: Arc::new(Arena {
+                ptr,
+                layout,
+                buf_size,
+                buf_count,
+                free,
+            }),
+        })
+    }
+
+    /// The number of the buffers.
+    pub fn buf_count(&self) -> u16 {
+        self.arena.buf_count
+    }
+
+    /// The capacity of each buffer.
+    pub fn buf_size(&self) -> usize {
+        self.arena.buf_size
+    }
+
+    /// Take a free buffer from the arena. Returns `None` if all the buffers
+    /// are in use.
+    pub fn take(&self) -> Option<FixedBuf> {
+        self.arena.free.pop().map(|index| FixedBuf {
+            arena: self.arena.clone(),
+            index,
+            len: 0,
+        })
+    }
+
+    /// The `iovec`s to register.
+    #[cfg(io_uring)]
+    pub(crate) fn iovecs(&self) -> Vec<libc::iovec> {
+        (0..self.arena.buf_count)
+            .map(|index| libc::iovec {
+                iov_base: self.arena.buf_ptr(index).cast(),
+                iov_len: self.arena.buf_size,
+            })
+            .collect()
+    }
+}
+
+impl Arena {
+    fn buf_ptr(&self, index: u16) -> *mut u8 {
+        // SAFETY: the index is less than `buf_count`.
+        unsafe { self.ptr.as_ptr().add(index as usize * self.buf_size) }
+    }
+}
+
+impl Debug for RegisteredBuffers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredBuffers")
+            .field("buf_count", &self.arena.buf_count)
+            .field("buf_size", &self.arena.buf_size)
+            .finish()
+    }
+}
+
+/// A buffer in the [`RegisteredBuffers`]. It could be used by the normal
+/// operations as a normal buffer, and by [`ReadFixedAt`], [`WriteFixedAt`]
+/// and [`SendFixed`] as a registered one.
+///
+/// [`ReadFixedAt`]: crate::op::ReadFixedAt
+/// [`WriteFixedAt`]: crate::op::WriteFixedAt
+/// [`SendFixed`]: crate::op::SendFixed
+pub struct FixedBuf {
+    arena: Arc<Arena>,
+    index: u16,
+    len: usize,
+}
+
+impl FixedBuf {
+    /// The index of the buffer in the arena.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// Clear the buffer, so that it could be read into again.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Drop for FixedBuf {
+    fn drop(&mut self) {
+        self.arena.free.push(self.index);
+    }
+}
+
+impl Debug for FixedBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FixedBuf")
+            .field("index", &self.index)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Deref for FixedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for FixedBuf {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the first `len` bytes are initialized.
+        unsafe { std::slice::from_raw_parts_mut(self.as_buf_mut_ptr(), self.len) }
+    }
+}
+
+unsafe impl IoBuf for FixedBuf {
+    fn as_buf_ptr(&self) -> *const u8 {
+        self.arena.buf_ptr(self.index)
+    }
+
+    fn buf_len(&self) -> usize {
+        self.len
+    }
+
+    fn buf_capacity(&self) -> usize {
+        self.arena.buf_size
+    }
+}
+
+unsafe impl IoBufMut for FixedBuf {
+    fn as_buf_mut_ptr(&mut self) -> *mut u8 {
+        self.arena.buf_ptr(self.index)
+    }
+}
+
+impl SetBufInit for FixedBuf {
+    unsafe fn set_buf_init(&mut self, len: usize) {
+        debug_assert!(len <= self.arena.buf_size);
+        self.len = self.len.max(len);
+    }
+}
+
+/// Buffers in the [`RegisteredBuffers`], which could be used by the fixed
+/// buffer operations.
+pub trait IoFixedBuf: IoBuf {
+    /// The index of the registered buffer.
+    fn buf_index(&self) -> u16;
+}
+
+impl IoFixedBuf for FixedBuf {
+    fn buf_index(&self) -> u16 {
+        self.index
+    }
+}
+
+impl<T: IoFixedBuf> IoFixedBuf for Slice<T> {
+    fn buf_index(&self) -> u16 {
+        self.as_inner().buf_index()
+    }
+}
@@ -12,10 +12,16 @@ use std::{io, task::Poll, time::Duration};
 
 pub use iour::{OpCode as IourOpCode, OpEntry};
 pub(crate) use iour::{sockaddr_storage, socklen_t};
+#[cfg(linux_aio)]
+pub use poll::Iocb;
 pub use poll::{Decision, OpCode as PollOpCode, OpType};
 
+#[cfg(feature = "metrics")]
+use crate::DriverMetrics;
 pub use crate::driver_type::DriverType; // Re-export so current user won't be broken
-use crate::{BufferPool, Key, ProactorBuilder};
+use crate::{
+    BufferPool, FixedFd, Key, ProactorBuilder, RegisteredBuffers, RingMessage, SetupReport,
+};
 
 /// Fused [`OpCode`]
 ///
@@ -38,7 +44,7 @@ pub(crate) struct Driver {
 impl Driver {
     /// Create a new fusion driver with given number of entries
     pub fn new(builder: &ProactorBuilder) -> io::Result<Self> {
-        match DriverType::current() {
+        match builder.driver_type() {
             DriverType::Poll => Ok(Self {
                 fuse: FuseDriver::Poll(poll::Driver::new(builder)?),
             }),
@@ -63,6 +69,56 @@ impl Driver {
         }
     }
 
+    pub fn register_buffers(&mut self, buffers: &RegisteredBuffers) -> io::Result<()> {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.register_buffers(buffers),
+            FuseDriver::IoUring(driver) => driver.register_buffers(buffers),
+        }
+    }
+
+    pub fn supports(&self, opcode: u8) -> bool {
+        match &self.fuse {
+            FuseDriver::Poll(driver) => driver.supports(opcode),
+            FuseDriver::IoUring(driver) => driver.supports(opcode),
+        }
+    }
+
+    pub fn setup_report(&self) -> SetupReport {
+        match &self.fuse {
+            FuseDriver::Poll(driver) => driver.setup_report(),
+            FuseDriver::IoUring(driver) => driver.setup_report(),
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> DriverMetrics {
+        match &self.fuse {
+            FuseDriver::Poll(driver) => driver.metrics(),
+            FuseDriver::IoUring(driver) => driver.metrics(),
+        }
+    }
+
+    pub fn pop_message(&mut self) -> Option<RingMessage> {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.pop_message(),
+            FuseDriver::IoUring(driver) => driver.pop_message(),
+        }
+    }
+
+    pub fn register_fd(&mut self, fd: OwnedFd) -> io::Result<FixedFd> {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.register_fd(fd),
+            FuseDriver::IoUring(driver) => driver.register_fd(fd),
+        }
+    }
+
+    pub fn direct_fd(&mut self, res: usize) -> FixedFd {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.direct_fd(res),
+            FuseDriver::IoUring(driver) => driver.direct_fd(res),
+        }
+    }
+
     pub fn cancel(&mut self, op: &mut Key<dyn OpCode>) {
         match &mut self.fuse {
             FuseDriver::Poll(driver) => driver.cancel(op),
@@ -77,6 +133,27 @@ impl Driver {
         }
     }
 
+    pub fn cancel_fd(&mut self, fd: RawFd) {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.cancel_fd(fd),
+            FuseDriver::IoUring(driver) => driver.cancel_fd(fd),
+        }
+    }
+
+    pub fn push_batch(&mut self, ops: &mut [Key<dyn OpCode>]) -> Vec<Poll<io::Result<usize>>> {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.push_batch(ops),
+            FuseDriver::IoUring(driver) => driver.push_batch(ops),
+        }
+    }
+
+    pub fn push_link(&mut self, ops: &[crate::LinkedOp]) -> io::Result<bool> {
+        match &mut self.fuse {
+            FuseDriver::Poll(driver) => driver.push_link(ops),
+            FuseDriver::IoUring(driver) => driver.push_link(ops),
+        }
+    }
+
     pub unsafe fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()> {
         match &mut self.fuse {
             FuseDriver::Poll(driver) => driver.poll(timeout),
@@ -92,6 +169,13 @@ impl Driver {
         NotifyHandle::from_fuse(fuse)
     }
 
+    pub fn next_timeout(&self) -> Option<Duration> {
+        match &self.fuse {
+            FuseDriver::Poll(driver) => driver.next_timeout(),
+            FuseDriver::IoUring(driver) => driver.next_timeout(),
+        }
+    }
+
     pub fn create_buffer_pool(
         &mut self,
         buffer_len: u16,
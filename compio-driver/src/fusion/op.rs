@@ -5,31 +5,50 @@ use socket2::SockAddr;
 
 use super::*;
 pub use crate::unix::op::*;
+use crate::{
+    AsFdTarget,
+    op::{Multishot, SelectedBuffer},
+};
 
 macro_rules! op {
     (<$($ty:ident: $trait:ident),* $(,)?> $name:ident( $($arg:ident: $arg_t:ty),* $(,)? )) => {
         ::paste::paste!{
             enum [< $name Inner >] <$($ty: $trait),*> {
+                // The arguments, before the op is pushed to a driver.
+                Created(Option<($($arg_t,)*)>),
                 Poll(poll::$name<$($ty),*>),
                 IoUring(iour::$name<$($ty),*>),
             }
 
             impl<$($ty: $trait),*> [< $name Inner >]<$($ty),*> {
                 fn poll(&mut self) -> &mut poll::$name<$($ty),*> {
-                    debug_assert!(DriverType::current() == DriverType::Poll);
-
+                    if let Self::Created(args) = self {
+                        let ($($arg,)*) = args.take().expect("the op should be created once");
+                        *self = Self::Poll(poll::$name::new($($arg),*));
+                    }
                     match self {
                         Self::Poll(ref mut op) => op,
-                        Self::IoUring(_) => unreachable!("Current driver is not `io-uring`"),
+                        _ => unreachable!("The op is pushed to `io-uring`"),
                     }
                 }
 
                 fn iour(&mut self) -> &mut iour::$name<$($ty),*> {
-                    debug_assert!(DriverType::current() == DriverType::IoUring);
-
+                    if let Self::Created(args) = self {
+                        let ($($arg,)*) = args.take().expect("the op should be created once");
+                        *self = Self::IoUring(iour::$name::new($($arg),*));
+                    }
                     match self {
                         Self::IoUring(ref mut op) => op,
-                        Self::Poll(_) => unreachable!("Current driver is not `polling`"),
+                        _ => unreachable!("The op is pushed to `polling`"),
+                    }
+                }
+
+                fn view(&mut self) -> crate::op::OpView<'_> {
+                    // SAFETY: the op is pinned with the outer one.
+                    match self {
+                        Self::IoUring(op) => iour::OpCode::view(unsafe { std::pin::Pin::new_unchecked(op) }),
+                        // The custom backends use the ops of `polling`.
+                        _ => poll::OpCode::view(unsafe { std::pin::Pin::new_unchecked(self.poll()) }),
                     }
                 }
             }
@@ -42,8 +61,12 @@ macro_rules! op {
             impl<$($ty: $trait),*> IntoInner for $name <$($ty),*> {
                 type Inner = <poll::$name<$($ty),*> as IntoInner>::Inner;
 
-                fn into_inner(self) -> Self::Inner {
+                fn into_inner(mut self) -> Self::Inner {
                     match self.inner {
+                        [< $name Inner >]::Created(_) => {
+                            let _ = self.inner.poll();
+                            self.into_inner()
+                        }
                         [< $name Inner >]::Poll(op) => op.into_inner(),
                         [< $name Inner >]::IoUring(op) => op.into_inner(),
                     }
@@ -51,16 +74,10 @@ macro_rules! op {
             }
 
             impl<$($ty: $trait),*> $name <$($ty),*> {
-                #[doc = concat!("Create a new `", stringify!($name), "`.")]
+                #[doc = concat!("Create a new `", stringify!($name), "`. It is created for the driver it is pushed to.")]
                 pub fn new($($arg: $arg_t),*) -> Self {
-                    match DriverType::current() {
-                        DriverType::Poll => Self {
-                            inner: [< $name Inner >]::Poll(poll::$name::new($($arg),*)),
-                        },
-                        DriverType::IoUring => Self {
-                            inner: [< $name Inner >]::IoUring(iour::$name::new($($arg),*)),
-                        },
-                        _ => unreachable!("Fuse driver will only be enabled on linux"),
+                    Self {
+                        inner: [< $name Inner >]::Created(Some(($($arg,)*))),
                     }
                 }
             }
@@ -80,6 +97,10 @@ macro_rules! op {
             ) -> std::task::Poll<std::io::Result<usize>> {
                 unsafe { self.map_unchecked_mut(|x| x.inner.poll() ) }.operate()
             }
+
+            fn view(self: std::pin::Pin<&mut Self>) -> crate::op::OpView<'_> {
+                unsafe { self.get_unchecked_mut() }.inner.view()
+            }
         }
 
         impl<$($ty: $trait),*> iour::OpCode for $name<$($ty),*> {
@@ -95,16 +116,16 @@ mod iour { pub use crate::sys::iour::{op::*, OpCode}; }
 #[rustfmt::skip]
 mod poll { pub use crate::sys::poll::{op::*, OpCode}; }
 
-op!(<T: IoBufMut, S: AsFd> RecvFrom(fd: S, buffer: T));
-op!(<T: IoBuf, S: AsFd> SendTo(fd: S, buffer: T, addr: SockAddr));
-op!(<T: IoVectoredBufMut, S: AsFd> RecvFromVectored(fd: S, buffer: T));
-op!(<T: IoVectoredBuf, S: AsFd> SendToVectored(fd: S, buffer: T, addr: SockAddr));
-op!(<S: AsFd> FileStat(fd: S));
+op!(<T: IoBufMut, S: AsFdTarget> RecvFrom(fd: S, buffer: T));
+op!(<T: IoBuf, S: AsFdTarget> SendTo(fd: S, buffer: T, addr: SockAddr));
+op!(<T: IoVectoredBufMut, S: AsFdTarget> RecvFromVectored(fd: S, buffer: T));
+op!(<T: IoVectoredBuf, S: AsFdTarget> SendToVectored(fd: S, buffer: T, addr: SockAddr));
+op!(<S: AsFdTarget> FileStat(fd: S));
 op!(<> PathStat(path: CString, follow_symlink: bool));
 
 #[cfg(io_uring)]
 macro_rules! mop {
-    (<$($ty:ident: $trait:ident),* $(,)?> $name:ident( $($arg:ident: $arg_t:ty),* $(,)? )) => {
+    (<$($ty:ident: $trait:ident),* $(,)?> $name:ident( $($arg:ident: $arg_t:ty),* $(,)? ) in $pool:ident) => {
         ::paste::paste!{
             enum [< $name Inner >] <$($ty: $trait),*> {
                 Poll(crate::op::managed::$name<$($ty),*>),
@@ -112,21 +133,24 @@ macro_rules! mop {
             }
 
             impl<$($ty: $trait),*> [< $name Inner >]<$($ty),*> {
-                fn poll(&mut self) -> &mut crate::op::managed::$name<$($ty),*> {
-                    debug_assert!(DriverType::current() == DriverType::Poll);
-
+                fn poll(&mut self) -> std::io::Result<&mut crate::op::managed::$name<$($ty),*>> {
                     match self {
-                        Self::Poll(ref mut op) => op,
-                        Self::IoUring(_) => unreachable!("Current driver is not `io-uring`"),
+                        Self::Poll(ref mut op) => Ok(op),
+                        Self::IoUring(_) => Err(mismatched_pool("io-uring", "polling")),
                     }
                 }
 
-                fn iour(&mut self) -> &mut iour::$name<$($ty),*> {
-                    debug_assert!(DriverType::current() == DriverType::IoUring);
+                fn iour(&mut self) -> std::io::Result<&mut iour::$name<$($ty),*>> {
+                    match self {
+                        Self::IoUring(ref mut op) => Ok(op),
+                        Self::Poll(_) => Err(mismatched_pool("polling", "io-uring")),
+                    }
+                }
 
+                fn is_supported(&self, setup: &crate::SetupReport) -> bool {
                     match self {
-                        Self::IoUring(ref mut op) => op,
-                        Self::Poll(_) => unreachable!("Current driver is not `polling`"),
+                        Self::IoUring(op) => iour::OpCode::is_supported(op, setup),
+                        Self::Poll(_) => true,
                     }
                 }
             }
@@ -137,16 +161,16 @@ macro_rules! mop {
             }
 
             impl<$($ty: $trait),*> $name <$($ty),*> {
-                #[doc = concat!("Create a new `", stringify!($name), "`.")]
+                #[doc = concat!("Create a new `", stringify!($name), "`. It is created for the driver of the buffer pool.")]
                 pub fn new($($arg: $arg_t),*) -> std::io::Result<Self> {
-                    Ok(match DriverType::current() {
-                        DriverType::Poll => Self {
-                            inner: [< $name Inner >]::Poll(crate::op::managed::$name::new($($arg),*)?),
-                        },
-                        DriverType::IoUring => Self {
+                    Ok(if $pool.is_io_uring() {
+                        Self {
                             inner: [< $name Inner >]::IoUring(iour::$name::new($($arg),*)?),
-                        },
-                        _ => unreachable!("Fuse driver will only be enabled on linux"),
+                        }
+                    } else {
+                        Self {
+                            inner: [< $name Inner >]::Poll(crate::op::managed::$name::new($($arg),*)?),
+                        }
                     })
                 }
             }
@@ -175,29 +199,81 @@ macro_rules! mop {
 
         impl<$($ty: $trait),*> poll::OpCode for $name<$($ty),*> {
             fn pre_submit(self: std::pin::Pin<&mut Self>) -> std::io::Result<crate::Decision> {
-                unsafe { self.map_unchecked_mut(|x| x.inner.poll() ) }.pre_submit()
+                let op = unsafe { self.get_unchecked_mut() }.inner.poll()?;
+                unsafe { std::pin::Pin::new_unchecked(op) }.pre_submit()
             }
 
             fn op_type(self: std::pin::Pin<&mut Self>) -> Option<OpType> {
-                unsafe { self.map_unchecked_mut(|x| x.inner.poll() ) }.op_type()
+                let op = unsafe { self.get_unchecked_mut() }.inner.poll().ok()?;
+                unsafe { std::pin::Pin::new_unchecked(op) }.op_type()
             }
 
             fn operate(
                 self: std::pin::Pin<&mut Self>,
             ) -> std::task::Poll<std::io::Result<usize>> {
-                unsafe { self.map_unchecked_mut(|x| x.inner.poll() ) }.operate()
+                let op = unsafe { self.get_unchecked_mut() }.inner.poll()?;
+                unsafe { std::pin::Pin::new_unchecked(op) }.operate()
             }
         }
 
         impl<$($ty: $trait),*> iour::OpCode for $name<$($ty),*> {
             fn create_entry(self: std::pin::Pin<&mut Self>) -> OpEntry {
-                unsafe { self.map_unchecked_mut(|x| x.inner.iour() ) }.create_entry()
+                match unsafe { self.get_unchecked_mut() }.inner.iour() {
+                    Ok(op) => unsafe { std::pin::Pin::new_unchecked(op) }.create_entry(),
+                    // Fails in `call_blocking`.
+                    Err(_) => OpEntry::Blocking,
+                }
+            }
+
+            fn call_blocking(self: std::pin::Pin<&mut Self>) -> std::io::Result<usize> {
+                unsafe { self.get_unchecked_mut() }.inner.iour().map(|_| 0)
+            }
+
+            fn is_supported(&self, setup: &crate::SetupReport) -> bool {
+                self.inner.is_supported(setup)
+            }
+
+            fn create_fallback_entry(self: std::pin::Pin<&mut Self>) -> OpEntry {
+                match unsafe { self.get_unchecked_mut() }.inner.iour() {
+                    Ok(op) => unsafe { std::pin::Pin::new_unchecked(op) }.create_fallback_entry(),
+                    Err(_) => OpEntry::Blocking,
+                }
+            }
+
+            unsafe fn push_multishot(self: std::pin::Pin<&mut Self>, res: std::io::Result<usize>, flags: u32) {
+                if let Ok(op) = unsafe { self.get_unchecked_mut() }.inner.iour() {
+                    unsafe { std::pin::Pin::new_unchecked(op) }.push_multishot(res, flags)
+                }
             }
         }
     };
 }
 
+/// The error of a managed op pushed to a driver other than the one of its
+/// buffer pool.
+#[cfg(io_uring)]
+fn mismatched_pool(pool: &str, driver: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("the buffer pool of `{pool}` is used by the `{driver}` driver"),
+    )
+}
+
+#[cfg(io_uring)]
+mop!(<S: AsFdTarget> ReadManagedAt(fd: S, offset: u64, pool: &BufferPool, len: usize) in pool);
 #[cfg(io_uring)]
-mop!(<S: AsFd> ReadManagedAt(fd: S, offset: u64, pool: &BufferPool, len: usize));
+mop!(<S: AsFdTarget> RecvManaged(fd: S, pool: &BufferPool, len: usize) in pool);
 #[cfg(io_uring)]
-mop!(<S: AsFd> RecvManaged(fd: S, pool: &BufferPool, len: usize));
+mop!(<S: AsFdTarget> RecvMulti(fd: S, pool: &BufferPool) in pool);
+
+#[cfg(io_uring)]
+impl<S: AsFdTarget> Multishot for RecvMulti<S> {
+    type Item = SelectedBuffer;
+
+    fn pop_multishot(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            RecvMultiInner::Poll(op) => op.pop_multishot(),
+            RecvMultiInner::IoUring(op) => op.pop_multishot(),
+        }
+    }
+}
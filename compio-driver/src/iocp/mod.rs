@@ -12,9 +12,17 @@ use std::{
 };
 
 use compio_log::{instrument, trace};
-use windows_sys::Win32::{Foundation::ERROR_CANCELLED, System::IO::OVERLAPPED};
-
-use crate::{AsyncifyPool, BufferPool, Entry, Key, ProactorBuilder};
+use windows_sys::Win32::System::IO::{CancelIoEx, OVERLAPPED};
+
+#[cfg(feature = "metrics")]
+use crate::DriverMetrics;
+use crate::{
+    BlockingOps, BufferPool, DispatchOptions, Entry, FixedFd, Key, ProactorBuilder,
+    RegisteredBuffers, RingMessage, SetupReport, cancelled_error,
+    metrics::{DriverCounters, SharedCounter},
+    op::OpView,
+    timer::TimerQueue,
+};
 
 pub(crate) mod op;
 
@@ -242,6 +250,9 @@ pub enum OpType {
     /// handle is valid till operation completes. The `operate` method should be
     /// thread safe.
     Event(RawFd),
+    /// A timer which completes after the duration elapses. The `operate`
+    /// method won't be called.
+    Timer(Duration),
 }
 
 /// Abstraction of IOCP operations.
@@ -278,14 +289,31 @@ pub trait OpCode {
         let _optr = optr; // ignore it
         Ok(())
     }
+
+    /// Where and how the operation is dispatched, if [`OpCode::op_type`]
+    /// returns [`OpType::Blocking`]. `None` for the options of the built-in
+    /// operations, see [`ProactorBuilder::op_dispatch`].
+    fn dispatch_options(&self) -> Option<DispatchOptions> {
+        None
+    }
+
+    /// Describe the operation for a custom [`Backend`]. The operations are not
+    /// described on Windows yet, and they are all [`OpView::Other`].
+    ///
+    /// [`Backend`]: crate::Backend
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        OpView::Other
+    }
 }
 
 /// Low-level driver of IOCP.
 pub(crate) struct Driver {
     port: cp::Port,
     waits: HashMap<usize, wait::Wait>,
-    pool: AsyncifyPool,
+    timers: TimerQueue,
+    blocking: BlockingOps,
     notify_overlapped: Arc<Overlapped>,
+    counters: DriverCounters,
 }
 
 impl Driver {
@@ -294,11 +322,24 @@ impl Driver {
 
         let port = cp::Port::new()?;
         let driver = port.as_raw_handle() as _;
+        let notify_overlapped = Arc::new(Overlapped::new(driver));
+        let counters = DriverCounters::default();
+        // Notified when the pools could accept the operations in the backlog.
+        let handle = NotifyHandle::new(
+            port.handle(),
+            notify_overlapped.clone(),
+            counters.notified.clone(),
+        );
+        let blocking = BlockingOps::new(builder, move || {
+            handle.notify().ok();
+        });
         Ok(Self {
             port,
             waits: HashMap::default(),
-            pool: builder.create_or_get_thread_pool(),
-            notify_overlapped: Arc::new(Overlapped::new(driver)),
+            timers: TimerQueue::new(),
+            blocking,
+            notify_overlapped,
+            counters,
         })
     }
 
@@ -310,10 +351,44 @@ impl Driver {
         self.port.attach(fd)
     }
 
+    pub fn register_buffers(&mut self, _buffers: &RegisteredBuffers) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn supports(&self, _opcode: u8) -> bool {
+        false
+    }
+
+    pub fn setup_report(&self) -> SetupReport {
+        SetupReport::default()
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> DriverMetrics {
+        self.counters.snapshot(self.blocking.pool())
+    }
+
+    pub fn pop_message(&mut self) -> Option<RingMessage> {
+        None
+    }
+
+    pub fn register_fd(&mut self, fd: OwnedFd) -> io::Result<FixedFd> {
+        Ok(FixedFd::from_fd(fd))
+    }
+
     pub fn cancel(&mut self, op: &mut Key<dyn OpCode>) {
         instrument!(compio_log::Level::TRACE, "cancel", ?op);
         trace!("cancel RawOp");
         let overlapped_ptr = op.as_mut_ptr();
+        if self.timers.remove(op.user_data())
+            || (op.is_blocking()
+                && self
+                    .blocking
+                    .cancel(op.user_data(), op.as_op_pin().dispatch_options().as_ref()))
+        {
+            self.port.post(Err(cancelled_error()), overlapped_ptr).ok();
+            return;
+        }
         if let Some(w) = self.waits.get_mut(&op.user_data()) {
             if w.cancel().is_ok() {
                 // The pack has been cancelled successfully, which means no packet will be post
@@ -327,6 +402,21 @@ impl Driver {
         unsafe { op.cancel(overlapped_ptr.cast()) }.ok();
     }
 
+    pub fn cancel_fd(&mut self, fd: RawFd) {
+        trace!("cancel fd {fd:?}");
+        // It's OK to fail to cancel.
+        unsafe { CancelIoEx(fd as _, std::ptr::null()) };
+    }
+
+    pub fn push_batch(&mut self, ops: &mut [Key<dyn OpCode>]) -> Vec<Poll<io::Result<usize>>> {
+        ops.iter_mut().map(|op| self.push(op)).collect()
+    }
+
+    pub fn push_link(&mut self, _ops: &[crate::LinkedOp]) -> io::Result<bool> {
+        // Linked operations are emulated by the proactor.
+        Ok(false)
+    }
+
     pub fn push(&mut self, op: &mut Key<dyn OpCode>) -> Poll<io::Result<usize>> {
         instrument!(compio_log::Level::TRACE, "push", ?op);
         let user_data = op.user_data();
@@ -335,35 +425,34 @@ impl Driver {
         let op_pin = op.as_op_pin();
         match op_pin.op_type() {
             OpType::Overlapped => unsafe { op_pin.operate(optr.cast()) },
-            OpType::Blocking => loop {
-                if self.push_blocking(user_data) {
-                    break Poll::Pending;
-                } else {
-                    // It's OK to wait forever, because any blocking task will notify the IOCP after
-                    // it completes.
-                    unsafe {
-                        self.poll(None)?;
-                    }
-                }
-            },
+            OpType::Blocking => {
+                let options = op_pin.dispatch_options();
+                op.set_blocking();
+                self.push_blocking(user_data, options);
+                Poll::Pending
+            }
             OpType::Event(e) => {
                 self.waits
                     .insert(user_data, wait::Wait::new(&self.port, e, op)?);
                 Poll::Pending
             }
+            OpType::Timer(delay) => {
+                self.timers.insert(user_data, delay);
+                Poll::Pending
+            }
         }
     }
 
-    fn push_blocking(&mut self, user_data: usize) -> bool {
+    fn push_blocking(&mut self, user_data: usize, options: Option<DispatchOptions>) {
         let port = self.port.handle();
-        self.pool
-            .dispatch(move || {
-                let mut op = unsafe { Key::<dyn OpCode>::new_unchecked(user_data) };
-                let optr = op.as_mut_ptr();
-                let res = op.operate_blocking();
-                port.post(res, optr).ok();
-            })
-            .is_ok()
+        // The operation waits in the backlog if the pool is full.
+        let dispatched = self.blocking.dispatch(user_data, options, move || {
+            let mut op = unsafe { Key::<dyn OpCode>::new_unchecked(user_data) };
+            let optr = op.as_mut_ptr();
+            let res = op.operate_blocking();
+            port.post(res, optr).ok();
+        });
+        self.counters.blocking.add(dispatched);
     }
 
     fn create_entry(
@@ -375,10 +464,7 @@ impl Driver {
         if user_data != notify_user_data {
             if let Some(w) = waits.remove(&user_data) {
                 if w.is_cancelled() {
-                    Some(Entry::new(
-                        user_data,
-                        Err(io::Error::from_raw_os_error(ERROR_CANCELLED as _)),
-                    ))
+                    Some(Entry::new(user_data, Err(cancelled_error())))
                 } else if entry.result.is_err() {
                     Some(entry)
                 } else {
@@ -399,7 +485,21 @@ impl Driver {
 
         let notify_user_data = self.notify_overlapped.as_ref() as *const Overlapped as usize;
 
-        for e in self.port.poll(timeout)? {
+        let dispatched = self.blocking.retry();
+        self.counters.blocking.add(dispatched);
+
+        let res = self.port.poll(self.timers.wait_timeout(timeout));
+        let expired = self.timers.pop_expired();
+        let has_expired = !expired.is_empty();
+        for user_data in expired {
+            Entry::new(user_data, Ok(0)).notify();
+        }
+        let entries = match res {
+            Ok(entries) => entries,
+            Err(e) if has_expired && e.kind() == io::ErrorKind::TimedOut => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for e in entries {
             if let Some(e) = Self::create_entry(notify_user_data, &mut self.waits, e) {
                 e.notify();
             }
@@ -409,7 +509,15 @@ impl Driver {
     }
 
     pub fn handle(&self) -> NotifyHandle {
-        NotifyHandle::new(self.port.handle(), self.notify_overlapped.clone())
+        NotifyHandle::new(
+            self.port.handle(),
+            self.notify_overlapped.clone(),
+            self.counters.notified.clone(),
+        )
+    }
+
+    pub fn next_timeout(&self) -> Option<Duration> {
+        self.timers.min_timeout()
     }
 
     pub fn create_buffer_pool(
@@ -438,15 +546,21 @@ impl AsRawFd for Driver {
 pub struct NotifyHandle {
     port: cp::PortHandle,
     overlapped: Arc<Overlapped>,
+    notified: SharedCounter,
 }
 
 impl NotifyHandle {
-    fn new(port: cp::PortHandle, overlapped: Arc<Overlapped>) -> Self {
-        Self { port, overlapped }
+    fn new(port: cp::PortHandle, overlapped: Arc<Overlapped>, notified: SharedCounter) -> Self {
+        Self {
+            port,
+            overlapped,
+            notified,
+        }
     }
 
     /// Notify the inner driver.
     pub fn notify(&self) -> io::Result<()> {
+        self.notified.inc();
         self.port.post_raw(self.overlapped.as_ref())
     }
 }
@@ -41,7 +41,7 @@ use windows_sys::{
     core::GUID,
 };
 
-use crate::{AsFd, AsRawFd, OpCode, OpType, RawFd, op::*, syscall};
+use crate::{AsFd, AsRawFd, DispatchOptions, IoFixedBuf, OpCode, OpType, RawFd, op::*, syscall};
 
 #[inline]
 fn winapi_result(transferred: u32) -> Poll<io::Result<usize>> {
@@ -132,6 +132,20 @@ impl<
         this.data = Some(data);
         Poll::Ready(res)
     }
+
+    fn dispatch_options(&self) -> Option<DispatchOptions> {
+        Some(self.options.clone())
+    }
+}
+
+impl OpCode for Timeout {
+    fn op_type(&self) -> OpType {
+        OpType::Timer(self.delay)
+    }
+
+    unsafe fn operate(self: Pin<&mut Self>, _optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
 }
 
 impl OpCode for CloseFile {
@@ -430,6 +444,16 @@ impl<S: AsFd> OpCode for RecvManaged<S> {
     }
 }
 
+impl<S: AsFd> OpCode for RecvMulti<S> {
+    unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        self.map_unchecked_mut(|this| &mut this.op).operate(optr)
+    }
+
+    unsafe fn cancel(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> io::Result<()> {
+        self.map_unchecked_mut(|this| &mut this.op).cancel(optr)
+    }
+}
+
 impl<T: IoBufMut, S: AsFd> OpCode for Recv<T, S> {
     unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
         let fd = self.fd.as_fd().as_raw_fd();
@@ -904,7 +928,7 @@ impl<T: IoVectoredBuf, C: IoBuf, S> SendMsg<T, C, S> {
     /// This function will panic if the control message buffer is misaligned.
     pub fn new(fd: S, buffer: T, control: C, addr: SockAddr) -> Self {
         assert!(
-            control.as_buf_ptr().cast::<CMSGHDR>().is_aligned(),
+            control.buf_len() == 0 || control.as_buf_ptr().cast::<CMSGHDR>().is_aligned(),
             "misaligned control message buffer"
         );
         Self {
@@ -1041,3 +1065,85 @@ impl<S: AsFd, I: IoBuf, O: IoBufMut> OpCode for DeviceIoControl<S, I, O> {
         cancel(self.fd.as_fd().as_raw_fd(), optr)
     }
 }
+
+impl<T: IoBufMut + IoFixedBuf, S: AsFd> OpCode for ReadFixedAt<T, S> {
+    fn op_type(&self) -> OpType {
+        self.op.op_type()
+    }
+
+    unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        self.map_unchecked_mut(|this| &mut this.op).operate(optr)
+    }
+
+    unsafe fn cancel(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> io::Result<()> {
+        self.map_unchecked_mut(|this| &mut this.op).cancel(optr)
+    }
+}
+
+impl<T: IoFixedBuf, S: AsFd> OpCode for WriteFixedAt<T, S> {
+    fn op_type(&self) -> OpType {
+        self.op.op_type()
+    }
+
+    unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        self.map_unchecked_mut(|this| &mut this.op).operate(optr)
+    }
+
+    unsafe fn cancel(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> io::Result<()> {
+        self.map_unchecked_mut(|this| &mut this.op).cancel(optr)
+    }
+}
+
+impl<T: IoFixedBuf, S: AsFd> OpCode for SendFixed<T, S> {
+    fn op_type(&self) -> OpType {
+        self.op.op_type()
+    }
+
+    unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        self.map_unchecked_mut(|this| &mut this.op).operate(optr)
+    }
+
+    unsafe fn cancel(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> io::Result<()> {
+        self.map_unchecked_mut(|this| &mut this.op).cancel(optr)
+    }
+}
+
+impl<T: IoBuf, S: AsFd> OpCode for SendZc<T, S> {
+    fn op_type(&self) -> OpType {
+        self.op.op_type()
+    }
+
+    unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        self.map_unchecked_mut(|this| &mut this.op).operate(optr)
+    }
+
+    unsafe fn cancel(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> io::Result<()> {
+        self.map_unchecked_mut(|this| &mut this.op).cancel(optr)
+    }
+}
+
+impl<T: IoVectoredBuf, C: IoBuf, S: AsFd> OpCode for SendMsgZc<T, C, S> {
+    fn op_type(&self) -> OpType {
+        self.op.op_type()
+    }
+
+    unsafe fn operate(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        self.map_unchecked_mut(|this| &mut this.op).operate(optr)
+    }
+
+    unsafe fn cancel(self: Pin<&mut Self>, optr: *mut OVERLAPPED) -> io::Result<()> {
+        self.map_unchecked_mut(|this| &mut this.op).cancel(optr)
+    }
+}
+
+impl OpCode for MsgRing {
+    unsafe fn operate(self: Pin<&mut Self>, _optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::Unsupported.into()))
+    }
+}
+
+impl OpCode for MsgRingFd {
+    unsafe fn operate(self: Pin<&mut Self>, _optr: *mut OVERLAPPED) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::Unsupported.into()))
+    }
+}
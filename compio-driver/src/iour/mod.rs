@@ -1,7 +1,9 @@
 #[cfg_attr(all(doc, docsrs), doc(cfg(all())))]
 #[allow(unused_imports)]
 pub use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
-use std::{io, os::fd::FromRawFd, pin::Pin, sync::Arc, task::Poll, time::Duration};
+use std::{
+    collections::VecDeque, io, os::fd::FromRawFd, pin::Pin, sync::Arc, task::Poll, time::Duration,
+};
 
 use compio_log::{instrument, trace, warn};
 use crossbeam_queue::SegQueue;
@@ -20,16 +22,25 @@ cfg_if::cfg_if! {
     }
 }
 use io_uring::{
-    IoUring,
+    EnterFlags, IoUring, Probe,
     cqueue::more,
-    opcode::{AsyncCancel, PollAdd},
-    types::{Fd, SubmitArgs, Timespec},
+    opcode::{AsyncCancel, AsyncCancel2, LinkTimeout, PollAdd},
+    squeue::Flags,
+    types::{CancelBuilder, Fd, SubmitArgs, Timespec},
 };
 pub(crate) use libc::{sockaddr_storage, socklen_t};
 #[cfg(io_uring)]
 use slab::Slab;
 
-use crate::{AsyncifyPool, BufferPool, Entry, Key, ProactorBuilder, syscall};
+#[cfg(feature = "metrics")]
+use crate::DriverMetrics;
+use crate::{
+    BlockingOps, BufferPool, DispatchOptions, Entry, FixedFd, Key, LinkedOp, ProactorBuilder,
+    RegisteredBuffers, RingMessage, SetupReport, cancelled_error,
+    metrics::{DriverCounters, SharedCounter},
+    op::OpView,
+    syscall,
+};
 
 pub(crate) mod op;
 
@@ -68,6 +79,14 @@ pub trait OpCode {
         unreachable!("this operation is asynchronous")
     }
 
+    /// Where and how the operation is dispatched, if [`create_entry`] returns
+    /// [`OpEntry::Blocking`].
+    ///
+    /// [`create_entry`]: OpCode::create_entry
+    fn dispatch_options(self: Pin<&mut Self>) -> Option<DispatchOptions> {
+        None
+    }
+
     /// Set the result when it successfully completes.
     /// The operation stores the result and is responsible to release it if the
     /// operation is cancelled.
@@ -76,43 +95,112 @@ pub trait OpCode {
     ///
     /// Users should not call it.
     unsafe fn set_result(self: Pin<&mut Self>, _: usize) {}
+
+    /// Create a fallback entry when the opcode of the entry from
+    /// [`create_entry`] is not supported by the running kernel, e.g.,
+    /// [`OpEntry::Blocking`], or an entry with an older opcode. By default, the
+    /// entry is submitted anyway, and the operation fails with `EINVAL`.
+    ///
+    /// [`create_entry`]: OpCode::create_entry
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        self.create_entry()
+    }
+
+    /// Whether the entry from [`create_entry`] could be submitted, with the
+    /// kernel features reported in `setup`, besides its opcode, e.g., the
+    /// flags it uses. If not, [`create_fallback_entry`] is used instead, and a
+    /// linked chain with the operation is emulated.
+    ///
+    /// [`create_entry`]: OpCode::create_entry
+    /// [`create_fallback_entry`]: OpCode::create_fallback_entry
+    fn is_supported(&self, _setup: &SetupReport) -> bool {
+        true
+    }
+
+    /// Push an intermediate result of a multishot operation, which completes
+    /// with `IORING_CQE_F_MORE`. See [`Multishot`].
+    ///
+    /// A zero-copy operation also completes with `IORING_CQE_F_MORE` first,
+    /// and the result should be stored until the notification arrives.
+    ///
+    /// # Safety
+    ///
+    /// Users should not call it.
+    ///
+    /// [`Multishot`]: crate::op::Multishot
+    unsafe fn push_multishot(self: Pin<&mut Self>, _res: io::Result<usize>, _flags: u32) {}
+
+    /// Take the stored result of a zero-copy operation, when the notification
+    /// with `IORING_CQE_F_NOTIF` arrives, and the buffer could be released.
+    ///
+    /// # Safety
+    ///
+    /// Users should not call it.
+    unsafe fn take_notif_result(self: Pin<&mut Self>) -> io::Result<usize> {
+        unreachable!("this operation is not zero-copy")
+    }
+
+    /// Describe the operation for a custom [`Backend`]. The operations which
+    /// are not described are [`OpView::Other`].
+    ///
+    /// [`Backend`]: crate::Backend
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        OpView::Other
+    }
 }
 
 /// Low-level driver of io-uring.
 pub(crate) struct Driver {
     inner: IoUring<SEntry, CEntry>,
     notifier: Notifier,
-    pool: AsyncifyPool,
+    blocking: BlockingOps,
     pool_completed: Arc<SegQueue<Entry>>,
     #[cfg(io_uring)]
     buffer_group_ids: Slab<()>,
+    fixed_files: FixedFiles,
+    probe: Option<Probe>,
+    setup: SetupReport,
+    ring_index: Option<u32>,
+    messages: VecDeque<RingMessage>,
+    counters: DriverCounters,
 }
 
 impl Driver {
     const CANCEL: u64 = u64::MAX;
+    pub(crate) const MSG_DATA: u64 = u64::MAX - 2;
+    pub(crate) const MSG_FD: u64 = u64::MAX - 3;
     const NOTIFY: u64 = u64::MAX - 1;
 
     pub fn new(builder: &ProactorBuilder) -> io::Result<Self> {
         instrument!(compio_log::Level::TRACE, "new", ?builder);
         trace!("new iour driver");
         let notifier = Notifier::new()?;
-        let mut io_uring_builder = IoUring::builder();
-        if let Some(sqpoll_idle) = builder.sqpoll_idle {
-            io_uring_builder.setup_sqpoll(sqpoll_idle.as_millis() as _);
-        }
-        if builder.coop_taskrun {
-            io_uring_builder.setup_coop_taskrun();
-        }
-        if builder.taskrun_flag {
-            io_uring_builder.setup_taskrun_flag();
-        }
-
-        let mut inner = io_uring_builder.build(builder.capacity)?;
+        let (mut inner, mut setup) = Self::setup(builder)?;
 
         if let Some(fd) = builder.eventfd {
             inner.submitter().register_eventfd(fd)?;
         }
 
+        let fixed_files = match FixedFiles::new(&inner, builder.fixed_files) {
+            Ok(fixed_files) => {
+                setup.fixed_files = builder.fixed_files > 0;
+                fixed_files
+            }
+            Err(_e) => {
+                warn!("cannot register the fixed file table: {_e}");
+                FixedFiles::new(&inner, 0)?
+            }
+        };
+
+        let mut probe = Probe::new();
+        let probe = match inner.submitter().register_probe(&mut probe) {
+            Ok(()) => Some(probe),
+            Err(_e) => {
+                warn!("cannot probe the supported opcodes: {_e}");
+                None
+            }
+        };
+
         #[allow(clippy::useless_conversion)]
         unsafe {
             inner
@@ -126,16 +214,135 @@ impl Driver {
                 )
                 .expect("the squeue sould not be full");
         }
+
+        let ring_index = if builder.register_ring_fd {
+            register_ring_fd(inner.as_raw_fd())
+                .inspect_err(|_e| {
+                    warn!("cannot register the ring fd: {_e}");
+                })
+                .ok()
+        } else {
+            None
+        };
+        setup.register_ring_fd = ring_index.is_some();
+        let version = kernel_version();
+        let version = builder
+            .max_kernel_version
+            .map_or(version, |max| version.min(max));
+        setup.timeout_etime_success = version >= (5, 16);
+        setup.accept_multishot = version >= (5, 19);
+        setup.recv_multishot = version >= (6, 0);
+        let counters = DriverCounters::default();
+        // Notified when the pools could accept the operations in the backlog.
+        let handle = notifier.handle(counters.notified.clone());
+        let blocking = BlockingOps::new(builder, move || {
+            handle.notify().ok();
+        });
         Ok(Self {
             inner,
             notifier,
-            pool: builder.create_or_get_thread_pool(),
+            blocking,
             pool_completed: Arc::new(SegQueue::new()),
             #[cfg(io_uring)]
             buffer_group_ids: Slab::new(),
+            fixed_files,
+            probe,
+            setup,
+            ring_index,
+            messages: VecDeque::new(),
+            counters,
         })
     }
 
+    /// Build the io-uring instance. If the kernel rejects the setup flags, find
+    /// out and disable the unsupported ones.
+    fn setup(builder: &ProactorBuilder) -> io::Result<(IoUring<SEntry, CEntry>, SetupReport)> {
+        let requested = SetupReport {
+            sqpoll: builder.sqpoll_idle.is_some(),
+            sqpoll_cpu: builder.sqpoll_idle.is_some() && builder.sqpoll_cpu.is_some(),
+            coop_taskrun: builder.coop_taskrun,
+            taskrun_flag: builder.taskrun_flag,
+            single_issuer: builder.single_issuer,
+            defer_taskrun: builder.defer_taskrun,
+            submit_all: builder.submit_all,
+            cqsize: builder.cqsize.is_some(),
+            attach_wq: builder.attach_wq.is_some(),
+            register_ring_fd: false,
+            ..SetupReport::default()
+        };
+        match Self::build_ring(builder, &requested) {
+            Ok(inner) => return Ok((inner, requested)),
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {}
+            Err(e) => return Err(e),
+        }
+
+        // The dependent flags follow the ones they depend on.
+        type SetupOption = (&'static str, fn(&mut SetupReport) -> &mut bool);
+        let options: [SetupOption; 9] = [
+            ("sqpoll", |s| &mut s.sqpoll),
+            ("sqpoll_cpu", |s| &mut s.sqpoll_cpu),
+            ("coop_taskrun", |s| &mut s.coop_taskrun),
+            ("taskrun_flag", |s| &mut s.taskrun_flag),
+            ("single_issuer", |s| &mut s.single_issuer),
+            ("defer_taskrun", |s| &mut s.defer_taskrun),
+            ("submit_all", |s| &mut s.submit_all),
+            ("cqsize", |s| &mut s.cqsize),
+            ("attach_wq", |s| &mut s.attach_wq),
+        ];
+        let mut setup = SetupReport::default();
+        for (_name, option) in options {
+            let mut wanted = requested;
+            if !*option(&mut wanted) {
+                continue;
+            }
+            let mut candidate = setup;
+            *option(&mut candidate) = true;
+            match Self::build_ring(builder, &candidate) {
+                Ok(_) => setup = candidate,
+                Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+                    warn!("setup option {_name} is rejected by the kernel");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((Self::build_ring(builder, &setup)?, setup))
+    }
+
+    fn build_ring(
+        builder: &ProactorBuilder,
+        setup: &SetupReport,
+    ) -> io::Result<IoUring<SEntry, CEntry>> {
+        let mut io_uring_builder = IoUring::builder();
+        if let Some(sqpoll_idle) = builder.sqpoll_idle.filter(|_| setup.sqpoll) {
+            io_uring_builder.setup_sqpoll(sqpoll_idle.as_millis() as _);
+        }
+        if let Some(cpu) = builder.sqpoll_cpu.filter(|_| setup.sqpoll_cpu) {
+            io_uring_builder.setup_sqpoll_cpu(cpu);
+        }
+        if setup.coop_taskrun {
+            io_uring_builder.setup_coop_taskrun();
+        }
+        if setup.taskrun_flag {
+            io_uring_builder.setup_taskrun_flag();
+        }
+        if setup.single_issuer {
+            io_uring_builder.setup_single_issuer();
+        }
+        if setup.defer_taskrun {
+            io_uring_builder.setup_defer_taskrun();
+        }
+        if setup.submit_all {
+            io_uring_builder.setup_submit_all();
+        }
+        if let Some(entries) = builder.cqsize.filter(|_| setup.cqsize) {
+            io_uring_builder.setup_cqsize(entries);
+        }
+        if let Some(fd) = builder.attach_wq.filter(|_| setup.attach_wq) {
+            io_uring_builder.setup_attach_wq(fd);
+        }
+        io_uring_builder.build(builder.capacity)
+    }
+
     // Auto means that it choose to wait or not automatically.
     fn submit_auto(&mut self, timeout: Option<Duration>) -> io::Result<()> {
         instrument!(compio_log::Level::TRACE, "submit_auto", ?timeout);
@@ -148,7 +355,9 @@ impl Driver {
             1
         };
 
-        let res = {
+        let res = if let Some(index) = self.ring_index {
+            self.enter_registered(index, want_sqe, timeout)
+        } else {
             // Last part of submission queue, wait till timeout.
             if let Some(duration) = timeout {
                 let timespec = timespec(duration);
@@ -175,6 +384,56 @@ impl Driver {
         }
     }
 
+    /// Submit and wait like [`submit_auto`](Self::submit_auto), but enter
+    /// with the registered ring fd.
+    fn enter_registered(
+        &mut self,
+        index: u32,
+        want: usize,
+        timeout: Option<Duration>,
+    ) -> io::Result<usize> {
+        let mut flags = EnterFlags::REGISTERED_RING | EnterFlags::GETEVENTS | EnterFlags::EXT_ARG;
+        let squeue = self.inner.submission();
+        if squeue.need_wakeup() {
+            flags |= EnterFlags::SQ_WAKEUP;
+        }
+        let len = squeue.len() as u32;
+        drop(squeue);
+        let timespec = timeout.map(timespec);
+        let args = GeteventsArg {
+            sigmask: 0,
+            sigmask_sz: 0,
+            min_wait_usec: 0,
+            // Null means waiting without timeout.
+            ts: timespec
+                .as_ref()
+                .map_or(0, |ts| ts as *const Timespec as u64),
+        };
+        syscall!(libc::syscall(
+            libc::SYS_io_uring_enter,
+            index,
+            len,
+            want,
+            flags.bits(),
+            &args as *const GeteventsArg,
+            std::mem::size_of::<GeteventsArg>(),
+        ))
+        .map(|res| res as _)
+    }
+
+    pub fn setup_report(&self) -> SetupReport {
+        self.setup
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> DriverMetrics {
+        self.counters.snapshot(self.blocking.pool())
+    }
+
+    pub fn pop_message(&mut self) -> Option<RingMessage> {
+        self.messages.pop_front()
+    }
+
     fn poll_blocking(&mut self) {
         // Cheaper than pop.
         if !self.pool_completed.is_empty() {
@@ -188,6 +447,7 @@ impl Driver {
 
     fn poll_entries(&mut self) -> bool {
         self.poll_blocking();
+        self.fixed_files.release(&self.inner);
 
         let mut cqueue = self.inner.completion();
         cqueue.sync();
@@ -200,6 +460,18 @@ impl Driver {
                     debug_assert!(more(flags));
                     self.notifier.clear().expect("cannot clear notifier");
                 }
+                Self::MSG_DATA => {
+                    self.messages
+                        .push_back(RingMessage::Data(entry.result() as _));
+                }
+                Self::MSG_FD => {
+                    let fd = FixedFd::new_fixed(
+                        entry.result() as _,
+                        None,
+                        self.fixed_files.released.clone(),
+                    );
+                    self.messages.push_back(RingMessage::Fd(fd));
+                }
                 _ => unsafe {
                     create_entry(entry).notify();
                 },
@@ -216,37 +488,97 @@ impl Driver {
         Ok(())
     }
 
+    pub fn register_buffers(&mut self, buffers: &RegisteredBuffers) -> io::Result<()> {
+        // SAFETY: the memory is kept alive by the proactor until the ring is
+        // dropped.
+        unsafe { self.inner.submitter().register_buffers(&buffers.iovecs()) }
+    }
+
+    // `Option::is_none_or` requires Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn supports(&self, opcode: u8) -> bool {
+        self.probe
+            .as_ref()
+            .map_or(true, |probe| probe.is_supported(opcode))
+    }
+
+    /// Create the entry of the op, or the fallback one if the opcode or other
+    /// features are not supported.
+    fn op_entry(&self, op: &mut Key<dyn crate::sys::OpCode>) -> OpEntry {
+        if !OpCode::is_supported(&*op.as_op_pin(), &self.setup) {
+            trace!("the features of the op are not supported");
+            return op.as_op_pin().create_fallback_entry();
+        }
+        match op.as_op_pin().create_entry() {
+            OpEntry::Submission(entry) if !self.supports(entry.get_opcode() as _) => {
+                trace!("opcode {} is not supported", entry.get_opcode());
+                op.as_op_pin().create_fallback_entry()
+            }
+            entry => entry,
+        }
+    }
+
+    pub fn register_fd(&mut self, fd: OwnedFd) -> io::Result<FixedFd> {
+        self.fixed_files.release(&self.inner);
+        self.fixed_files.register(&self.inner, fd)
+    }
+
+    pub fn direct_fd(&mut self, res: usize) -> FixedFd {
+        FixedFd::new_fixed(res as _, None, self.fixed_files.released.clone())
+    }
+
     pub fn cancel(&mut self, op: &mut Key<dyn crate::sys::OpCode>) {
         instrument!(compio_log::Level::TRACE, "cancel", ?op);
         trace!("cancel RawOp");
-        unsafe {
-            #[allow(clippy::useless_conversion)]
-            if self
-                .inner
-                .submission()
-                .push(
-                    &AsyncCancel::new(op.user_data() as _)
-                        .build()
-                        .user_data(Self::CANCEL)
-                        .into(),
-                )
-                .is_err()
-            {
-                warn!("could not push AsyncCancel entry");
+        let user_data = op.user_data();
+        if op.is_blocking() {
+            let options = OpCode::dispatch_options(op.as_op_pin());
+            if self.blocking.cancel(user_data, options.as_ref()) {
+                // The blocking operation hasn't started.
+                self.pool_completed
+                    .push(Entry::new(user_data, Err(cancelled_error())));
             }
+            return;
+        }
+        let entry = AsyncCancel::new(user_data as _)
+            .build()
+            .user_data(Self::CANCEL);
+        #[allow(clippy::useless_conversion)]
+        if let Err(_e) = self.push_raw(&[entry.into()]) {
+            warn!("could not push AsyncCancel entry: {_e:?}");
+        }
+    }
+
+    pub fn cancel_fd(&mut self, fd: RawFd) {
+        trace!("cancel fd {fd}");
+        let entry = AsyncCancel2::new(CancelBuilder::fd(Fd(fd)).all())
+            .build()
+            .user_data(Self::CANCEL);
+        #[allow(clippy::useless_conversion)]
+        if let Err(_e) = self.push_raw(&[entry.into()]) {
+            warn!("could not push AsyncCancel entry: {_e:?}");
         }
     }
 
-    fn push_raw(&mut self, entry: SEntry) -> io::Result<()> {
+    /// Push all entries at once, so that a linked chain is never split across
+    /// submissions.
+    fn push_raw(&mut self, entries: &[SEntry]) -> io::Result<()> {
+        if entries.len() > self.inner.params().sq_entries() as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "too many entries to push at once",
+            ));
+        }
         loop {
             let mut squeue = self.inner.submission();
-            match unsafe { squeue.push(&entry) } {
+            match unsafe { squeue.push_multiple(entries) } {
                 Ok(()) => {
                     squeue.sync();
                     break Ok(());
                 }
                 Err(_) => {
                     drop(squeue);
+                    self.counters.sq_full.inc();
                     self.poll_entries();
                     match self.submit_auto(Some(Duration::ZERO)) {
                         Ok(()) => {}
@@ -265,41 +597,139 @@ impl Driver {
     pub fn push(&mut self, op: &mut Key<dyn crate::sys::OpCode>) -> Poll<io::Result<usize>> {
         instrument!(compio_log::Level::TRACE, "push", ?op);
         let user_data = op.user_data();
-        let op_pin = op.as_op_pin();
         trace!("push RawOp");
-        match op_pin.create_entry() {
+        match self.op_entry(op) {
             OpEntry::Submission(entry) => {
                 #[allow(clippy::useless_conversion)]
-                self.push_raw(entry.user_data(user_data as _).into())?;
+                self.push_raw(&[entry.user_data(user_data as _).into()])?;
                 Poll::Pending
             }
             #[cfg(feature = "io-uring-sqe128")]
             OpEntry::Submission128(entry) => {
-                self.push_raw(entry.user_data(user_data as _))?;
+                self.push_raw(&[entry.user_data(user_data as _)])?;
                 Poll::Pending
             }
-            OpEntry::Blocking => loop {
-                if self.push_blocking(user_data) {
-                    break Poll::Pending;
-                } else {
-                    self.poll_blocking();
+            OpEntry::Blocking => {
+                self.push_blocking(op);
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Queue the entries of all operations, and submit them with one syscall.
+    pub fn push_batch(
+        &mut self,
+        ops: &mut [Key<dyn crate::sys::OpCode>],
+    ) -> Vec<Poll<io::Result<usize>>> {
+        instrument!(compio_log::Level::TRACE, "push_batch", len = ops.len());
+        let mut res = Vec::with_capacity(ops.len());
+        // The entries, and the indices of their ops.
+        let mut entries = Vec::with_capacity(ops.len());
+        let mut indices = Vec::with_capacity(ops.len());
+        for (i, op) in ops.iter_mut().enumerate() {
+            let user_data = op.user_data();
+            match self.op_entry(op) {
+                #[allow(clippy::useless_conversion)]
+                OpEntry::Submission(entry) => {
+                    entries.push(SEntry::from(entry.user_data(user_data as _)));
+                    indices.push(i);
                 }
-            },
+                #[cfg(feature = "io-uring-sqe128")]
+                OpEntry::Submission128(entry) => {
+                    entries.push(entry.user_data(user_data as _));
+                    indices.push(i);
+                }
+                OpEntry::Blocking => self.push_blocking(op),
+            }
+            res.push(Poll::Pending);
+        }
+        trace!("push {} RawOps", entries.len());
+        let sq_entries = self.inner.params().sq_entries() as usize;
+        let mut pushed = 0;
+        while pushed < entries.len() {
+            let end = (pushed + sq_entries).min(entries.len());
+            if let Err(e) = self.push_raw(&entries[pushed..end]) {
+                // Fail the ops not pushed.
+                for &i in &indices[pushed..] {
+                    res[i] = Poll::Ready(Err(match e.raw_os_error() {
+                        Some(code) => io::Error::from_raw_os_error(code),
+                        None => e.kind().into(),
+                    }));
+                }
+                break;
+            }
+            pushed = end;
+        }
+        if let Err(_e) = self.inner.submit() {
+            // The queued entries are submitted again when polling.
+            trace!("submit batch: {_e}");
+        }
+        res
+    }
+
+    /// Push the linked operations with `IOSQE_IO_LINK`. Returns `false` if any
+    /// of them is blocking, and the chain needs to be emulated.
+    pub fn push_link(&mut self, ops: &[LinkedOp]) -> io::Result<bool> {
+        instrument!(compio_log::Level::TRACE, "push_link", len = ops.len());
+        let mut entries = Vec::with_capacity(ops.len());
+        for op in ops {
+            let mut key = unsafe { Key::<dyn crate::sys::OpCode>::new_unchecked(op.user_data) };
+            if !OpCode::is_supported(&*key.as_op_pin(), &self.setup) {
+                // The fallback entry may break the chain in a different way.
+                return Ok(false);
+            }
+            match self.op_entry(&mut key) {
+                #[allow(clippy::useless_conversion)]
+                OpEntry::Submission(entry) => {
+                    entries.push(SEntry::from(entry.user_data(op.user_data as _)))
+                }
+                #[cfg(feature = "io-uring-sqe128")]
+                OpEntry::Submission128(entry) => entries.push(entry.user_data(op.user_data as _)),
+                OpEntry::Blocking => return Ok(false),
+            }
+            if let Some(timer) = op.timeout {
+                let timer = unsafe { Key::<crate::op::Timeout>::new_unchecked(timer) };
+                #[allow(clippy::useless_conversion)]
+                entries.push(
+                    LinkTimeout::new(&timer.op().timespec)
+                        .build()
+                        .user_data(timer.user_data() as _)
+                        .into(),
+                );
+            }
         }
+        let last = entries.len().saturating_sub(1);
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                if i < last {
+                    entry.flags(Flags::IO_LINK)
+                } else {
+                    entry
+                }
+            })
+            .collect::<Vec<_>>();
+        trace!("push linked RawOps");
+        self.push_raw(&entries)?;
+        Ok(true)
     }
 
-    fn push_blocking(&mut self, user_data: usize) -> bool {
+    fn push_blocking(&mut self, op: &mut Key<dyn crate::sys::OpCode>) {
+        let user_data = op.user_data();
         let handle = self.handle();
         let completed = self.pool_completed.clone();
-        self.pool
-            .dispatch(move || {
-                let mut op = unsafe { Key::<dyn crate::sys::OpCode>::new_unchecked(user_data) };
-                let op_pin = op.as_op_pin();
-                let res = op_pin.call_blocking();
-                completed.push(Entry::new(user_data, res));
-                handle.notify().ok();
-            })
-            .is_ok()
+        let options = OpCode::dispatch_options(op.as_op_pin());
+        op.set_blocking();
+        // The operation waits in the backlog if the pool is full.
+        let dispatched = self.blocking.dispatch(user_data, options, move || {
+            let mut op = unsafe { Key::<dyn crate::sys::OpCode>::new_unchecked(user_data) };
+            let op_pin = op.as_op_pin();
+            let res = op_pin.call_blocking();
+            completed.push(Entry::new(user_data, res));
+            handle.notify().ok();
+        });
+        self.counters.blocking.add(dispatched);
     }
 
     pub unsafe fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()> {
@@ -307,6 +737,9 @@ impl Driver {
         // Anyway we need to submit once, no matter there are entries in squeue.
         trace!("start polling");
 
+        let dispatched = self.blocking.retry();
+        self.counters.blocking.add(dispatched);
+
         if !self.poll_entries() {
             self.submit_auto(timeout)?;
             self.poll_entries();
@@ -316,7 +749,12 @@ impl Driver {
     }
 
     pub fn handle(&self) -> NotifyHandle {
-        self.notifier.handle()
+        self.notifier.handle(self.counters.notified.clone())
+    }
+
+    pub fn next_timeout(&self) -> Option<Duration> {
+        // Timers are managed by the kernel.
+        None
     }
 
     #[cfg(io_uring)]
@@ -387,6 +825,16 @@ impl Driver {
     }
 }
 
+impl Drop for Driver {
+    fn drop(&mut self) {
+        if let Some(index) = self.ring_index {
+            if let Err(_e) = unregister_ring_fd(self.inner.as_raw_fd(), index) {
+                warn!("cannot unregister the ring fd: {_e}");
+            }
+        }
+    }
+}
+
 impl AsRawFd for Driver {
     fn as_raw_fd(&self) -> RawFd {
         self.inner.as_raw_fd()
@@ -394,23 +842,179 @@ impl AsRawFd for Driver {
 }
 
 fn create_entry(cq_entry: CEntry) -> Entry {
+    let user_data = cq_entry.user_data() as usize;
     let result = cq_entry.result();
-    let result = if result < 0 {
-        let result = if result == -libc::ECANCELED {
-            libc::ETIMEDOUT
-        } else {
-            -result
-        };
-        Err(io::Error::from_raw_os_error(result))
+    let result = if result == -libc::ETIME && is_timeout(user_data) {
+        // The timer expires.
+        Ok(0)
+    } else if result == -libc::ECANCELED {
+        Err(cancelled_error())
+    } else if result < 0 {
+        Err(io::Error::from_raw_os_error(-result))
     } else {
         Ok(result as _)
     };
-    let mut entry = Entry::new(cq_entry.user_data() as _, result);
+    let mut entry = Entry::new(user_data, result);
     entry.set_flags(cq_entry.flags());
 
     entry
 }
 
+/// Whether the op is a [`Timeout`](crate::op::Timeout), which completes with
+/// `ETIME` when the timer expires.
+fn is_timeout(user_data: usize) -> bool {
+    // SAFETY: the op is alive until the entry is notified.
+    let mut op = unsafe { Key::<dyn crate::sys::OpCode>::new_unchecked(user_data) };
+    let is_timeout = matches!(op.view(), OpView::Timeout(_));
+    is_timeout
+}
+
+/// The fixed file table. The lower half is managed by the driver for the
+/// registered fds, and the upper half is allocated by the kernel for the
+/// direct descriptors.
+struct FixedFiles {
+    free: Vec<u32>,
+    released: Arc<SegQueue<u32>>,
+    registered_len: u32,
+}
+
+/// `struct io_uring_file_index_range`.
+#[repr(C)]
+struct FileIndexRange {
+    off: u32,
+    len: u32,
+    resv: u64,
+}
+
+impl FixedFiles {
+    const REGISTER_FILE_ALLOC_RANGE: libc::c_uint = 25;
+
+    fn new(inner: &IoUring<SEntry, CEntry>, len: u32) -> io::Result<Self> {
+        let registered_len = len / 2;
+        if len > 0 {
+            inner.submitter().register_files_sparse(len)?;
+            let range = FileIndexRange {
+                off: registered_len,
+                len: len - registered_len,
+                resv: 0,
+            };
+            if let Err(e) = syscall!(libc::syscall(
+                libc::SYS_io_uring_register,
+                inner.as_raw_fd(),
+                Self::REGISTER_FILE_ALLOC_RANGE,
+                &range as *const FileIndexRange,
+                0,
+            )) {
+                inner.submitter().unregister_files().ok();
+                return Err(e);
+            }
+        }
+        Ok(Self {
+            free: (0..registered_len).rev().collect(),
+            released: Arc::new(SegQueue::new()),
+            registered_len,
+        })
+    }
+
+    fn register(&mut self, inner: &IoUring<SEntry, CEntry>, fd: OwnedFd) -> io::Result<FixedFd> {
+        let Some(index) = self.free.pop() else {
+            return Ok(FixedFd::from_fd(fd));
+        };
+        if let Err(e) = inner
+            .submitter()
+            .register_files_update(index, &[fd.as_raw_fd()])
+        {
+            self.free.push(index);
+            return Err(e);
+        }
+        Ok(FixedFd::new_fixed(index, Some(fd), self.released.clone()))
+    }
+
+    /// Clear the slots of the dropped [`FixedFd`]s.
+    fn release(&mut self, inner: &IoUring<SEntry, CEntry>) {
+        while let Some(index) = self.released.pop() {
+            if let Err(_e) = inner.submitter().register_files_update(index, &[-1]) {
+                warn!("could not release fixed file {index}: {_e}");
+                continue;
+            }
+            if index < self.registered_len {
+                self.free.push(index);
+            }
+        }
+    }
+}
+
+const IORING_REGISTER_RING_FDS: libc::c_uint = 20;
+const IORING_UNREGISTER_RING_FDS: libc::c_uint = 21;
+
+/// `struct io_uring_rsrc_update`.
+#[repr(C)]
+struct RsrcUpdate {
+    offset: u32,
+    resv: u32,
+    data: u64,
+}
+
+/// `struct io_uring_getevents_arg`.
+#[repr(C)]
+struct GeteventsArg {
+    sigmask: u64,
+    sigmask_sz: u32,
+    min_wait_usec: u32,
+    ts: u64,
+}
+
+// The registered index is not kept by `Submitter` across calls, so the ring fd
+// is registered and used with the raw syscalls.
+fn register_ring_fd(fd: RawFd) -> io::Result<u32> {
+    let mut update = RsrcUpdate {
+        offset: u32::MAX,
+        resv: 0,
+        data: fd as _,
+    };
+    syscall!(libc::syscall(
+        libc::SYS_io_uring_register,
+        fd,
+        IORING_REGISTER_RING_FDS,
+        &mut update as *mut RsrcUpdate,
+        1,
+    ))?;
+    Ok(update.offset)
+}
+
+fn unregister_ring_fd(fd: RawFd, index: u32) -> io::Result<()> {
+    let update = RsrcUpdate {
+        offset: index,
+        resv: 0,
+        data: 0,
+    };
+    syscall!(libc::syscall(
+        libc::SYS_io_uring_register,
+        fd,
+        IORING_UNREGISTER_RING_FDS,
+        &update as *const RsrcUpdate,
+        1,
+    ))?;
+    Ok(())
+}
+
+/// The `major.minor` version of the running kernel, or `(0, 0)` if unknown.
+fn kernel_version() -> (u32, u32) {
+    let mut uts = unsafe { std::mem::zeroed::<libc::utsname>() };
+    if unsafe { libc::uname(&mut uts) } < 0 {
+        return (0, 0);
+    }
+    let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) };
+    let mut parts = release
+        .to_bytes()
+        .split(|c| !c.is_ascii_digit())
+        .map(|part| std::str::from_utf8(part).ok()?.parse().ok());
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(major), Some(minor)) => (major, minor),
+        _ => (0, 0),
+    }
+}
+
 fn timespec(duration: std::time::Duration) -> Timespec {
     Timespec::new()
         .sec(duration.as_secs())
@@ -452,8 +1056,8 @@ impl Notifier {
         }
     }
 
-    pub fn handle(&self) -> NotifyHandle {
-        NotifyHandle::new(self.fd.clone())
+    pub fn handle(&self, notified: SharedCounter) -> NotifyHandle {
+        NotifyHandle::new(self.fd.clone(), notified)
     }
 }
 
@@ -466,15 +1070,17 @@ impl AsRawFd for Notifier {
 /// A notify handle to the inner driver.
 pub struct NotifyHandle {
     fd: Arc<OwnedFd>,
+    notified: SharedCounter,
 }
 
 impl NotifyHandle {
-    pub(crate) fn new(fd: Arc<OwnedFd>) -> Self {
-        Self { fd }
+    pub(crate) fn new(fd: Arc<OwnedFd>, notified: SharedCounter) -> Self {
+        Self { fd, notified }
     }
 
     /// Notify the inner driver.
     pub fn notify(&self) -> io::Result<()> {
+        self.notified.inc();
         let data = 1u64;
         syscall!(libc::write(
             self.fd.as_raw_fd(),
@@ -11,20 +11,45 @@ use compio_buf::{
 };
 use io_uring::{
     opcode,
-    types::{Fd, FsyncFlags},
+    squeue::Flags,
+    types::{DestinationSlot, Fd, Fixed, FsyncFlags, TimeoutFlags},
 };
 use libc::{sockaddr_storage, socklen_t};
 use socket2::SockAddr;
 
-use super::OpCode;
+use super::{Driver, OpCode};
 pub use crate::unix::op::*;
-use crate::{OpEntry, op::*, syscall};
+use crate::{
+    AsFdTarget, DispatchOptions, FdTarget, IoFixedBuf, OpEntry, SetupReport, op::*, syscall,
+};
+
+/// The fd of an entry, and the flags to use it as a fixed file if it is
+/// registered.
+fn entry_fd(fd: &impl AsFdTarget) -> (Fd, Flags) {
+    match fd.as_fd_target() {
+        FdTarget::Fd(fd) => (Fd(fd), Flags::empty()),
+        FdTarget::Fixed(index, _) => (Fd(index as _), Flags::FIXED_FILE),
+    }
+}
+
+/// The fd of `splice_fd_in`, and the `splice_flags` for it.
+fn splice_fd_in(fd: &impl AsFdTarget) -> (Fd, u32) {
+    /// `SPLICE_F_FD_IN_FIXED`, which is not exported by `libc`.
+    const SPLICE_F_FD_IN_FIXED: u32 = 1 << 31;
+
+    match fd.as_fd_target() {
+        FdTarget::Fd(fd) => (Fd(fd), 0),
+        FdTarget::Fixed(index, _) => (Fd(index as _), SPLICE_F_FD_IN_FIXED),
+    }
+}
 
 impl<
     D: std::marker::Send + 'static,
     F: (FnOnce() -> BufResult<usize, D>) + std::marker::Send + 'static,
 > OpCode for Asyncify<F, D>
 {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         OpEntry::Blocking
     }
@@ -40,19 +65,49 @@ impl<
         this.data = Some(data);
         res
     }
+
+    fn dispatch_options(self: Pin<&mut Self>) -> Option<DispatchOptions> {
+        Some(self.options.clone())
+    }
 }
 
 impl OpCode for OpenFile {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        opcode::OpenAt::new(Fd(libc::AT_FDCWD), self.path.as_ptr())
-            .flags(self.flags | libc::O_CLOEXEC)
-            .mode(self.mode)
-            .build()
-            .into()
+        let entry = opcode::OpenAt::new(Fd(libc::AT_FDCWD), self.path.as_ptr()).mode(self.mode);
+        if self.direct {
+            // A direct descriptor has no `O_CLOEXEC`.
+            entry
+                .flags(self.flags & !libc::O_CLOEXEC)
+                .file_index(Some(DestinationSlot::auto_target()))
+                .build()
+                .into()
+        } else {
+            entry.flags(self.flags | libc::O_CLOEXEC).build().into()
+        }
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        if self.direct {
+            self.create_entry()
+        } else {
+            OpEntry::Blocking
+        }
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        Ok(syscall!(libc::open(
+            self.path.as_ptr(),
+            self.flags | libc::O_CLOEXEC,
+            self.mode as libc::c_int
+        ))? as _)
     }
 }
 
 impl OpCode for CloseFile {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         opcode::Close::new(Fd(self.fd.as_fd().as_raw_fd()))
             .build()
@@ -76,12 +131,12 @@ impl<S> FileStat<S> {
     }
 }
 
-impl<S: AsFd> OpCode for FileStat<S> {
+impl<S: AsFdTarget> OpCode for FileStat<S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         static EMPTY_NAME: &[u8] = b"\0";
         opcode::Statx::new(
-            Fd(this.fd.as_fd().as_fd().as_raw_fd()),
+            Fd(this.fd.as_fd_target().as_raw_fd()),
             EMPTY_NAME.as_ptr().cast(),
             std::ptr::addr_of_mut!(this.stat).cast(),
         )
@@ -142,94 +197,129 @@ impl IntoInner for PathStat {
     }
 }
 
-impl<T: IoBufMut, S: AsFd> OpCode for ReadAt<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> OpCode for ReadAt<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        let fd = Fd(self.fd.as_fd().as_raw_fd());
+        let (fd, flags) = entry_fd(&self.fd);
         let offset = self.offset;
         let slice = unsafe { self.get_unchecked_mut() }.buffer.as_mut_slice();
         opcode::Read::new(fd, slice.as_mut_ptr() as _, slice.len() as _)
             .offset(offset)
             .build()
+            .flags(flags)
             .into()
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> OpCode for ReadVectoredAt<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> OpCode for ReadVectoredAt<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slices = unsafe { this.buffer.io_slices_mut() };
-        opcode::Readv::new(
-            Fd(this.fd.as_fd().as_raw_fd()),
-            this.slices.as_ptr() as _,
-            this.slices.len() as _,
-        )
-        .offset(this.offset)
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::Readv::new(fd, this.slices.as_ptr() as _, this.slices.len() as _)
+            .offset(this.offset)
+            .build()
+            .flags(flags)
+            .into()
     }
 }
 
-impl<T: IoBuf, S: AsFd> OpCode for WriteAt<T, S> {
+impl<T: IoBuf, S: AsFdTarget> OpCode for WriteAt<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let slice = self.buffer.as_slice();
-        opcode::Write::new(
-            Fd(self.fd.as_fd().as_raw_fd()),
-            slice.as_ptr(),
-            slice.len() as _,
-        )
-        .offset(self.offset)
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::Write::new(fd, slice.as_ptr(), slice.len() as _)
+            .offset(self.offset)
+            .build()
+            .flags(flags)
+            .into()
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> OpCode for WriteVectoredAt<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> OpCode for WriteVectoredAt<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slices = unsafe { this.buffer.io_slices() };
-        opcode::Writev::new(
-            Fd(this.fd.as_fd().as_raw_fd()),
-            this.slices.as_ptr() as _,
-            this.slices.len() as _,
-        )
-        .offset(this.offset)
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::Writev::new(fd, this.slices.as_ptr() as _, this.slices.len() as _)
+            .offset(this.offset)
+            .build()
+            .flags(flags)
+            .into()
     }
 }
 
-impl<S: AsFd> OpCode for Sync<S> {
+impl<S: AsFdTarget> OpCode for Sync<S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        opcode::Fsync::new(Fd(self.fd.as_fd().as_raw_fd()))
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::Fsync::new(fd)
             .flags(if self.datasync {
                 FsyncFlags::DATASYNC
             } else {
                 FsyncFlags::empty()
             })
             .build()
+            .flags(flags)
             .into()
     }
 }
 
 impl OpCode for Unlink {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         opcode::UnlinkAt::new(Fd(libc::AT_FDCWD), self.path.as_ptr())
             .flags(if self.dir { libc::AT_REMOVEDIR } else { 0 })
             .build()
             .into()
     }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        if self.dir {
+            syscall!(libc::rmdir(self.path.as_ptr()))?;
+        } else {
+            syscall!(libc::unlink(self.path.as_ptr()))?;
+        }
+        Ok(0)
+    }
 }
 
 impl OpCode for CreateDir {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         opcode::MkDirAt::new(Fd(libc::AT_FDCWD), self.path.as_ptr())
             .mode(self.mode)
             .build()
             .into()
     }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        syscall!(libc::mkdir(self.path.as_ptr(), self.mode))?;
+        Ok(0)
+    }
 }
 
 impl OpCode for Rename {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         opcode::RenameAt::new(
             Fd(libc::AT_FDCWD),
@@ -240,6 +330,15 @@ impl OpCode for Rename {
         .build()
         .into()
     }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        syscall!(libc::rename(self.old_path.as_ptr(), self.new_path.as_ptr()))?;
+        Ok(0)
+    }
 }
 
 impl OpCode for Symlink {
@@ -252,6 +351,15 @@ impl OpCode for Symlink {
         .build()
         .into()
     }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        syscall!(libc::symlink(self.source.as_ptr(), self.target.as_ptr()))?;
+        Ok(0)
+    }
 }
 
 impl OpCode for HardLink {
@@ -265,33 +373,80 @@ impl OpCode for HardLink {
         .build()
         .into()
     }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        syscall!(libc::link(self.source.as_ptr(), self.target.as_ptr()))?;
+        Ok(0)
+    }
 }
 
 impl OpCode for CreateSocket {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        opcode::Socket::new(
-            self.domain,
-            self.socket_type | libc::SOCK_CLOEXEC,
-            self.protocol,
-        )
-        .build()
-        .into()
+        if self.direct {
+            opcode::Socket::new(self.domain, self.socket_type, self.protocol)
+                .file_index(Some(DestinationSlot::auto_target()))
+                .build()
+                .into()
+        } else {
+            opcode::Socket::new(
+                self.domain,
+                self.socket_type | libc::SOCK_CLOEXEC,
+                self.protocol,
+            )
+            .build()
+            .into()
+        }
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        if self.direct {
+            self.create_entry()
+        } else {
+            OpEntry::Blocking
+        }
     }
 
     fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
-        Ok(syscall!(libc::socket(self.domain, self.socket_type, self.protocol))? as _)
+        Ok(syscall!(libc::socket(
+            self.domain,
+            self.socket_type | libc::SOCK_CLOEXEC,
+            self.protocol
+        ))? as _)
     }
 }
 
-impl<S: AsFd> OpCode for ShutdownSocket<S> {
+impl<S: AsFdTarget> OpCode for ShutdownSocket<S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        opcode::Shutdown::new(Fd(self.fd.as_fd().as_raw_fd()), self.how())
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::Shutdown::new(fd, self.how())
             .build()
+            .flags(flags)
             .into()
     }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        Ok(syscall!(libc::shutdown(
+            self.fd.as_fd_target().as_raw_fd(),
+            self.how()
+        ))? as _)
+    }
 }
 
 impl OpCode for CloseSocket {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         opcode::Close::new(Fd(self.fd.as_fd().as_raw_fd()))
             .build()
@@ -299,84 +454,132 @@ impl OpCode for CloseSocket {
     }
 }
 
-impl<S: AsFd> OpCode for Accept<S> {
+impl<S: AsFdTarget> OpCode for Accept<S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
-        opcode::Accept::new(
-            Fd(this.fd.as_fd().as_raw_fd()),
+        let (fd, flags) = entry_fd(&this.fd);
+        let entry = opcode::Accept::new(
+            fd,
             &mut this.buffer as *mut sockaddr_storage as *mut libc::sockaddr,
             &mut this.addr_len,
-        )
-        .flags(libc::SOCK_CLOEXEC)
-        .build()
-        .into()
+        );
+        let entry = if this.direct {
+            entry.file_index(Some(DestinationSlot::auto_target()))
+        } else {
+            entry.flags(libc::SOCK_CLOEXEC)
+        };
+        entry.build().flags(flags).into()
     }
 
     unsafe fn set_result(self: Pin<&mut Self>, fd: usize) {
-        self.get_unchecked_mut().accepted_fd = Some(OwnedFd::from_raw_fd(fd as _));
+        // A direct descriptor is an index, not owned by the op. The fd may have
+        // been stored by the polling driver of the fusion driver.
+        if !self.direct && self.accepted_fd.is_none() {
+            self.get_unchecked_mut().accepted_fd = Some(OwnedFd::from_raw_fd(fd as _));
+        }
     }
 }
 
-impl<S: AsFd> OpCode for Connect<S> {
+impl<S: AsFdTarget> OpCode for AcceptMulti<S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        opcode::Connect::new(
-            Fd(self.fd.as_fd().as_raw_fd()),
-            self.addr.as_ptr(),
-            self.addr.len(),
-        )
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&self.op.fd);
+        opcode::AcceptMulti::new(fd)
+            .flags(libc::SOCK_CLOEXEC)
+            .build()
+            .flags(flags)
+            .into()
+    }
+
+    fn is_supported(&self, setup: &SetupReport) -> bool {
+        setup.accept_multishot
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        // Accept only one connection, and the caller submits it again.
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.create_entry()
+    }
+
+    unsafe fn set_result(self: Pin<&mut Self>, fd: usize) {
+        let op = &mut self.get_unchecked_mut().op;
+        if op.accepted_fd.is_none() {
+            op.accepted_fd = Some(OwnedFd::from_raw_fd(fd as _));
+        }
+    }
+
+    unsafe fn push_multishot(self: Pin<&mut Self>, res: io::Result<usize>, _: u32) {
+        if let Ok(fd) = res {
+            self.get_unchecked_mut()
+                .accepted
+                .push_back(OwnedFd::from_raw_fd(fd as _));
+        }
     }
 }
 
-impl<T: IoBufMut, S: AsFd> OpCode for Recv<T, S> {
+impl<S: AsFdTarget> OpCode for Connect<S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        let fd = self.fd.as_fd().as_raw_fd();
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::Connect::new(fd, self.addr.as_ptr(), self.addr.len())
+            .build()
+            .flags(flags)
+            .into()
+    }
+}
+
+impl<T: IoBufMut, S: AsFdTarget> OpCode for Recv<T, S> {
+    op_view!();
+
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let (fd, flags) = entry_fd(&self.fd);
         let slice = unsafe { self.get_unchecked_mut() }.buffer.as_mut_slice();
-        opcode::Read::new(Fd(fd), slice.as_mut_ptr() as _, slice.len() as _)
+        opcode::Read::new(fd, slice.as_mut_ptr() as _, slice.len() as _)
             .build()
+            .flags(flags)
             .into()
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> OpCode for RecvVectored<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> OpCode for RecvVectored<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slices = unsafe { this.buffer.io_slices_mut() };
-        opcode::Readv::new(
-            Fd(this.fd.as_fd().as_raw_fd()),
-            this.slices.as_ptr() as _,
-            this.slices.len() as _,
-        )
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::Readv::new(fd, this.slices.as_ptr() as _, this.slices.len() as _)
+            .build()
+            .flags(flags)
+            .into()
     }
 }
 
-impl<T: IoBuf, S: AsFd> OpCode for Send<T, S> {
+impl<T: IoBuf, S: AsFdTarget> OpCode for Send<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let slice = self.buffer.as_slice();
-        opcode::Write::new(
-            Fd(self.fd.as_fd().as_raw_fd()),
-            slice.as_ptr(),
-            slice.len() as _,
-        )
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::Write::new(fd, slice.as_ptr(), slice.len() as _)
+            .build()
+            .flags(flags)
+            .into()
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> OpCode for SendVectored<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> OpCode for SendVectored<T, S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slices = unsafe { this.buffer.io_slices() };
-        opcode::Writev::new(
-            Fd(this.fd.as_fd().as_raw_fd()),
-            this.slices.as_ptr() as _,
-            this.slices.len() as _,
-        )
-        .build()
-        .into()
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::Writev::new(fd, this.slices.as_ptr() as _, this.slices.len() as _)
+            .build()
+            .flags(flags)
+            .into()
     }
 }
 
@@ -398,14 +601,16 @@ impl<S> RecvFromHeader<S> {
     }
 }
 
-impl<S: AsFd> RecvFromHeader<S> {
+impl<S: AsFdTarget> RecvFromHeader<S> {
     pub fn create_entry(&mut self, slices: &mut [IoSliceMut]) -> OpEntry {
         self.msg.msg_name = &mut self.addr as *mut _ as _;
         self.msg.msg_namelen = std::mem::size_of_val(&self.addr) as _;
         self.msg.msg_iov = slices.as_mut_ptr() as _;
         self.msg.msg_iovlen = slices.len() as _;
-        opcode::RecvMsg::new(Fd(self.fd.as_fd().as_raw_fd()), &mut self.msg)
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::RecvMsg::new(fd, &mut self.msg)
             .build()
+            .flags(flags)
             .into()
     }
 
@@ -433,15 +638,24 @@ impl<T: IoBufMut, S> RecvFrom<T, S> {
     }
 }
 
-impl<T: IoBufMut, S: AsFd> OpCode for RecvFrom<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> OpCode for RecvFrom<T, S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slice[0] = unsafe { this.buffer.as_io_slice_mut() };
         this.header.create_entry(&mut this.slice)
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::RecvFrom {
+            fd: this.header.fd.as_fd_target().as_raw_fd(),
+            bufs: vec![this.buffer.as_mut_slice()],
+            addr: AddrMut::new(&mut this.header.addr, &mut this.header.msg.msg_namelen),
+        }
+    }
 }
 
-impl<T: IoBufMut, S: AsFd> IntoInner for RecvFrom<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> IntoInner for RecvFrom<T, S> {
     type Inner = (T, sockaddr_storage, socklen_t);
 
     fn into_inner(self) -> Self::Inner {
@@ -468,15 +682,24 @@ impl<T: IoVectoredBufMut, S> RecvFromVectored<T, S> {
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> OpCode for RecvFromVectored<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> OpCode for RecvFromVectored<T, S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slice = unsafe { this.buffer.io_slices_mut() };
         this.header.create_entry(&mut this.slice)
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::RecvFrom {
+            fd: this.header.fd.as_fd_target().as_raw_fd(),
+            bufs: bufs_mut(unsafe { this.buffer.io_slices_mut() }),
+            addr: AddrMut::new(&mut this.header.addr, &mut this.header.msg.msg_namelen),
+        }
+    }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> IntoInner for RecvFromVectored<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> IntoInner for RecvFromVectored<T, S> {
     type Inner = (T, sockaddr_storage, socklen_t);
 
     fn into_inner(self) -> Self::Inner {
@@ -503,14 +726,16 @@ impl<S> SendToHeader<S> {
     }
 }
 
-impl<S: AsFd> SendToHeader<S> {
+impl<S: AsFdTarget> SendToHeader<S> {
     pub fn create_entry(&mut self, slices: &mut [IoSlice]) -> OpEntry {
         self.msg.msg_name = self.addr.as_ptr() as _;
         self.msg.msg_namelen = self.addr.len();
         self.msg.msg_iov = slices.as_mut_ptr() as _;
         self.msg.msg_iovlen = slices.len() as _;
-        opcode::SendMsg::new(Fd(self.fd.as_fd().as_raw_fd()), &self.msg)
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::SendMsg::new(fd, &self.msg)
             .build()
+            .flags(flags)
             .into()
     }
 }
@@ -534,12 +759,21 @@ impl<T: IoBuf, S> SendTo<T, S> {
     }
 }
 
-impl<T: IoBuf, S: AsFd> OpCode for SendTo<T, S> {
+impl<T: IoBuf, S: AsFdTarget> OpCode for SendTo<T, S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slice[0] = unsafe { this.buffer.as_io_slice() };
         this.header.create_entry(&mut this.slice)
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::SendTo {
+            fd: this.header.fd.as_fd_target().as_raw_fd(),
+            bufs: vec![this.buffer.as_slice()],
+            addr: &this.header.addr,
+        }
+    }
 }
 
 impl<T: IoBuf, S> IntoInner for SendTo<T, S> {
@@ -568,12 +802,21 @@ impl<T: IoVectoredBuf, S> SendToVectored<T, S> {
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> OpCode for SendToVectored<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> OpCode for SendToVectored<T, S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         this.slice = unsafe { this.buffer.io_slices() };
         this.header.create_entry(&mut this.slice)
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::SendTo {
+            fd: this.header.fd.as_fd_target().as_raw_fd(),
+            bufs: bufs(unsafe { this.buffer.io_slices() }),
+            addr: &this.header.addr,
+        }
+    }
 }
 
 impl<T: IoVectoredBuf, S> IntoInner for SendToVectored<T, S> {
@@ -584,52 +827,209 @@ impl<T: IoVectoredBuf, S> IntoInner for SendToVectored<T, S> {
     }
 }
 
-impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFd> OpCode for RecvMsg<T, C, S> {
+impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFdTarget> OpCode for RecvMsg<T, C, S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         unsafe { this.set_msg() };
-        opcode::RecvMsg::new(Fd(this.fd.as_fd().as_raw_fd()), &mut this.msg)
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::RecvMsg::new(fd, &mut this.msg)
             .build()
+            .flags(flags)
             .into()
     }
 }
 
-impl<T: IoVectoredBuf, C: IoBuf, S: AsFd> OpCode for SendMsg<T, C, S> {
+impl<T: IoVectoredBuf, C: IoBuf, S: AsFdTarget> OpCode for SendMsg<T, C, S> {
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
         let this = unsafe { self.get_unchecked_mut() };
         unsafe { this.set_msg() };
-        opcode::SendMsg::new(Fd(this.fd.as_fd().as_raw_fd()), &this.msg)
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::SendMsg::new(fd, &this.msg)
             .build()
+            .flags(flags)
             .into()
     }
 }
 
-impl<S: AsFd> OpCode for PollOnce<S> {
+impl<S: AsFdTarget> OpCode for PollOnce<S> {
+    op_view!();
+
     fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-        let flags = match self.interest {
+        let poll_flags = match self.interest {
             Interest::Readable => libc::POLLIN,
             Interest::Writable => libc::POLLOUT,
         };
-        opcode::PollAdd::new(Fd(self.fd.as_fd().as_raw_fd()), flags as _)
+        let (fd, flags) = entry_fd(&self.fd);
+        opcode::PollAdd::new(fd, poll_flags as _)
+            .build()
+            .flags(flags)
+            .into()
+    }
+}
+
+impl OpCode for Timeout {
+    op_view!();
+
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        // Keep the links untouched when the timer expires.
+        opcode::Timeout::new(&self.timespec)
+            .flags(TimeoutFlags::ETIME_SUCCESS)
+            .build()
+            .into()
+    }
+
+    fn is_supported(&self, setup: &SetupReport) -> bool {
+        setup.timeout_etime_success
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        // It completes with ETIME when the timer expires.
+        opcode::Timeout::new(&self.timespec).build().into()
+    }
+}
+
+impl<T: IoBufMut + IoFixedBuf, S: AsFdTarget> OpCode for ReadFixedAt<T, S> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let op = unsafe { &mut self.get_unchecked_mut().op };
+        let (fd, flags) = entry_fd(&op.fd);
+        let buf_index = op.buffer.buf_index();
+        let slice = op.buffer.as_mut_slice();
+        opcode::ReadFixed::new(fd, slice.as_mut_ptr() as _, slice.len() as _, buf_index)
+            .offset(op.offset)
+            .build()
+            .flags(flags)
+            .into()
+    }
+}
+
+impl<T: IoFixedBuf, S: AsFdTarget> OpCode for WriteFixedAt<T, S> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let (fd, flags) = entry_fd(&self.op.fd);
+        let slice = self.op.buffer.as_slice();
+        opcode::WriteFixed::new(
+            fd,
+            slice.as_ptr(),
+            slice.len() as _,
+            self.op.buffer.buf_index(),
+        )
+        .offset(self.op.offset)
+        .build()
+        .flags(flags)
+        .into()
+    }
+}
+
+impl<T: IoFixedBuf, S: AsFdTarget> OpCode for SendFixed<T, S> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let (fd, flags) = entry_fd(&self.op.fd);
+        let slice = self.op.buffer.as_slice();
+        opcode::WriteFixed::new(
+            fd,
+            slice.as_ptr(),
+            slice.len() as _,
+            self.op.buffer.buf_index(),
+        )
+        .build()
+        .flags(flags)
+        .into()
+    }
+}
+
+impl<T: IoBuf, S: AsFdTarget> OpCode for SendZc<T, S> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let slice = self.op.buffer.as_slice();
+        let (fd, flags) = entry_fd(&self.op.fd);
+        opcode::SendZc::new(fd, slice.as_ptr(), slice.len() as _)
             .build()
+            .flags(flags)
+            .into()
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.create_entry()
+    }
+
+    unsafe fn push_multishot(self: Pin<&mut Self>, res: io::Result<usize>, _: u32) {
+        self.get_unchecked_mut().res = Some(res);
+    }
+
+    unsafe fn take_notif_result(self: Pin<&mut Self>) -> io::Result<usize> {
+        self.get_unchecked_mut()
+            .res
+            .take()
+            .expect("the result should arrive before the notification")
+    }
+}
+
+impl<T: IoVectoredBuf, C: IoBuf, S: AsFdTarget> OpCode for SendMsgZc<T, C, S> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let this = unsafe { &mut self.get_unchecked_mut().op };
+        unsafe { this.set_msg() };
+        let (fd, flags) = entry_fd(&this.fd);
+        opcode::SendMsgZc::new(fd, &this.msg)
+            .build()
+            .flags(flags)
+            .into()
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.create_entry()
+    }
+
+    unsafe fn push_multishot(self: Pin<&mut Self>, res: io::Result<usize>, _: u32) {
+        self.get_unchecked_mut().res = Some(res);
+    }
+
+    unsafe fn take_notif_result(self: Pin<&mut Self>) -> io::Result<usize> {
+        self.get_unchecked_mut()
+            .res
+            .take()
+            .expect("the result should arrive before the notification")
+    }
+}
+
+impl<S1: AsFdTarget, S2: AsFdTarget> OpCode for Splice<S1, S2> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let (fd_in, splice_flags) = splice_fd_in(&self.fd_in);
+        let (fd_out, flags) = entry_fd(&self.fd_out);
+        opcode::Splice::new(
+            fd_in,
+            self.offset_in,
+            fd_out,
+            self.offset_out,
+            self.len.min(u32::MAX as usize) as _,
+        )
+        .flags(splice_flags)
+        .build()
+        .flags(flags)
+        .into()
+    }
+}
+
+impl<S1: AsFdTarget, S2: AsFdTarget> OpCode for Tee<S1, S2> {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        let (fd_in, splice_flags) = splice_fd_in(&self.fd_in);
+        let (fd_out, flags) = entry_fd(&self.fd_out);
+        opcode::Tee::new(fd_in, fd_out, self.len.min(u32::MAX as usize) as _)
+            .flags(splice_flags)
+            .build()
+            .flags(flags)
             .into()
     }
 }
 
 #[cfg(io_uring)]
 mod buf_ring {
-    use std::{
-        io,
-        marker::PhantomPinned,
-        os::fd::{AsFd, AsRawFd},
-        pin::Pin,
-        ptr,
-    };
+    use std::{collections::VecDeque, io, marker::PhantomPinned, pin::Pin, ptr, sync::Arc};
 
-    use io_uring::{opcode, squeue::Flags, types::Fd};
+    use crossbeam_queue::SegQueue;
+    use io_uring::{opcode, squeue::Flags};
 
-    use super::OpCode;
-    use crate::{BorrowedBuffer, BufferPool, OpEntry, TakeBuffer};
+    use super::{OpCode, entry_fd};
+    use crate::{
+        AsFdTarget, BorrowedBuffer, BufferPool, OpEntry, SetupReport, TakeBuffer,
+        op::{Multishot, SelectedBuffer},
+    };
 
     /// Read a file at specified position into specified buffer.
     #[derive(Debug)]
@@ -658,15 +1058,15 @@ mod buf_ring {
         }
     }
 
-    impl<S: AsFd> OpCode for ReadManagedAt<S> {
+    impl<S: AsFdTarget> OpCode for ReadManagedAt<S> {
         fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-            let fd = Fd(self.fd.as_fd().as_raw_fd());
+            let (fd, flags) = entry_fd(&self.fd);
             let offset = self.offset;
             opcode::Read::new(fd, ptr::null_mut(), self.len)
                 .offset(offset)
                 .buf_group(self.buffer_group)
                 .build()
-                .flags(Flags::BUFFER_SELECT)
+                .flags(flags | Flags::BUFFER_SELECT)
                 .into()
         }
     }
@@ -716,13 +1116,13 @@ mod buf_ring {
         }
     }
 
-    impl<S: AsFd> OpCode for RecvManaged<S> {
+    impl<S: AsFdTarget> OpCode for RecvManaged<S> {
         fn create_entry(self: Pin<&mut Self>) -> OpEntry {
-            let fd = self.fd.as_fd().as_raw_fd();
-            opcode::Read::new(Fd(fd), ptr::null_mut(), self.len)
+            let (fd, flags) = entry_fd(&self.fd);
+            opcode::Read::new(fd, ptr::null_mut(), self.len)
                 .buf_group(self.buffer_group)
                 .build()
-                .flags(Flags::BUFFER_SELECT)
+                .flags(flags | Flags::BUFFER_SELECT)
                 .into()
         }
     }
@@ -747,25 +1147,176 @@ mod buf_ring {
             res
         }
     }
+
+    /// Receive data from remote into managed buffers with one submission, see
+    /// [`Multishot`].
+    ///
+    /// The intermediate results are the selected buffers. The operation
+    /// completes when the connection is closed, or with an error, e.g.,
+    /// `ENOBUFS` if the pool runs out of buffers. It should be submitted again
+    /// to continue receiving.
+    ///
+    /// ## Platform specific
+    /// * io-uring: it is `IORING_OP_RECV` with `IORING_RECV_MULTISHOT` since
+    ///   Linux 6.0. On older kernels, it receives only once.
+    /// * polling: it receives only once, the same as [`RecvManaged`].
+    pub struct RecvMulti<S> {
+        fd: S,
+        buffer_group: u16,
+        selected: VecDeque<SelectedBuffer>,
+        recycled: Arc<SegQueue<u16>>,
+        _p: PhantomPinned,
+    }
+
+    impl<S> RecvMulti<S> {
+        /// Create [`RecvMulti`].
+        pub fn new(fd: S, buffer_pool: &BufferPool) -> io::Result<Self> {
+            #[cfg(fusion)]
+            let buffer_pool = buffer_pool.as_io_uring();
+            Ok(Self {
+                fd,
+                buffer_group: buffer_pool.buffer_group(),
+                selected: VecDeque::new(),
+                recycled: buffer_pool.recycled(),
+                _p: PhantomPinned,
+            })
+        }
+    }
+
+    impl<S: AsFdTarget> OpCode for RecvMulti<S> {
+        fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+            let (fd, flags) = entry_fd(&self.fd);
+            opcode::RecvMulti::new(fd, self.buffer_group)
+                .build()
+                .flags(flags)
+                .into()
+        }
+
+        fn is_supported(&self, setup: &SetupReport) -> bool {
+            setup.recv_multishot
+        }
+
+        fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+            // Receive only once into a buffer as large as the selected one.
+            let (fd, flags) = entry_fd(&self.fd);
+            opcode::Recv::new(fd, ptr::null_mut(), 0)
+                .buf_group(self.buffer_group)
+                .build()
+                .flags(flags | Flags::BUFFER_SELECT)
+                .into()
+        }
+
+        unsafe fn push_multishot(self: Pin<&mut Self>, res: io::Result<usize>, flags: u32) {
+            if let Ok(res) = res {
+                let this = self.get_unchecked_mut();
+                let buffer = SelectedBuffer::new(res, flags, this.recycled.clone());
+                this.selected.push_back(buffer);
+            }
+        }
+    }
+
+    impl<S> Multishot for RecvMulti<S> {
+        type Item = SelectedBuffer;
+
+        fn pop_multishot(&mut self) -> Option<Self::Item> {
+            self.selected.pop_front()
+        }
+    }
+
+    impl<S> TakeBuffer for RecvMulti<S> {
+        type Buffer<'a> = BorrowedBuffer<'a>;
+        type BufferPool = BufferPool;
+
+        fn take_buffer(
+            self,
+            buffer_pool: &Self::BufferPool,
+            result: io::Result<usize>,
+            flags: u32,
+        ) -> io::Result<Self::Buffer<'_>> {
+            #[cfg(fusion)]
+            let buffer_pool = buffer_pool.as_io_uring();
+            let result = result.inspect_err(|_| buffer_pool.reuse_buffer(flags))?;
+            // Safety: result is valid
+            let res = unsafe { buffer_pool.get_buffer(flags, result) };
+            #[cfg(fusion)]
+            let res = res.map(BorrowedBuffer::new_io_uring);
+            res
+        }
+    }
+}
+
+const _: () = assert!(MsgRing::OPCODE == opcode::MsgRingData::CODE);
+
+impl OpCode for MsgRing {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        opcode::MsgRingData::new(Fd(self.ring), self.data as _, Driver::MSG_DATA, None)
+            .build()
+            .into()
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+impl OpCode for MsgRingFd {
+    fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+        match self.fd.index() {
+            Some(index) => opcode::MsgRingSendFd::new(
+                Fd(self.ring),
+                Fixed(index),
+                DestinationSlot::auto_target(),
+                Driver::MSG_FD,
+            )
+            .build()
+            .into(),
+            None => OpEntry::Blocking,
+        }
+    }
+
+    fn create_fallback_entry(self: Pin<&mut Self>) -> OpEntry {
+        OpEntry::Blocking
+    }
+
+    fn call_blocking(self: Pin<&mut Self>) -> io::Result<usize> {
+        if self.fd.index().is_none() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the fd is not in the fixed file table",
+            ))
+        } else {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
 }
 
 #[cfg(io_uring)]
-pub use buf_ring::{ReadManagedAt, RecvManaged};
+pub use buf_ring::{ReadManagedAt, RecvManaged, RecvMulti};
 
 #[cfg(not(io_uring))]
 mod fallback {
     use std::pin::Pin;
 
     use super::OpCode;
-    use crate::{AsFd, OpEntry, op::managed::*};
+    use crate::{AsFdTarget, OpEntry, op::managed::*};
+
+    impl<S: AsFdTarget> OpCode for ReadManagedAt<S> {
+        fn create_entry(self: Pin<&mut Self>) -> OpEntry {
+            unsafe { self.map_unchecked_mut(|this| &mut this.op) }.create_entry()
+        }
+    }
 
-    impl<S: AsFd> OpCode for ReadManagedAt<S> {
+    impl<S: AsFdTarget> OpCode for RecvManaged<S> {
         fn create_entry(self: Pin<&mut Self>) -> OpEntry {
             unsafe { self.map_unchecked_mut(|this| &mut this.op) }.create_entry()
         }
     }
 
-    impl<S: AsFd> OpCode for RecvManaged<S> {
+    impl<S: AsFdTarget> OpCode for RecvMulti<S> {
         fn create_entry(self: Pin<&mut Self>) -> OpEntry {
             unsafe { self.map_unchecked_mut(|this| &mut this.op) }.create_entry()
         }
@@ -2,7 +2,7 @@ use std::{io, marker::PhantomData, mem::MaybeUninit, pin::Pin, task::Waker};
 
 use compio_buf::BufResult;
 
-use crate::{OpCode, Overlapped, PushEntry, RawFd};
+use crate::{Entry, OpCode, Overlapped, PushEntry, RawFd, op::OpView};
 
 /// An operation with other needed information. It should be allocated on the
 /// heap. The pointer to this struct is used as `user_data`, and on Windows, it
@@ -18,6 +18,8 @@ pub(crate) struct RawOp<T: ?Sized> {
     // strong ref until it completes; the runtime holds the strong ref until the future is
     // dropped.
     cancelled: bool,
+    // Whether the op is dispatched to a thread pool.
+    blocking: bool,
     // The metadata in `*mut RawOp<dyn OpCode>`
     metadata: usize,
     result: PushEntry<Option<Waker>, io::Result<usize>>,
@@ -83,6 +85,7 @@ impl<T: OpCode + 'static> Key<T> {
         let raw_op = Box::new(RawOp {
             header,
             cancelled: false,
+            blocking: false,
             metadata: opcode_metadata::<T>(),
             result: PushEntry::Pending(None),
             flags: 0,
@@ -162,6 +165,26 @@ impl<T: ?Sized> Key<T> {
         this.cancelled
     }
 
+    /// Push an intermediate result of a multishot op, and wake the future. The
+    /// op is not completed.
+    #[cfg(io_uring)]
+    pub(crate) fn push_multishot(&mut self, res: io::Result<usize>, flags: u32) {
+        let this = unsafe { &mut *self.as_dyn_mut_ptr() };
+        unsafe {
+            Pin::new_unchecked(&mut this.op).push_multishot(res, flags);
+        }
+        if let PushEntry::Pending(Some(w)) = &this.result {
+            w.wake_by_ref();
+        }
+    }
+
+    /// Take the stored result of a zero-copy op when its notification arrives.
+    #[cfg(io_uring)]
+    pub(crate) fn take_notif_result(&mut self) -> io::Result<usize> {
+        let this = unsafe { &mut *self.as_dyn_mut_ptr() };
+        unsafe { Pin::new_unchecked(&mut this.op).take_notif_result() }
+    }
+
     pub(crate) fn set_flags(&mut self, flags: u32) {
         self.as_opaque_mut().flags = flags;
     }
@@ -170,11 +193,26 @@ impl<T: ?Sized> Key<T> {
         self.as_opaque().flags
     }
 
+    /// Mark the op as dispatched to a thread pool.
+    pub(crate) fn set_blocking(&mut self) {
+        self.as_opaque_mut().blocking = true;
+    }
+
+    /// Whether the op is dispatched to a thread pool.
+    pub(crate) fn is_blocking(&self) -> bool {
+        self.as_opaque().blocking
+    }
+
     /// Whether the op is completed.
     pub(crate) fn has_result(&self) -> bool {
         self.as_opaque().result.is_ready()
     }
 
+    /// Whether the op is completed successfully.
+    pub(crate) fn is_ok(&self) -> bool {
+        matches!(self.as_opaque().result, PushEntry::Ready(Ok(_)))
+    }
+
     /// Set waker of the current future.
     pub(crate) fn set_waker(&mut self, waker: Waker) {
         if let PushEntry::Pending(w) = &mut self.as_opaque_mut().result {
@@ -196,6 +234,19 @@ impl<T: ?Sized> Key<T> {
 }
 
 impl<T> Key<T> {
+    /// Get a reference to the inner op.
+    #[cfg(io_uring)]
+    pub(crate) fn op(&self) -> &T {
+        // SAFETY: user_data is unique and RawOp is repr(C).
+        unsafe { &(*(self.user_data as *const RawOp<T>)).op }
+    }
+
+    /// Get a mutable reference to the inner op.
+    pub(crate) fn op_mut(&mut self) -> &mut T {
+        // SAFETY: see `op`.
+        unsafe { &mut (*(self.user_data as *mut RawOp<T>)).op }
+    }
+
     /// Get the inner result if it is completed.
     ///
     /// # Safety
@@ -217,6 +268,21 @@ impl<T: OpCode + ?Sized> Key<T> {
         }
     }
 
+    /// Describe the inner op for a custom [`Backend`], see [`OpView`].
+    ///
+    /// [`Backend`]: crate::Backend
+    pub fn view(&mut self) -> OpView<'_> {
+        let op = self.as_op_pin();
+        #[cfg(fusion)]
+        {
+            crate::sys::PollOpCode::view(op)
+        }
+        #[cfg(not(fusion))]
+        {
+            op.view()
+        }
+    }
+
     /// Call [`OpCode::operate`] and assume that it is not an overlapped op,
     /// which means it never returns [`Poll::Pending`].
     ///
@@ -235,6 +301,21 @@ impl<T: OpCode + ?Sized> Key<T> {
     }
 }
 
+impl Key<dyn OpCode> {
+    /// Complete the op pushed to a custom [`Backend`] with the result, and
+    /// wake the future. If the op has been cancelled, it is released.
+    ///
+    /// # Safety
+    ///
+    /// The key should be pushed to the backend, and completed only once. It
+    /// shouldn't be used after completed.
+    ///
+    /// [`Backend`]: crate::Backend
+    pub unsafe fn complete(self, res: io::Result<usize>) {
+        Entry::new(self.user_data(), res).notify()
+    }
+}
+
 impl<T: ?Sized> std::fmt::Debug for Key<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Key({})", self.user_data())
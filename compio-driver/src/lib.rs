@@ -37,12 +37,41 @@ pub use asyncify::*;
 mod fd;
 pub use fd::*;
 
+mod fixed;
+pub use fixed::*;
+
+mod fixed_buf;
+pub use fixed_buf::*;
+
 mod driver_type;
 pub use driver_type::*;
 
 mod buffer_pool;
 pub use buffer_pool::*;
 
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::{DriverMetrics, OpMetrics};
+
+#[cfg(feature = "faults")]
+mod fault;
+#[cfg(feature = "faults")]
+pub use fault::{Fault, FaultRule, FaultRules};
+
+mod backend;
+use backend::{AnyDriver, BackendFactory};
+pub use backend::{Backend, NotifyHandle};
+
+#[cfg(all(unix, feature = "sim"))]
+pub mod sim;
+
+#[cfg(any(not(io_uring), fusion))]
+mod timer;
+
+mod link;
+pub use link::Link;
+pub(crate) use link::LinkedOp;
+
 cfg_if::cfg_if! {
     if #[cfg(windows)] {
         #[path = "iocp/mod.rs"]
@@ -61,6 +90,31 @@ cfg_if::cfg_if! {
 
 pub use sys::*;
 
+/// The error of an operation cancelled by the driver.
+pub(crate) fn cancelled_error() -> io::Error {
+    #[cfg(unix)]
+    {
+        io::Error::from_raw_os_error(libc::ETIMEDOUT)
+    }
+    #[cfg(windows)]
+    {
+        io::Error::from_raw_os_error(windows_sys::Win32::Foundation::ERROR_CANCELLED as _)
+    }
+}
+
+/// Whether the error is the one of an operation cancelled by the driver.
+///
+/// An operation which fails with the same error, e.g., `ETIMEDOUT` on unix, is
+/// also treated as cancelled.
+pub fn is_cancelled(e: &io::Error) -> bool {
+    #[cfg(windows)]
+    if e.raw_os_error() == Some(windows_sys::Win32::Foundation::ERROR_OPERATION_ABORTED as _) {
+        // Cancelled by `CancelIoEx`.
+        return true;
+    }
+    e.raw_os_error() == cancelled_error().raw_os_error()
+}
+
 #[cfg(windows)]
 #[macro_export]
 #[doc(hidden)]
@@ -238,7 +292,13 @@ impl<K, R> PushEntry<K, R> {
 /// Low-level actions of completion-based IO.
 /// It owns the operations to keep the driver safe.
 pub struct Proactor {
-    driver: Driver,
+    driver: AnyDriver,
+    links: Vec<link::LinkChain>,
+    registered_buffers: Option<RegisteredBuffers>,
+    driver_type: DriverType,
+    counters: metrics::OpCounters,
+    #[cfg(feature = "faults")]
+    faults: fault::Faults,
 }
 
 impl Proactor {
@@ -253,8 +313,23 @@ impl Proactor {
     }
 
     fn with_builder(builder: &ProactorBuilder) -> io::Result<Self> {
+        let mut driver = AnyDriver::new(builder)?;
+        let registered_buffers = match builder.registered_buffers {
+            (0, _) => None,
+            (count, size) => {
+                let buffers = RegisteredBuffers::new(count, size)?;
+                driver.register_buffers(&buffers)?;
+                Some(buffers)
+            }
+        };
         Ok(Self {
-            driver: Driver::new(builder)?,
+            driver,
+            links: vec![],
+            registered_buffers,
+            driver_type: builder.driver_type(),
+            counters: metrics::OpCounters::new(builder),
+            #[cfg(feature = "faults")]
+            faults: fault::Faults::new(&builder.faults),
         })
     }
 
@@ -264,11 +339,98 @@ impl Proactor {
     /// * IOCP: it will be attached to the completion port. An fd could only be
     ///   attached to one driver, and could only be attached once, even if you
     ///   `try_clone` it.
-    /// * io-uring & polling: it will do nothing but return `Ok(())`.
+    /// * polling: on Linux, it records whether the fd is opened with
+    ///   `O_DIRECT`, to submit the file operations on it by Linux AIO. Attach
+    ///   the fd again after changing its flags.
+    /// * io-uring: it will do nothing but return `Ok(())`.
     pub fn attach(&mut self, fd: RawFd) -> io::Result<()> {
         self.driver.attach(fd)
     }
 
+    /// Register an fd into the fixed file table. The fd is closed by the
+    /// returned [`FixedFd`], and the slot is released on dropping.
+    ///
+    /// ## Platform specific
+    /// * io-uring: if the table is not enabled or full, the fd is not
+    ///   registered, and [`FixedFd::index`] returns `None`.
+    /// * IOCP & polling: the fd is not registered.
+    pub fn register_fd(&mut self, fd: OwnedFd) -> io::Result<FixedFd> {
+        self.driver.register_fd(fd)
+    }
+
+    /// Create [`FixedFd`] from the result of a direct operation, e.g.,
+    /// [`op::OpenFile::new_direct`].
+    ///
+    /// # Safety
+    ///
+    /// `res` should be the successful result of a direct operation pushed into
+    /// this proactor.
+    #[cfg(unix)]
+    pub unsafe fn direct_fd(&mut self, res: usize) -> FixedFd {
+        self.driver.direct_fd(res)
+    }
+
+    /// Get the [`RegisteredBuffers`], if it is enabled by
+    /// [`ProactorBuilder::registered_buffers`].
+    pub fn registered_buffers(&self) -> Option<&RegisteredBuffers> {
+        self.registered_buffers.as_ref()
+    }
+
+    /// Check if an io-uring opcode, e.g., `io_uring::opcode::SendZc::CODE`, is
+    /// supported by the running kernel. An operation with an unsupported opcode
+    /// falls back to a blocking call or an older opcode if possible.
+    ///
+    /// ## Platform specific
+    /// * io-uring: the opcodes are probed when the proactor is created. All of
+    ///   them are considered supported if the kernel cannot be probed.
+    /// * IOCP & polling: always `false`.
+    pub fn supports(&self, opcode: u8) -> bool {
+        self.driver.supports(opcode)
+    }
+
+    /// The type of the driver, see [`ProactorBuilder::driver`]. The fused
+    /// operations pushed into the proactor should be created in
+    /// [`DriverType::scope`] of it.
+    ///
+    /// ## Platform specific
+    /// * Custom backends: it is the driver type set by the builder, or the
+    ///   default one of the process.
+    pub fn driver_type(&self) -> DriverType {
+        self.driver_type
+    }
+
+    /// The setup options of the builder that actually took effect.
+    ///
+    /// ## Platform specific
+    /// * io-uring: the options rejected by the kernel are disabled with a
+    ///   warning.
+    /// * IOCP & polling: no option is reported.
+    pub fn setup_report(&self) -> SetupReport {
+        self.driver.setup_report()
+    }
+
+    /// Take a snapshot of the counters of the proactor and its driver.
+    ///
+    /// ## Platform specific
+    /// * io-uring: the submission queue may be full when pushing.
+    /// * IOCP & polling: [`DriverMetrics::sq_full`] is always zero.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> DriverMetrics {
+        let mut metrics = self.driver.metrics();
+        self.counters.fill(&mut metrics);
+        metrics
+    }
+
+    /// Pop a message posted by [`op::MsgRing`] or [`op::MsgRingFd`] from
+    /// another driver. The messages arrive when the driver is polled.
+    ///
+    /// ## Platform specific
+    /// * io-uring: the messages are posted to the completion queue.
+    /// * IOCP & polling: there is never a message.
+    pub fn pop_message(&mut self) -> Option<RingMessage> {
+        self.driver.pop_message()
+    }
+
     /// Cancel an operation with the pushed user-defined data.
     ///
     /// The cancellation is not reliable. The underlying operation may continue,
@@ -276,26 +438,103 @@ impl Proactor {
     /// operation is cancelled, you should not reuse its `user_data`.
     pub fn cancel<T: OpCode>(&mut self, mut op: Key<T>) -> Option<BufResult<usize, T>> {
         instrument!(compio_log::Level::DEBUG, "cancel", ?op);
+        #[cfg(feature = "faults")]
+        self.faults.remove(op.user_data());
+        self.advance_links();
+        if self.cancel_linked(op.user_data()) {
+            self.counters.cancel::<T>();
+            return None;
+        }
         if op.set_cancelled() {
+            self.counters.complete::<T>();
             // SAFETY: completed.
             Some(unsafe { op.into_inner() })
         } else {
+            self.counters.cancel::<T>();
             self.driver
                 .cancel(&mut unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data()) });
             None
         }
     }
 
+    /// Cancel an operation, but keep waiting for it. Unlike
+    /// [`Proactor::cancel`], the operation still completes through
+    /// [`Proactor::pop`], usually with an error, so that the buffer could be
+    /// taken back. It is fine to interrupt an operation more than once.
+    ///
+    /// The operation may complete successfully if it has finished before
+    /// being interrupted.
+    pub fn interrupt<T: OpCode>(&mut self, op: &mut Key<T>) {
+        instrument!(compio_log::Level::DEBUG, "interrupt", ?op);
+        #[cfg(feature = "faults")]
+        self.faults.remove(op.user_data());
+        self.advance_links();
+        if op.has_result() {
+            return;
+        }
+        if !self.interrupt_linked(op.user_data()) {
+            self.driver
+                .cancel(&mut unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data()) });
+        }
+    }
+
+    /// Cancel all operations submitted against the fd, e.g., before closing it.
+    /// The cancelled operations complete with an error, just like the ones
+    /// cancelled by the kernel.
+    ///
+    /// ## Platform specific
+    /// * io-uring: the operations are cancelled with `IORING_ASYNC_CANCEL_FD`,
+    ///   which requires Linux 5.19. The operations submitted with the index of
+    ///   a fixed fd are not cancelled.
+    /// * IOCP: the overlapped operations are cancelled with `CancelIoEx`.
+    /// * polling: the fd is deregistered, and the waiting operations complete.
+    pub fn cancel_fd(&mut self, fd: RawFd) {
+        instrument!(compio_log::Level::DEBUG, "cancel_fd", fd);
+        self.driver.cancel_fd(fd)
+    }
+
     /// Push an operation into the driver, and return the unique key, called
     /// user-defined data, associated with it.
     pub fn push<T: OpCode + 'static>(&mut self, op: T) -> PushEntry<Key<T>, BufResult<usize, T>> {
         let mut op = self.driver.create_op(op);
-        match self
-            .driver
-            .push(&mut unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data()) })
+        self.counters.push::<T>();
+        let res = match self.inject(&mut op) {
+            Some(res) => Poll::Ready(res),
+            None => self
+                .driver
+                .push(&mut unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data()) }),
+        };
+        self.finish_push(op, res)
+    }
+
+    /// Inject the faults before the operation is submitted. Returns the result
+    /// if the operation should not be submitted.
+    fn inject<T: OpCode + 'static>(&mut self, _op: &mut Key<T>) -> Option<io::Result<usize>> {
+        #[cfg(feature = "faults")]
         {
+            let native = matches!(self.driver, AnyDriver::Native(_));
+            self.faults.before_push(_op, native)
+        }
+        #[cfg(not(feature = "faults"))]
+        None
+    }
+
+    fn finish_push<T: OpCode + 'static>(
+        &mut self,
+        mut op: Key<T>,
+        res: Poll<io::Result<usize>>,
+    ) -> PushEntry<Key<T>, BufResult<usize, T>> {
+        #[cfg(feature = "faults")]
+        if self.faults.after_push::<T>(op.user_data()) {
+            if let Poll::Ready(res) = res {
+                op.set_result(res);
+            }
+            return PushEntry::Pending(op);
+        }
+        match res {
             Poll::Pending => PushEntry::Pending(op),
             Poll::Ready(res) => {
+                self.counters.complete::<T>();
                 op.set_result(res);
                 // SAFETY: just completed.
                 PushEntry::Ready(unsafe { op.into_inner() })
@@ -303,11 +542,73 @@ impl Proactor {
         }
     }
 
+    /// Push several operations into the driver at once, and return the keys
+    /// in the same order.
+    ///
+    /// ## Platform specific
+    /// * io-uring: all entries are queued before being submitted with one
+    ///   syscall.
+    /// * IOCP & polling: the operations are started one by one, the same as
+    ///   [`Proactor::push`].
+    pub fn push_batch<T: OpCode + 'static>(
+        &mut self,
+        ops: impl IntoIterator<Item = T>,
+    ) -> Vec<PushEntry<Key<T>, BufResult<usize, T>>> {
+        let mut ops = ops
+            .into_iter()
+            .map(|op| {
+                self.counters.push::<T>();
+                let mut op = self.driver.create_op(op);
+                let injected = self.inject(&mut op);
+                (op, injected)
+            })
+            .collect::<Vec<_>>();
+        // Only the operations without injected results are submitted.
+        let mut keys = ops
+            .iter()
+            .filter(|(_, injected)| injected.is_none())
+            .map(|(op, _)| unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data()) })
+            .collect::<Vec<_>>();
+        let mut res = self.driver.push_batch(&mut keys).into_iter();
+        ops.iter_mut()
+            .map(|(_, injected)| match injected.take() {
+                Some(res) => Poll::Ready(res),
+                None => res.next().expect("one result per operation"),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .zip(ops)
+            .map(|(res, (op, _))| self.finish_push(op, res))
+            .collect()
+    }
+
     /// Poll the driver and get completed entries.
     /// You need to call [`Proactor::pop`] to get the pushed
     /// operations.
     pub fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()> {
-        unsafe { self.driver.poll(timeout) }
+        #[cfg(feature = "faults")]
+        let timeout = match (timeout, self.faults.next_timeout()) {
+            (Some(timeout), Some(delay)) => Some(timeout.min(delay)),
+            (timeout, delay) => timeout.or(delay),
+        };
+        let res = unsafe { self.driver.poll(timeout) };
+        #[cfg(feature = "faults")]
+        let res = match res {
+            Err(e) if e.kind() == io::ErrorKind::TimedOut && self.faults.wake() => Ok(()),
+            res => {
+                self.faults.wake();
+                res
+            }
+        };
+        self.counters.polls.inc();
+        if res
+            .as_ref()
+            .is_err_and(|e| e.kind() == io::ErrorKind::TimedOut)
+        {
+            self.counters.poll_timeouts.inc();
+        }
+        self.advance_links();
+        res
     }
 
     /// Get the pushed operations from the completion entries.
@@ -317,7 +618,13 @@ impl Proactor {
     /// completed.
     pub fn pop<T>(&mut self, op: Key<T>) -> PushEntry<Key<T>, (BufResult<usize, T>, u32)> {
         instrument!(compio_log::Level::DEBUG, "pop", ?op);
+        self.advance_links();
+        #[cfg(feature = "faults")]
+        if op.has_result() && self.faults.hold(op.user_data()) {
+            return PushEntry::Pending(op);
+        }
         if op.has_result() {
+            self.counters.complete::<T>();
             let flags = op.flags();
             // SAFETY: completed.
             PushEntry::Ready((unsafe { op.into_inner() }, flags))
@@ -326,8 +633,15 @@ impl Proactor {
         }
     }
 
+    /// Take an intermediate result of a multishot op, see [`op::Multishot`].
+    pub fn pop_multishot<T: op::Multishot>(&mut self, op: &mut Key<T>) -> Option<T::Item> {
+        op.op_mut().pop_multishot()
+    }
+
     /// Update the waker of the specified op.
     pub fn update_waker<T>(&mut self, op: &mut Key<T>, waker: Waker) {
+        #[cfg(feature = "faults")]
+        self.faults.set_waker(op.user_data(), &waker);
         op.set_waker(waker);
     }
 
@@ -336,6 +650,23 @@ impl Proactor {
         self.driver.handle()
     }
 
+    /// Get the duration until the next timer expires, if the timers of
+    /// [`op::Timeout`] are managed by the driver itself rather than the
+    /// kernel.
+    ///
+    /// [`Proactor::poll`] takes it into account automatically. It is only
+    /// useful when waiting for the driver outside of [`Proactor::poll`], e.g.,
+    /// in a custom event loop.
+    pub fn next_timeout(&self) -> Option<Duration> {
+        let timeout = self.driver.next_timeout();
+        #[cfg(feature = "faults")]
+        let timeout = match (timeout, self.faults.next_timeout()) {
+            (Some(timeout), Some(delay)) => Some(timeout.min(delay)),
+            (timeout, delay) => timeout.or(delay),
+        };
+        timeout
+    }
+
     /// Create buffer pool with given `buffer_size` and `buffer_len`
     ///
     /// # Notes
@@ -408,8 +739,23 @@ impl Entry {
     pub unsafe fn notify(self) {
         let user_data = self.user_data();
         let mut op = Key::<()>::new_unchecked(user_data);
+        #[cfg(io_uring)]
+        if io_uring::cqueue::more(self.flags) {
+            // An intermediate result of a multishot op, or the result of a zero-copy op
+            // before the buffer is released.
+            op.push_multishot(self.result, self.flags);
+            return;
+        }
         op.set_flags(self.flags());
-        if op.set_result(self.into_result()) {
+        #[cfg(io_uring)]
+        let res = if io_uring::cqueue::notif(self.flags) {
+            op.take_notif_result()
+        } else {
+            self.into_result()
+        };
+        #[cfg(not(io_uring))]
+        let res = self.into_result();
+        if op.set_result(res) {
             // SAFETY: completed and cancelled.
             let _ = op.into_box();
         }
@@ -418,7 +764,7 @@ impl Entry {
 
 #[derive(Debug, Clone)]
 enum ThreadPoolBuilder {
-    Create { limit: usize, recv_limit: Duration },
+    Create(AsyncifyPoolBuilder),
     Reuse(AsyncifyPool),
 }
 
@@ -430,29 +776,91 @@ impl Default for ThreadPoolBuilder {
 
 impl ThreadPoolBuilder {
     pub fn new() -> Self {
-        Self::Create {
-            limit: 256,
-            recv_limit: Duration::from_secs(60),
-        }
+        Self::Create(AsyncifyPoolBuilder::new())
     }
 
     pub fn create_or_reuse(&self) -> AsyncifyPool {
         match self {
-            Self::Create { limit, recv_limit } => AsyncifyPool::new(*limit, *recv_limit),
+            Self::Create(builder) => builder.build(),
             Self::Reuse(pool) => pool.clone(),
         }
     }
 }
 
+/// A message posted by another driver, see [`Proactor::pop_message`].
+#[derive(Debug)]
+pub enum RingMessage {
+    /// The value posted by [`op::MsgRing`].
+    Data(u32),
+    /// The direct descriptor sent by [`op::MsgRingFd`].
+    Fd(FixedFd),
+}
+
+/// The setup options that actually took effect, and the kernel features
+/// detected, see [`Proactor::setup_report`].
+///
+/// Only the io-uring driver reports the options. The ones rejected by the
+/// running kernel are disabled, instead of failing to build the proactor. The
+/// operations relying on a missing feature fall back to the older ways.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SetupReport {
+    /// See [`ProactorBuilder::sqpoll_idle`].
+    pub sqpoll: bool,
+    /// See [`ProactorBuilder::sqpoll_cpu`].
+    pub sqpoll_cpu: bool,
+    /// See [`ProactorBuilder::coop_taskrun`].
+    pub coop_taskrun: bool,
+    /// See [`ProactorBuilder::taskrun_flag`].
+    pub taskrun_flag: bool,
+    /// See [`ProactorBuilder::single_issuer`].
+    pub single_issuer: bool,
+    /// See [`ProactorBuilder::defer_taskrun`].
+    pub defer_taskrun: bool,
+    /// See [`ProactorBuilder::submit_all`].
+    pub submit_all: bool,
+    /// See [`ProactorBuilder::cqsize`].
+    pub cqsize: bool,
+    /// See [`ProactorBuilder::attach_wq`].
+    pub attach_wq: bool,
+    /// See [`ProactorBuilder::register_ring_fd`].
+    pub register_ring_fd: bool,
+    /// See [`ProactorBuilder::fixed_files`].
+    pub fixed_files: bool,
+    /// `IORING_TIMEOUT_ETIME_SUCCESS` for [`op::Timeout`], since Linux 5.16.
+    pub timeout_etime_success: bool,
+    /// Multishot `AcceptMulti`, since Linux 5.19.
+    pub accept_multishot: bool,
+    /// Multishot `RecvMulti`, since Linux 6.0.
+    pub recv_multishot: bool,
+}
+
 /// Builder for [`Proactor`].
 #[derive(Debug, Clone)]
 pub struct ProactorBuilder {
     capacity: u32,
     pool_builder: ThreadPoolBuilder,
+    op_dispatch: DispatchOptions,
     sqpoll_idle: Option<Duration>,
     coop_taskrun: bool,
     taskrun_flag: bool,
+    sqpoll_cpu: Option<u32>,
+    single_issuer: bool,
+    defer_taskrun: bool,
+    submit_all: bool,
+    cqsize: Option<u32>,
+    attach_wq: Option<RawFd>,
+    register_ring_fd: bool,
+    max_kernel_version: Option<(u32, u32)>,
     eventfd: Option<RawFd>,
+    fixed_files: u32,
+    registered_buffers: (u16, usize),
+    backend: Option<BackendFactory>,
+    driver_type: Option<DriverType>,
+    #[cfg(feature = "metrics")]
+    op_metrics: bool,
+    #[cfg(feature = "faults")]
+    faults: FaultRules,
 }
 
 impl Default for ProactorBuilder {
@@ -467,10 +875,27 @@ impl ProactorBuilder {
         Self {
             capacity: 1024,
             pool_builder: ThreadPoolBuilder::new(),
+            op_dispatch: DispatchOptions::default(),
             sqpoll_idle: None,
             coop_taskrun: false,
             taskrun_flag: false,
+            sqpoll_cpu: None,
+            single_issuer: false,
+            defer_taskrun: false,
+            submit_all: false,
+            cqsize: None,
+            attach_wq: None,
+            register_ring_fd: false,
+            max_kernel_version: None,
             eventfd: None,
+            fixed_files: 0,
+            registered_buffers: (0, 0),
+            backend: None,
+            driver_type: None,
+            #[cfg(feature = "metrics")]
+            op_metrics: false,
+            #[cfg(feature = "faults")]
+            faults: FaultRules::new(),
         }
     }
 
@@ -486,8 +911,8 @@ impl ProactorBuilder {
     ///
     /// It will be ignored if `reuse_thread_pool` is set.
     pub fn thread_pool_limit(&mut self, value: usize) -> &mut Self {
-        if let ThreadPoolBuilder::Create { limit, .. } = &mut self.pool_builder {
-            *limit = value;
+        if let ThreadPoolBuilder::Create(builder) = &mut self.pool_builder {
+            builder.thread_limit(value);
         }
         self
     }
@@ -497,8 +922,32 @@ impl ProactorBuilder {
     ///
     /// It will be ignored if `reuse_thread_pool` is set.
     pub fn thread_pool_recv_timeout(&mut self, timeout: Duration) -> &mut Self {
-        if let ThreadPoolBuilder::Create { recv_limit, .. } = &mut self.pool_builder {
-            *recv_limit = timeout;
+        if let ThreadPoolBuilder::Create(builder) = &mut self.pool_builder {
+            builder.recv_timeout(timeout);
+        }
+        self
+    }
+
+    /// Set the number of blocking operations allowed to wait in the queue of
+    /// the inner thread pool when all threads are busy, see
+    /// [`AsyncifyPoolBuilder::queue_capacity`]. The default value is 0. The
+    /// operations not accepted by the pool wait in the proactor until a thread
+    /// is available.
+    ///
+    /// It will be ignored if `reuse_thread_pool` is set.
+    pub fn thread_pool_queue_capacity(&mut self, capacity: usize) -> &mut Self {
+        if let ThreadPoolBuilder::Create(builder) = &mut self.pool_builder {
+            builder.queue_capacity(capacity);
+        }
+        self
+    }
+
+    /// Set the name of the inner thread pool and its threads.
+    ///
+    /// It will be ignored if `reuse_thread_pool` is set.
+    pub fn thread_pool_name(&mut self, name: impl Into<String>) -> &mut Self {
+        if let ThreadPoolBuilder::Create(builder) = &mut self.pool_builder {
+            builder.name(name);
         }
         self
     }
@@ -516,6 +965,23 @@ impl ProactorBuilder {
         self
     }
 
+    /// Set the options to dispatch the blocking parts of the built-in
+    /// operations, e.g., the file operations, to another pool or with another
+    /// priority. By default, they are dispatched to the thread pool of the
+    /// proactor, shared with [`op::Asyncify`], which has its own options.
+    ///
+    /// ```
+    /// use compio_driver::{AsyncifyPool, DispatchOptions, ProactorBuilder};
+    ///
+    /// let fs_pool = AsyncifyPool::builder().name("fs").build();
+    /// let mut builder = ProactorBuilder::new();
+    /// builder.op_dispatch(DispatchOptions::new().pool(fs_pool));
+    /// ```
+    pub fn op_dispatch(&mut self, options: DispatchOptions) -> &mut Self {
+        self.op_dispatch = options;
+        self
+    }
+
     /// Create or reuse the thread pool from the config.
     pub fn create_or_get_thread_pool(&self) -> AsyncifyPool {
         self.pool_builder.create_or_reuse()
@@ -561,6 +1027,108 @@ impl ProactorBuilder {
         self
     }
 
+    /// Bind the sqpoll thread to the CPU. It is ignored if
+    /// [`sqpoll_idle`](Self::sqpoll_idle) is not set.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    pub fn sqpoll_cpu(&mut self, cpu: u32) -> &mut Self {
+        self.sqpoll_cpu = Some(cpu);
+        self
+    }
+
+    /// `single_issuer` feature has been available since Linux Kernel 6.0. It
+    /// hints the kernel that only the thread creating the proactor submits the
+    /// operations, which is always true for a thread-per-core runtime.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    /// - The proactor should not be sent to another thread after creation
+    pub fn single_issuer(&mut self, enable: bool) -> &mut Self {
+        self.single_issuer = enable;
+        self
+    }
+
+    /// `defer_taskrun` feature has been available since Linux Kernel 6.1. The
+    /// completion work is deferred until the proactor polls, instead of
+    /// interrupting the thread. It should be enabled with
+    /// [`single_issuer`](Self::single_issuer), and can't run with sqpoll
+    /// feature.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    pub fn defer_taskrun(&mut self, enable: bool) -> &mut Self {
+        self.defer_taskrun = enable;
+        self
+    }
+
+    /// `submit_all` feature has been available since Linux Kernel 5.18. The
+    /// kernel continues submitting the queued operations even if one of them
+    /// fails to be submitted.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    pub fn submit_all(&mut self, enable: bool) -> &mut Self {
+        self.submit_all = enable;
+        self
+    }
+
+    /// Set the size of the completion queue independently. By default it is
+    /// twice the [`capacity`](Self::capacity), and it should not be less than
+    /// the capacity.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    pub fn cqsize(&mut self, entries: u32) -> &mut Self {
+        self.cqsize = Some(entries);
+        self
+    }
+
+    /// Share the kernel worker pool with an existing io-uring instance, e.g.,
+    /// the fd of another [`Proactor`]. It is useful when several proactors run
+    /// on different threads, to limit the total number of the kernel workers.
+    /// The fd only needs to be valid when the proactor is built.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    pub fn attach_wq(&mut self, fd: RawFd) -> &mut Self {
+        self.attach_wq = Some(fd);
+        self
+    }
+
+    /// Register the fd of io-uring to itself, which has been available since
+    /// Linux Kernel 5.18. It saves the cost of looking up the fd when waiting
+    /// for the completions.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    /// - The registration belongs to the thread creating the proactor, and the
+    ///   proactor should be polled and dropped on that thread
+    pub fn register_ring_fd(&mut self, enable: bool) -> &mut Self {
+        self.register_ring_fd = enable;
+        self
+    }
+
+    /// Detect the kernel features as if the running kernel were not newer than
+    /// `major.minor`, e.g., to test the fallbacks for older kernels. See
+    /// [`SetupReport`] for the features.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    #[doc(hidden)]
+    pub fn max_kernel_version(&mut self, major: u32, minor: u32) -> &mut Self {
+        self.max_kernel_version = Some((major, minor));
+        self
+    }
+
     /// Register an eventfd to io-uring.
     ///
     /// # Notes
@@ -571,6 +1139,84 @@ impl ProactorBuilder {
         self
     }
 
+    /// Set the length of the fixed file table, see [`FixedFd`]. The lower half
+    /// of the table is for [`Proactor::register_fd`], and the upper half is for
+    /// the direct descriptors. The default value is 0, which disables the
+    /// table.
+    ///
+    /// # Notes
+    ///
+    /// - Only effective when the `io-uring` feature is enabled
+    /// - Allocating the direct descriptors needs Linux 6.0. If the table cannot
+    ///   be registered, it is disabled, see [`SetupReport::fixed_files`].
+    pub fn fixed_files(&mut self, len: u32) -> &mut Self {
+        self.fixed_files = len;
+        self
+    }
+
+    /// Create the [`RegisteredBuffers`] with `count` buffers of `size` bytes.
+    /// The default value is 0, which disables the arena.
+    ///
+    /// # Notes
+    ///
+    /// - The buffers are registered only when the `io-uring` feature is
+    ///   enabled, and they are counted against `RLIMIT_MEMLOCK` before Linux
+    ///   5.12
+    pub fn registered_buffers(&mut self, count: u16, size: usize) -> &mut Self {
+        self.registered_buffers = (count, size);
+        self
+    }
+
+    /// Select the type of the driver, instead of the default one of the
+    /// process, e.g., to run an io-uring proactor and a polling one side by
+    /// side. Building fails with [`io::ErrorKind::Unsupported`] if the driver
+    /// is not available, see [`DriverType::is_available`].
+    ///
+    /// ## Platform specific
+    /// * Linux: both io-uring and polling are available if both features are
+    ///   enabled. The availability of io-uring in the kernel is not checked.
+    pub fn driver(&mut self, driver_type: DriverType) -> &mut Self {
+        self.driver_type = Some(driver_type);
+        self
+    }
+
+    pub(crate) fn driver_type(&self) -> DriverType {
+        self.driver_type.unwrap_or_else(DriverType::default_type)
+    }
+
+    /// Run the proactor over a custom [`Backend`] created by `f`, instead of
+    /// the driver of the platform. Most of the other options only apply to the
+    /// driver of the platform.
+    pub fn backend<B: Backend + 'static>(
+        &mut self,
+        f: impl Fn() -> io::Result<B> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.backend = Some(BackendFactory::new(f));
+        self
+    }
+
+    /// Count the operations by their types in [`DriverMetrics::ops`], which
+    /// costs a hash map lookup per operation. The default value is `false`,
+    /// and only the totals are counted.
+    #[cfg(feature = "metrics")]
+    pub fn op_metrics(&mut self, enable: bool) -> &mut Self {
+        self.op_metrics = enable;
+        self
+    }
+
+    /// Inject faults into the operations pushed by [`Proactor::push`] and
+    /// [`Proactor::push_batch`], to test the error paths. It is meant for
+    /// tests only.
+    ///
+    /// The operations pushed by [`Proactor::link`] are not affected. The
+    /// multishot operations are only affected when pushed, and their
+    /// intermediate results are never altered.
+    #[cfg(feature = "faults")]
+    pub fn faults(&mut self, rules: FaultRules) -> &mut Self {
+        self.faults = rules;
+        self
+    }
+
     /// Build the [`Proactor`].
     pub fn build(&self) -> io::Result<Proactor> {
         Proactor::with_builder(self)
@@ -0,0 +1,249 @@
+use std::{collections::VecDeque, io, task::Poll, time::Duration};
+
+use compio_log::{instrument, trace};
+
+use crate::{Key, OpCode, Proactor, backend::AnyDriver, cancelled_error, op::Timeout};
+
+/// An operation in a linked chain.
+#[derive(Debug)]
+pub(crate) struct LinkedOp {
+    pub user_data: usize,
+    /// The `user_data` of the [`Timeout`] of this op.
+    pub timeout: Option<usize>,
+    // The following fields are only used by the emulation.
+    started: bool,
+    cancelled: bool,
+    timed_out: bool,
+}
+
+impl LinkedOp {
+    fn new(user_data: usize, timeout: Option<usize>) -> Self {
+        Self {
+            user_data,
+            timeout,
+            started: false,
+            cancelled: false,
+            timed_out: false,
+        }
+    }
+}
+
+/// A chain of linked operations, created by [`Proactor::link`].
+///
+/// The operations are started one after another: an operation starts only
+/// after the previous one completes successfully. If an operation fails, the
+/// following ones are cancelled and fail without being started. The chain is
+/// submitted as a whole by [`Link::submit`], or when it is dropped.
+///
+/// ## Platform specific
+/// * io-uring: the chain is submitted with `IOSQE_IO_LINK`, and the timeouts
+///   are `IORING_OP_LINK_TIMEOUT`. Note that the kernel also breaks the chain
+///   if a read or write is short. If any operation is a blocking one, the chain
+///   is emulated as below.
+/// * IOCP & polling: the chain is emulated by the proactor, which starts the
+///   next operation when the previous one is popped or polled.
+pub struct Link<'a> {
+    proactor: &'a mut Proactor,
+    ops: Vec<LinkedOp>,
+}
+
+impl<'a> Link<'a> {
+    pub(crate) fn new(proactor: &'a mut Proactor) -> Self {
+        Self {
+            proactor,
+            ops: vec![],
+        }
+    }
+
+    /// Append an operation to the chain, and return the key of it.
+    pub fn push<T: OpCode + 'static>(&mut self, op: T) -> Key<T> {
+        let op = self.proactor.driver.create_op(op);
+        self.proactor.counters.push::<T>();
+        self.ops.push(LinkedOp::new(op.user_data(), None));
+        op
+    }
+
+    /// Append an operation with a timeout. If the operation doesn't complete
+    /// in `timeout`, it is cancelled and fails with
+    /// [`io::ErrorKind::TimedOut`], which breaks the chain.
+    pub fn push_with_timeout<T: OpCode + 'static>(&mut self, op: T, timeout: Duration) -> Key<T> {
+        let op = self.proactor.driver.create_op(op);
+        let timer = self.proactor.driver.create_op(Timeout::new(timeout));
+        self.proactor.counters.push::<T>();
+        self.ops
+            .push(LinkedOp::new(op.user_data(), Some(timer.user_data())));
+        op
+    }
+
+    /// Submit the chain.
+    pub fn submit(self) {}
+}
+
+impl Drop for Link<'_> {
+    fn drop(&mut self) {
+        let ops = std::mem::take(&mut self.ops);
+        if !ops.is_empty() {
+            self.proactor.push_link(ops);
+        }
+    }
+}
+
+/// A linked chain emulated by the proactor. The first op is the running one.
+pub(crate) struct LinkChain {
+    ops: VecDeque<LinkedOp>,
+}
+
+impl LinkChain {
+    /// Start the ops until one of them is pending. The return value indicates
+    /// if the chain is finished.
+    fn advance(&mut self, driver: &mut AnyDriver) -> bool {
+        while let Some(op) = self.ops.front_mut() {
+            let mut key = unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data) };
+            if !op.started {
+                op.started = true;
+                if op.cancelled {
+                    key.set_result(Err(cancelled_error()));
+                } else if let Poll::Ready(res) = driver.push(&mut key) {
+                    key.set_result(res);
+                } else if let Some(timer) = op.timeout {
+                    let mut timer = unsafe { Key::<dyn OpCode>::new_unchecked(timer) };
+                    if let Poll::Ready(res) = driver.push(&mut timer) {
+                        timer.set_result(res);
+                    }
+                }
+            }
+            if !key.has_result() {
+                if let Some(timer) = op.timeout {
+                    let timer = unsafe { Key::<dyn OpCode>::new_unchecked(timer) };
+                    if timer.has_result() && !op.timed_out {
+                        trace!("linked op timed out");
+                        op.timed_out = true;
+                        driver.cancel(&mut key);
+                    }
+                }
+                return false;
+            }
+
+            let op = self.ops.pop_front().expect("the chain should not be empty");
+            if let Some(timer) = op.timeout {
+                let mut timer = unsafe { Key::<dyn OpCode>::new_unchecked(timer) };
+                if timer.set_cancelled() {
+                    // SAFETY: completed and cancelled.
+                    drop(unsafe { timer.into_box() });
+                } else {
+                    driver.cancel(&mut timer);
+                }
+            }
+            let succeeded = key.is_ok();
+            if op.cancelled {
+                // SAFETY: completed, and cancelled by the user.
+                drop(unsafe { key.into_box() });
+            }
+            if !succeeded {
+                self.fail();
+            }
+        }
+        true
+    }
+
+    /// Complete all the rest ops with error.
+    fn fail(&mut self) {
+        for op in self.ops.drain(..) {
+            fail_op(op, cancelled_error());
+        }
+    }
+
+    fn find_mut(&mut self, user_data: usize) -> Option<&mut LinkedOp> {
+        self.ops.iter_mut().find(|op| op.user_data == user_data)
+    }
+}
+
+/// Complete a not started op with error.
+fn fail_op(op: LinkedOp, err: io::Error) {
+    let mut key = unsafe { Key::<dyn OpCode>::new_unchecked(op.user_data) };
+    key.set_result(Err(err));
+    if op.cancelled {
+        // SAFETY: completed, and cancelled by the user.
+        drop(unsafe { key.into_box() });
+    }
+    if let Some(timer) = op.timeout {
+        // SAFETY: the timer is not started, and not visible to the user.
+        drop(unsafe { Key::<dyn OpCode>::new_unchecked(timer).into_box() });
+    }
+}
+
+impl Proactor {
+    /// Create a [`Link`] to push a chain of linked operations.
+    pub fn link(&mut self) -> Link<'_> {
+        Link::new(self)
+    }
+
+    fn push_link(&mut self, ops: Vec<LinkedOp>) {
+        instrument!(compio_log::Level::DEBUG, "push_link", len = ops.len());
+        match self.driver.push_link(&ops) {
+            Ok(true) => {
+                // The timers are owned by the driver now.
+                for timer in ops.iter().filter_map(|op| op.timeout) {
+                    let mut timer = unsafe { Key::<dyn OpCode>::new_unchecked(timer) };
+                    if timer.set_cancelled() {
+                        // SAFETY: completed and cancelled.
+                        drop(unsafe { timer.into_box() });
+                    }
+                }
+            }
+            Ok(false) => {
+                trace!("emulate linked ops");
+                self.links.push(LinkChain { ops: ops.into() });
+                self.advance_links();
+            }
+            Err(e) => {
+                let mut ops = ops.into_iter();
+                if let Some(op) = ops.next() {
+                    fail_op(op, e);
+                }
+                ops.for_each(|op| fail_op(op, cancelled_error()));
+            }
+        }
+    }
+
+    /// Drive the emulated chains.
+    pub(crate) fn advance_links(&mut self) {
+        if !self.links.is_empty() {
+            let driver = &mut self.driver;
+            self.links.retain_mut(|chain| !chain.advance(driver));
+        }
+    }
+
+    /// Cancel an op of the emulated chains. The op is dropped by the chain
+    /// after it completes. The return value indicates if the op belongs to a
+    /// chain.
+    pub(crate) fn cancel_linked(&mut self, user_data: usize) -> bool {
+        for chain in &mut self.links {
+            if let Some(op) = chain.find_mut(user_data) {
+                op.cancelled = true;
+                if op.started {
+                    self.driver
+                        .cancel(&mut unsafe { Key::<dyn OpCode>::new_unchecked(user_data) });
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Cancel a started op of the emulated chains without dropping it. The
+    /// ops not started are left to the chain. The return value indicates if
+    /// the op belongs to a chain.
+    pub(crate) fn interrupt_linked(&mut self, user_data: usize) -> bool {
+        for chain in &mut self.links {
+            if let Some(op) = chain.find_mut(user_data) {
+                if op.started {
+                    self.driver
+                        .cancel(&mut unsafe { Key::<dyn OpCode>::new_unchecked(user_data) });
+                }
+                return true;
+            }
+        }
+        false
+    }
+}
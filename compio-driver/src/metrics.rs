@@ -0,0 +1,255 @@
+//! Counters of the proactor. The counters are plain integers updated by the
+//! thread owning the proactor, and the public snapshot types are only
+//! available with the `metrics` feature. Without the feature, the counters are
+//! zero-sized and do nothing.
+
+#[cfg(feature = "metrics")]
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+#[cfg(feature = "metrics")]
+use crate::{AsyncifyPool, ProactorBuilder};
+
+/// The counters of one kind of operations, see [`DriverMetrics::ops`].
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OpMetrics {
+    /// The operations pushed into the proactor.
+    pub pushed: u64,
+    /// The operations whose results have been taken, by
+    /// [`Proactor::pop`](crate::Proactor::pop) or immediately when pushed.
+    pub completed: u64,
+    /// The operations cancelled before their results are taken.
+    pub cancelled: u64,
+}
+
+/// A snapshot of the counters of a proactor, see
+/// [`Proactor::metrics`](crate::Proactor::metrics).
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct DriverMetrics {
+    /// All operations.
+    pub total: OpMetrics,
+    /// The operations, keyed by the type name without generic parameters,
+    /// e.g., `compio_driver::sys::op::Recv`. It is empty unless enabled by
+    /// [`ProactorBuilder::op_metrics`].
+    pub ops: HashMap<&'static str, OpMetrics>,
+    /// The times the submission queue was full when pushing. Only io-uring
+    /// has a submission queue.
+    pub sq_full: u64,
+    /// The blocking operations dispatched to the [`AsyncifyPool`].
+    pub blocking_dispatched: u64,
+    /// The threads of the [`AsyncifyPool`], see
+    /// [`AsyncifyPool::thread_count`].
+    pub pool_threads: usize,
+    /// The queue depth of the [`AsyncifyPool`], see
+    /// [`AsyncifyPool::queue_len`].
+    pub pool_queue_len: usize,
+    /// The times the interest of an fd was added, modified or deleted in the
+    /// poller. Only the polling driver registers fds.
+    pub registry_updates: u64,
+    /// The times the driver was notified by a
+    /// [`NotifyHandle`](crate::NotifyHandle).
+    pub notified: u64,
+    /// The times the proactor was polled.
+    pub polls: u64,
+    /// The times polling the proactor timed out without any completion.
+    pub poll_timeouts: u64,
+}
+
+#[cfg(feature = "metrics")]
+impl DriverMetrics {
+    /// The operations pushed in total.
+    pub fn pushed(&self) -> u64 {
+        self.total.pushed
+    }
+
+    /// The operations completed in total.
+    pub fn completed(&self) -> u64 {
+        self.total.completed
+    }
+
+    /// The operations cancelled in total.
+    pub fn cancelled(&self) -> u64 {
+        self.total.cancelled
+    }
+}
+
+/// A counter updated by one thread.
+#[derive(Debug, Default)]
+pub(crate) struct Counter(#[cfg(feature = "metrics")] u64);
+
+impl Counter {
+    #[inline]
+    pub fn inc(&mut self) {
+        #[cfg(feature = "metrics")]
+        {
+            self.0 += 1;
+        }
+    }
+
+    #[inline]
+    pub fn add(&mut self, _n: usize) {
+        #[cfg(feature = "metrics")]
+        {
+            self.0 += _n as u64;
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A counter shared with other threads.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SharedCounter(#[cfg(feature = "metrics")] Arc<AtomicU64>);
+
+impl SharedCounter {
+    #[inline]
+    pub fn inc(&self) {
+        #[cfg(feature = "metrics")]
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The counters of a driver.
+#[derive(Debug, Default)]
+pub(crate) struct DriverCounters {
+    // Only io-uring has a submission queue.
+    #[cfg_attr(not(io_uring), allow(dead_code))]
+    pub sq_full: Counter,
+    pub blocking: Counter,
+    // Only the polling driver registers fds.
+    #[cfg_attr(any(windows, all(io_uring, not(fusion))), allow(dead_code))]
+    pub registry_updates: Counter,
+    pub notified: SharedCounter,
+}
+
+impl DriverCounters {
+    #[cfg(feature = "metrics")]
+    pub fn snapshot(&self, pool: &AsyncifyPool) -> DriverMetrics {
+        DriverMetrics {
+            sq_full: self.sq_full.get(),
+            blocking_dispatched: self.blocking.get(),
+            pool_threads: pool.thread_count(),
+            pool_queue_len: pool.queue_len(),
+            registry_updates: self.registry_updates.get(),
+            notified: self.notified.get(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Hashes the address and length of a type name, which are already unique.
+#[cfg(feature = "metrics")]
+#[derive(Default)]
+struct NameHasher(u64);
+
+#[cfg(feature = "metrics")]
+impl Hasher for NameHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = self.0.rotate_left(8) ^ b as u64;
+        }
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.0 = self.0.rotate_left(5) ^ n as u64;
+    }
+}
+
+/// The counters of the operations keyed by the addresses and lengths of their
+/// type names.
+#[cfg(feature = "metrics")]
+type OpMap = HashMap<(usize, usize), (&'static str, OpMetrics), BuildHasherDefault<NameHasher>>;
+
+/// The counters of the operations. The per-operation counters are keyed by
+/// the addresses of the full type names, which are merged by the names without
+/// generic parameters in the snapshot.
+#[derive(Debug, Default)]
+pub(crate) struct OpCounters {
+    #[cfg(feature = "metrics")]
+    total: OpMetrics,
+    #[cfg(feature = "metrics")]
+    ops: Option<OpMap>,
+    pub polls: Counter,
+    pub poll_timeouts: Counter,
+}
+
+impl OpCounters {
+    #[cfg(feature = "metrics")]
+    pub fn new(builder: &ProactorBuilder) -> Self {
+        Self {
+            ops: builder.op_metrics.then(HashMap::default),
+            ..Self::default()
+        }
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    pub fn new(_builder: &crate::ProactorBuilder) -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn update<T: ?Sized>(&mut self, f: impl Fn(&mut OpMetrics)) {
+        f(&mut self.total);
+        if let Some(ops) = &mut self.ops {
+            let name = std::any::type_name::<T>();
+            let (_, op) = ops
+                .entry((name.as_ptr() as usize, name.len()))
+                .or_insert_with(|| (name, OpMetrics::default()));
+            f(op);
+        }
+    }
+
+    #[inline]
+    pub fn push<T: ?Sized>(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.update::<T>(|op| op.pushed += 1);
+    }
+
+    #[inline]
+    pub fn complete<T: ?Sized>(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.update::<T>(|op| op.completed += 1);
+    }
+
+    #[inline]
+    pub fn cancel<T: ?Sized>(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.update::<T>(|op| op.cancelled += 1);
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn fill(&self, metrics: &mut DriverMetrics) {
+        metrics.total = self.total;
+        for (name, op) in self.ops.iter().flat_map(|ops| ops.values()) {
+            let name = name.split_once('<').map_or(*name, |(name, _)| name);
+            let total = metrics.ops.entry(name).or_default();
+            total.pushed += op.pushed;
+            total.completed += op.completed;
+            total.cancelled += op.cancelled;
+        }
+        metrics.polls = self.polls.get();
+        metrics.poll_timeouts = self.poll_timeouts.get();
+    }
+}
@@ -4,9 +4,9 @@
 //! The operation itself doesn't perform anything.
 //! You need to pass them to [`crate::Proactor`], and poll the driver.
 
-use std::{io, marker::PhantomPinned, mem::ManuallyDrop, net::Shutdown};
+use std::{io, marker::PhantomPinned, mem::ManuallyDrop, net::Shutdown, time::Duration};
 
-use compio_buf::{BufResult, IntoInner, IoBuf, IoBufMut, SetBufInit};
+use compio_buf::{BufResult, IntoInner, IoBuf, IoBufMut, IoVectoredBuf, SetBufInit};
 use socket2::SockAddr;
 
 #[cfg(windows)]
@@ -17,16 +17,21 @@ pub use crate::sys::op::{
 };
 #[cfg(unix)]
 pub use crate::sys::op::{
-    CreateDir, CreateSocket, FileStat, HardLink, Interest, OpenFile, PathStat, PollOnce,
-    ReadVectoredAt, Rename, Symlink, Unlink, WriteVectoredAt,
+    AcceptMulti, CreateDir, CreateSocket, FileStat, HardLink, Interest, OpenFile, PathStat,
+    PollOnce, ReadVectoredAt, Rename, Symlink, Unlink, WriteVectoredAt,
 };
 #[cfg(io_uring)]
-pub use crate::sys::op::{ReadManagedAt, RecvManaged};
+pub use crate::sys::op::{ReadManagedAt, RecvManaged, RecvMulti};
+#[cfg(linux_all)]
+pub use crate::sys::op::{Splice, Tee};
 use crate::{
-    OwnedFd, TakeBuffer,
+    DispatchOptions, FixedFd, IoFixedBuf, OwnedFd, RawFd, TakeBuffer,
     sys::{sockaddr_storage, socklen_t},
 };
 
+mod view;
+pub use view::*;
+
 /// Trait to update the buffer length inside the [`BufResult`].
 pub trait BufResultExt {
     /// Call [`SetBufInit::set_buf_init`] if the result is [`Ok`].
@@ -120,10 +125,91 @@ impl<T: TakeBuffer> ResultTakeBuffer for (BufResult<usize, T>, u32) {
     }
 }
 
+/// Operations which complete with multiple results from one submission, e.g.,
+/// `AcceptMulti`.
+///
+/// The intermediate results are stored in the operation as they arrive, and
+/// taken by [`Proactor::pop_multishot`]. The operation then completes with the
+/// final result as a normal one. The results not taken before completion are
+/// still owned by the operation.
+///
+/// [`Proactor::pop_multishot`]: crate::Proactor::pop_multishot
+pub trait Multishot {
+    /// The type of the intermediate results.
+    type Item;
+
+    /// Take the earliest intermediate result.
+    fn pop_multishot(&mut self) -> Option<Self::Item>;
+}
+
+/// A buffer selected from the [`BufferPool`] by an intermediate result of
+/// `RecvMulti`. If it is dropped without being taken, the buffer is returned
+/// to the pool the next time the pool is checked for returned buffers.
+///
+/// [`BufferPool`]: crate::BufferPool
+pub struct SelectedBuffer {
+    #[cfg(io_uring)]
+    len: usize,
+    #[cfg(io_uring)]
+    flags: u32,
+    #[cfg(io_uring)]
+    recycled: Option<std::sync::Arc<crossbeam_queue::SegQueue<u16>>>,
+    #[cfg(not(io_uring))]
+    never: std::convert::Infallible,
+}
+
+impl SelectedBuffer {
+    #[cfg(io_uring)]
+    pub(crate) fn new(
+        len: usize,
+        flags: u32,
+        recycled: std::sync::Arc<crossbeam_queue::SegQueue<u16>>,
+    ) -> Self {
+        Self {
+            len,
+            flags,
+            recycled: Some(recycled),
+        }
+    }
+
+    /// Take the buffer from the pool which the operation is created with.
+    pub fn take(self, buffer_pool: &crate::BufferPool) -> io::Result<crate::BorrowedBuffer<'_>> {
+        #[cfg(io_uring)]
+        {
+            let mut this = self;
+            this.recycled = None;
+            #[cfg(fusion)]
+            let buffer_pool = buffer_pool.as_io_uring();
+            // Safety: the length is the result of the operation.
+            let res = unsafe { buffer_pool.get_buffer(this.flags, this.len) };
+            #[cfg(fusion)]
+            let res = res.map(crate::BorrowedBuffer::new_io_uring);
+            res
+        }
+        #[cfg(not(io_uring))]
+        {
+            let _ = buffer_pool;
+            match self.never {}
+        }
+    }
+}
+
+#[cfg(io_uring)]
+impl Drop for SelectedBuffer {
+    fn drop(&mut self) {
+        if let Some(recycled) = self.recycled.take() {
+            if let Some(buffer_id) = io_uring::cqueue::buffer_select(self.flags) {
+                recycled.push(buffer_id);
+            }
+        }
+    }
+}
+
 /// Spawn a blocking function in the thread pool.
 pub struct Asyncify<F, D> {
     pub(crate) f: Option<F>,
     pub(crate) data: Option<D>,
+    pub(crate) options: DispatchOptions,
     _p: PhantomPinned,
 }
 
@@ -133,9 +219,17 @@ impl<F, D> Asyncify<F, D> {
         Self {
             f: Some(f),
             data: None,
+            options: DispatchOptions::default(),
             _p: PhantomPinned,
         }
     }
+
+    /// Dispatch the function with the options, e.g., to another pool or with
+    /// a higher priority.
+    pub fn with_options(mut self, options: DispatchOptions) -> Self {
+        self.options = options;
+        self
+    }
 }
 
 impl<F, D> IntoInner for Asyncify<F, D> {
@@ -168,6 +262,8 @@ pub struct ReadAt<T: IoBufMut, S> {
     pub(crate) buffer: T,
     #[cfg(aio)]
     pub(crate) aiocb: libc::aiocb,
+    #[cfg(linux_aio)]
+    pub(crate) iocb: crate::sys::Iocb,
     _p: PhantomPinned,
 }
 
@@ -180,6 +276,8 @@ impl<T: IoBufMut, S> ReadAt<T, S> {
             buffer,
             #[cfg(aio)]
             aiocb: unsafe { std::mem::zeroed() },
+            #[cfg(linux_aio)]
+            iocb: Default::default(),
             _p: PhantomPinned,
         }
     }
@@ -201,6 +299,8 @@ pub struct WriteAt<T: IoBuf, S> {
     pub(crate) buffer: T,
     #[cfg(aio)]
     pub(crate) aiocb: libc::aiocb,
+    #[cfg(linux_aio)]
+    pub(crate) iocb: crate::sys::Iocb,
     _p: PhantomPinned,
 }
 
@@ -213,6 +313,8 @@ impl<T: IoBuf, S> WriteAt<T, S> {
             buffer,
             #[cfg(aio)]
             aiocb: unsafe { std::mem::zeroed() },
+            #[cfg(linux_aio)]
+            iocb: Default::default(),
             _p: PhantomPinned,
         }
     }
@@ -226,6 +328,207 @@ impl<T: IoBuf, S> IntoInner for WriteAt<T, S> {
     }
 }
 
+/// Read a file at specified position into a registered buffer, see
+/// [`FixedBuf`].
+///
+/// ## Platform specific
+/// * io-uring: it is `IORING_OP_READ_FIXED`.
+/// * IOCP & polling: it is the same as [`ReadAt`].
+///
+/// [`FixedBuf`]: crate::FixedBuf
+pub struct ReadFixedAt<T: IoBufMut + IoFixedBuf, S> {
+    pub(crate) op: ReadAt<T, S>,
+}
+
+impl<T: IoBufMut + IoFixedBuf, S> ReadFixedAt<T, S> {
+    /// Create [`ReadFixedAt`].
+    pub fn new(fd: S, offset: u64, buffer: T) -> Self {
+        Self {
+            op: ReadAt::new(fd, offset, buffer),
+        }
+    }
+}
+
+impl<T: IoBufMut + IoFixedBuf, S> IntoInner for ReadFixedAt<T, S> {
+    type Inner = T;
+
+    fn into_inner(self) -> Self::Inner {
+        self.op.into_inner()
+    }
+}
+
+/// Write a file at specified position from a registered buffer, see
+/// [`ReadFixedAt`].
+pub struct WriteFixedAt<T: IoFixedBuf, S> {
+    pub(crate) op: WriteAt<T, S>,
+}
+
+impl<T: IoFixedBuf, S> WriteFixedAt<T, S> {
+    /// Create [`WriteFixedAt`].
+    pub fn new(fd: S, offset: u64, buffer: T) -> Self {
+        Self {
+            op: WriteAt::new(fd, offset, buffer),
+        }
+    }
+}
+
+impl<T: IoFixedBuf, S> IntoInner for WriteFixedAt<T, S> {
+    type Inner = T;
+
+    fn into_inner(self) -> Self::Inner {
+        self.op.into_inner()
+    }
+}
+
+/// Send data to remote from a registered buffer, see [`ReadFixedAt`].
+pub struct SendFixed<T: IoFixedBuf, S> {
+    pub(crate) op: Send<T, S>,
+}
+
+impl<T: IoFixedBuf, S> SendFixed<T, S> {
+    /// Create [`SendFixed`].
+    pub fn new(fd: S, buffer: T) -> Self {
+        Self {
+            op: Send::new(fd, buffer),
+        }
+    }
+}
+
+impl<T: IoFixedBuf, S> IntoInner for SendFixed<T, S> {
+    type Inner = T;
+
+    fn into_inner(self) -> Self::Inner {
+        self.op.into_inner()
+    }
+}
+
+/// Send data to remote without copying the buffer into the kernel.
+///
+/// With io-uring, the operation completes after the kernel releases the
+/// buffer, and the result is the one of the send. On other drivers, it is the
+/// same as [`Send`].
+pub struct SendZc<T: IoBuf, S> {
+    pub(crate) op: Send<T, S>,
+    #[cfg(io_uring)]
+    pub(crate) res: Option<io::Result<usize>>,
+}
+
+impl<T: IoBuf, S> SendZc<T, S> {
+    /// Create [`SendZc`].
+    pub fn new(fd: S, buffer: T) -> Self {
+        Self {
+            op: Send::new(fd, buffer),
+            #[cfg(io_uring)]
+            res: None,
+        }
+    }
+}
+
+impl<T: IoBuf, S> IntoInner for SendZc<T, S> {
+    type Inner = T;
+
+    fn into_inner(self) -> Self::Inner {
+        self.op.into_inner()
+    }
+}
+
+/// Send data to specified address accompanied by ancillary data from vectored
+/// buffer, without copying the buffer into the kernel. See [`SendZc`].
+pub struct SendMsgZc<T: IoVectoredBuf, C: IoBuf, S> {
+    pub(crate) op: SendMsg<T, C, S>,
+    #[cfg(io_uring)]
+    pub(crate) res: Option<io::Result<usize>>,
+}
+
+impl<T: IoVectoredBuf, C: IoBuf, S> SendMsgZc<T, C, S> {
+    /// Create [`SendMsgZc`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the control message buffer is misaligned.
+    pub fn new(fd: S, buffer: T, control: C, addr: SockAddr) -> Self {
+        Self {
+            op: SendMsg::new(fd, buffer, control, addr),
+            #[cfg(io_uring)]
+            res: None,
+        }
+    }
+}
+
+impl<T: IoVectoredBuf, C: IoBuf, S> IntoInner for SendMsgZc<T, C, S> {
+    type Inner = (T, C);
+
+    fn into_inner(self) -> Self::Inner {
+        self.op.into_inner()
+    }
+}
+
+/// Post a value to the completion queue of another driver, whose fd is
+/// `ring`. The target receives it as [`RingMessage::Data`] from
+/// [`Proactor::pop_message`].
+///
+/// ## Platform specific
+/// * io-uring: it is `IORING_OP_MSG_RING`, which requires Linux 5.18, and the
+///   target should be an io-uring driver.
+/// * IOCP & polling: it fails with [`io::ErrorKind::Unsupported`].
+///
+/// [`RingMessage::Data`]: crate::RingMessage::Data
+/// [`Proactor::pop_message`]: crate::Proactor::pop_message
+pub struct MsgRing {
+    #[allow(dead_code)]
+    pub(crate) ring: RawFd,
+    #[allow(dead_code)]
+    pub(crate) data: u32,
+}
+
+impl MsgRing {
+    /// The io-uring opcode of [`MsgRing`] and [`MsgRingFd`], to check with
+    /// [`Proactor::supports`].
+    ///
+    /// [`Proactor::supports`]: crate::Proactor::supports
+    pub const OPCODE: u8 = 40;
+
+    /// Create [`MsgRing`].
+    pub fn new(ring: RawFd, data: u32) -> Self {
+        Self { ring, data }
+    }
+}
+
+/// Send a [`FixedFd`] to another driver, whose fd is `ring`. The target
+/// receives a direct descriptor in its fixed file table as
+/// [`RingMessage::Fd`] from [`Proactor::pop_message`]. The fd of the sender
+/// is kept, and returned after completion.
+///
+/// ## Platform specific
+/// * io-uring: it requires Linux 6.0. The fd should be in the fixed file table
+///   of the sender, and the table of the target should be enabled by
+///   [`ProactorBuilder::fixed_files`].
+/// * IOCP & polling: it fails with [`io::ErrorKind::Unsupported`].
+///
+/// [`RingMessage::Fd`]: crate::RingMessage::Fd
+/// [`Proactor::pop_message`]: crate::Proactor::pop_message
+/// [`ProactorBuilder::fixed_files`]: crate::ProactorBuilder::fixed_files
+pub struct MsgRingFd {
+    #[allow(dead_code)]
+    pub(crate) ring: RawFd,
+    pub(crate) fd: FixedFd,
+}
+
+impl MsgRingFd {
+    /// Create [`MsgRingFd`].
+    pub fn new(ring: RawFd, fd: FixedFd) -> Self {
+        Self { ring, fd }
+    }
+}
+
+impl IntoInner for MsgRingFd {
+    type Inner = FixedFd;
+
+    fn into_inner(self) -> Self::Inner {
+        self.fd
+    }
+}
+
 /// Sync data to the disk.
 pub struct Sync<S> {
     pub(crate) fd: S,
@@ -289,13 +592,45 @@ impl<S> Connect<S> {
     }
 }
 
+/// Wait until the specified duration elapses.
+///
+/// The operation completes with `Ok(0)` when the timer expires. An expired
+/// timer doesn't break a linked chain.
+///
+/// ## Platform specific
+/// * io-uring: it uses `IORING_TIMEOUT_ETIME_SUCCESS` since Linux 5.16. On
+///   older kernels, the linked chains with it are emulated.
+pub struct Timeout {
+    pub(crate) delay: Duration,
+    #[cfg(io_uring)]
+    pub(crate) timespec: io_uring::types::Timespec,
+    _p: PhantomPinned,
+}
+
+impl Timeout {
+    /// Create [`Timeout`].
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            #[cfg(io_uring)]
+            timespec: io_uring::types::Timespec::from(delay),
+            _p: PhantomPinned,
+        }
+    }
+
+    /// The duration to wait.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
 #[cfg(any(not(io_uring), fusion))]
 pub(crate) mod managed {
     use std::io;
 
     use compio_buf::IntoInner;
 
-    use super::{ReadAt, Recv};
+    use super::{Multishot, ReadAt, Recv, SelectedBuffer};
     use crate::{BorrowedBuffer, BufferPool, OwnedBuffer, TakeBuffer};
 
     /// Read a file at specified position into managed buffer.
@@ -373,6 +708,43 @@ pub(crate) mod managed {
             Ok(res)
         }
     }
+
+    /// Receive data from remote into managed buffers with one submission, see
+    /// [`Multishot`].
+    pub struct RecvMulti<S> {
+        pub(crate) op: RecvManaged<S>,
+    }
+
+    impl<S> RecvMulti<S> {
+        /// Create [`RecvMulti`].
+        pub fn new(fd: S, pool: &BufferPool) -> io::Result<Self> {
+            Ok(Self {
+                op: RecvManaged::new(fd, pool, 0)?,
+            })
+        }
+    }
+
+    impl<S> Multishot for RecvMulti<S> {
+        type Item = SelectedBuffer;
+
+        fn pop_multishot(&mut self) -> Option<Self::Item> {
+            None
+        }
+    }
+
+    impl<S> TakeBuffer for RecvMulti<S> {
+        type Buffer<'a> = BorrowedBuffer<'a>;
+        type BufferPool = BufferPool;
+
+        fn take_buffer(
+            self,
+            buffer_pool: &Self::BufferPool,
+            result: io::Result<usize>,
+            flags: u32,
+        ) -> io::Result<Self::Buffer<'_>> {
+            self.op.take_buffer(buffer_pool, result, flags)
+        }
+    }
 }
 
 #[cfg(not(io_uring))]
@@ -0,0 +1,476 @@
+//! Driver-independent descriptions of the operations, for custom
+//! [`Backend`]s.
+//!
+//! [`Backend`]: crate::Backend
+
+#[cfg(unix)]
+use std::{ffi::CStr, mem::MaybeUninit, pin::Pin};
+use std::{io, time::Duration};
+
+#[cfg(unix)]
+use compio_buf::{
+    BufResult, IoBuf, IoBufMut, IoSlice, IoSliceMut, IoVectoredBuf, IoVectoredBufMut,
+};
+#[cfg(unix)]
+use socket2::SockAddr;
+
+#[cfg(unix)]
+use crate::{
+    AsFdTarget, AsRawFd, RawFd,
+    op::*,
+    sys::{sockaddr_storage, socklen_t},
+};
+
+/// The description of an operation, which is got by [`Key::view`]. The buffers
+/// and the addresses are borrowed from the operation, and they are valid until
+/// the operation completes.
+///
+/// [`Key::view`]: crate::Key::view
+#[non_exhaustive]
+pub enum OpView<'a> {
+    /// A blocking function, e.g., [`Asyncify`]. Call it and complete the
+    /// operation with the result.
+    Blocking(Box<dyn FnOnce() -> io::Result<usize> + 'a>),
+    /// A timer, see [`Timeout`].
+    Timeout(Duration),
+    /// Open a file, see [`OpenFile`]. The result is the fd.
+    #[cfg(unix)]
+    Open {
+        /// The path.
+        path: &'a CStr,
+        /// The flags of `open(2)`.
+        flags: i32,
+        /// The mode of the created file.
+        mode: u32,
+    },
+    /// Close an fd, see [`CloseFile`] and [`CloseSocket`]. The fd is owned by
+    /// the backend now.
+    #[cfg(unix)]
+    Close(RawFd),
+    /// Read from an fd, at the offset if any, e.g., [`ReadAt`] and [`Recv`].
+    #[cfg(unix)]
+    Read {
+        /// The fd.
+        fd: RawFd,
+        /// The offset, or `None` for streams.
+        offset: Option<u64>,
+        /// The buffers to fill in order.
+        bufs: Vec<&'a mut [MaybeUninit<u8>]>,
+    },
+    /// Write to an fd, at the offset if any, e.g., [`WriteAt`] and [`Send`].
+    #[cfg(unix)]
+    Write {
+        /// The fd.
+        fd: RawFd,
+        /// The offset, or `None` for streams.
+        offset: Option<u64>,
+        /// The buffers to write in order.
+        bufs: Vec<&'a [u8]>,
+    },
+    /// Flush the data of a file, see [`Sync`].
+    #[cfg(unix)]
+    Sync {
+        /// The fd.
+        fd: RawFd,
+        /// Whether only the data is flushed.
+        datasync: bool,
+    },
+    /// Remove a file or directory, see [`Unlink`].
+    #[cfg(unix)]
+    Unlink {
+        /// The path.
+        path: &'a CStr,
+        /// Whether it is a directory.
+        dir: bool,
+    },
+    /// Create a directory, see [`CreateDir`].
+    #[cfg(unix)]
+    CreateDir {
+        /// The path.
+        path: &'a CStr,
+        /// The mode of the directory.
+        mode: u32,
+    },
+    /// Rename a file or directory, see [`Rename`].
+    #[cfg(unix)]
+    Rename {
+        /// The old path.
+        from: &'a CStr,
+        /// The new path.
+        to: &'a CStr,
+    },
+    /// Create a socket, see [`CreateSocket`]. The result is the fd.
+    #[cfg(unix)]
+    Socket {
+        /// The domain, e.g., `AF_INET`.
+        domain: i32,
+        /// The type, e.g., `SOCK_STREAM`.
+        ty: i32,
+        /// The protocol.
+        protocol: i32,
+    },
+    /// Connect a socket, see [`Connect`].
+    #[cfg(unix)]
+    Connect {
+        /// The fd.
+        fd: RawFd,
+        /// The remote address.
+        addr: &'a SockAddr,
+    },
+    /// Accept a connection, see [`Accept`]. The result is the accepted fd,
+    /// and the remote address should be set.
+    #[cfg(unix)]
+    Accept {
+        /// The fd of the listener.
+        fd: RawFd,
+        /// The remote address.
+        addr: AddrMut<'a>,
+    },
+    /// Receive a datagram and its source, see [`RecvFrom`].
+    #[cfg(unix)]
+    RecvFrom {
+        /// The fd.
+        fd: RawFd,
+        /// The buffers to fill in order.
+        bufs: Vec<&'a mut [MaybeUninit<u8>]>,
+        /// The source address.
+        addr: AddrMut<'a>,
+    },
+    /// Send a datagram to the address, see [`SendTo`].
+    #[cfg(unix)]
+    SendTo {
+        /// The fd.
+        fd: RawFd,
+        /// The buffers to send in order.
+        bufs: Vec<&'a [u8]>,
+        /// The target address.
+        addr: &'a SockAddr,
+    },
+    /// Shut down a socket, see [`ShutdownSocket`].
+    #[cfg(unix)]
+    Shutdown {
+        /// The fd.
+        fd: RawFd,
+        /// `SHUT_RD`, `SHUT_WR` or `SHUT_RDWR`.
+        how: i32,
+    },
+    /// Wait for an fd to be ready, see [`PollOnce`].
+    #[cfg(unix)]
+    PollOnce {
+        /// The fd.
+        fd: RawFd,
+        /// The interest.
+        interest: Interest,
+    },
+    /// Other operations, which are not described yet.
+    Other,
+}
+
+/// A socket address to be filled by an operation.
+#[cfg(unix)]
+pub struct AddrMut<'a> {
+    storage: &'a mut sockaddr_storage,
+    len: &'a mut socklen_t,
+}
+
+#[cfg(unix)]
+impl<'a> AddrMut<'a> {
+    pub(crate) fn new(storage: &'a mut sockaddr_storage, len: &'a mut socklen_t) -> Self {
+        Self { storage, len }
+    }
+
+    /// Set the address.
+    pub fn set(&mut self, addr: &SockAddr) {
+        let len = (addr.len() as usize).min(std::mem::size_of::<sockaddr_storage>());
+        // SAFETY: both are valid for `len` bytes.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                addr.as_ptr().cast::<u8>(),
+                (self.storage as *mut sockaddr_storage).cast::<u8>(),
+                len,
+            );
+        }
+        *self.len = len as _;
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn bufs_mut<'a>(slices: Vec<IoSliceMut>) -> Vec<&'a mut [MaybeUninit<u8>]> {
+    slices
+        .into_iter()
+        // SAFETY: the slices are borrowed from the buffers of the op.
+        .map(|s| unsafe { std::slice::from_raw_parts_mut(s.as_ptr(), s.len()) })
+        .collect()
+}
+
+#[cfg(unix)]
+pub(crate) fn bufs<'a>(slices: Vec<IoSlice>) -> Vec<&'a [u8]> {
+    slices
+        .into_iter()
+        // SAFETY: the slices are borrowed from the buffers of the op.
+        .map(|s| unsafe { std::slice::from_raw_parts(s.as_ptr(), s.len()) })
+        .collect()
+}
+
+/// Implement the `view` method of `OpCode` with the `op_view` description of
+/// the operation, which is shared by the drivers.
+#[cfg(unix)]
+macro_rules! op_view {
+    () => {
+        fn view(self: std::pin::Pin<&mut Self>) -> $crate::op::OpView<'_> {
+            self.op_view()
+        }
+    };
+}
+
+#[cfg(unix)]
+pub(crate) use op_view;
+
+#[cfg(unix)]
+impl<D, F: FnOnce() -> BufResult<usize, D>> Asyncify<F, D> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        // SAFETY: self won't be moved
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::Blocking(Box::new(move || {
+            let f = this
+                .f
+                .take()
+                .expect("the operate method could only be called once");
+            let BufResult(res, data) = f();
+            this.data = Some(data);
+            res
+        }))
+    }
+}
+
+#[cfg(unix)]
+impl OpenFile {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Open {
+            path: &this.path,
+            flags: this.flags,
+            mode: this.mode as _,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl CloseFile {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Close(this.fd.as_raw_fd())
+    }
+}
+
+#[cfg(unix)]
+impl CloseSocket {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Close(this.fd.as_raw_fd())
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoBufMut, S: AsFdTarget> ReadAt<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::Read {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: Some(this.offset),
+            bufs: vec![this.buffer.as_mut_slice()],
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoBuf, S: AsFdTarget> WriteAt<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Write {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: Some(this.offset),
+            bufs: vec![this.buffer.as_slice()],
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVectoredBufMut, S: AsFdTarget> ReadVectoredAt<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::Read {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: Some(this.offset),
+            bufs: bufs_mut(unsafe { this.buffer.io_slices_mut() }),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVectoredBuf, S: AsFdTarget> WriteVectoredAt<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Write {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: Some(this.offset),
+            bufs: bufs(unsafe { this.buffer.io_slices() }),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<S: AsFdTarget> Sync<S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Sync {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            datasync: this.datasync,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Unlink {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Unlink {
+            path: &this.path,
+            dir: this.dir,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl CreateDir {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::CreateDir {
+            path: &this.path,
+            mode: this.mode as _,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Rename {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Rename {
+            from: &this.old_path,
+            to: &this.new_path,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl CreateSocket {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Socket {
+            domain: this.domain,
+            ty: this.socket_type,
+            protocol: this.protocol,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<S: AsFdTarget> ShutdownSocket<S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Shutdown {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            how: this.how(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<S: AsFdTarget> Connect<S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Connect {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            addr: &this.addr,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<S: AsFdTarget> Accept<S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::Accept {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            addr: AddrMut::new(&mut this.buffer, &mut this.addr_len),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoBufMut, S: AsFdTarget> Recv<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::Read {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: None,
+            bufs: vec![this.buffer.as_mut_slice()],
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVectoredBufMut, S: AsFdTarget> RecvVectored<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::Read {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: None,
+            bufs: bufs_mut(unsafe { this.buffer.io_slices_mut() }),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoBuf, S: AsFdTarget> Send<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Write {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: None,
+            bufs: vec![this.buffer.as_slice()],
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVectoredBuf, S: AsFdTarget> SendVectored<T, S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Write {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            offset: None,
+            bufs: bufs(unsafe { this.buffer.io_slices() }),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<S: AsFdTarget> PollOnce<S> {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::PollOnce {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            interest: this.interest,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Timeout {
+    pub(crate) fn op_view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = self.into_ref().get_ref();
+        OpView::Timeout(this.delay)
+    }
+}
@@ -0,0 +1,205 @@
+//! Linux native AIO for files opened with `O_DIRECT`. The completions are
+//! notified by an eventfd registered in the poller.
+
+use std::{
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+};
+
+use compio_log::trace;
+use polling::{Event, PollMode, Poller};
+
+use crate::{Entry, cancelled_error, syscall};
+
+/// The key of the events of the eventfd.
+pub(super) const EVENT_KEY: usize = usize::MAX - 1;
+
+const IOCB_CMD_PREAD: u16 = 0;
+const IOCB_CMD_PWRITE: u16 = 1;
+const IOCB_CMD_PREADV: u16 = 7;
+const IOCB_CMD_PWRITEV: u16 = 8;
+
+const IOCB_FLAG_RESFD: u32 = 1;
+
+/// The control block of a Linux AIO operation, see [`Decision::linux_aio`].
+///
+/// [`Decision::linux_aio`]: super::Decision::linux_aio
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct Iocb {
+    aio_data: u64,
+    #[cfg(target_endian = "little")]
+    aio_key: u32,
+    aio_rw_flags: i32,
+    #[cfg(target_endian = "big")]
+    aio_key: u32,
+    aio_lio_opcode: u16,
+    aio_reqprio: i16,
+    aio_fildes: u32,
+    aio_buf: u64,
+    aio_nbytes: u64,
+    aio_offset: i64,
+    aio_reserved2: u64,
+    aio_flags: u32,
+    aio_resfd: u32,
+}
+
+impl Iocb {
+    fn new(opcode: u16, fd: RawFd, buf: u64, nbytes: usize, offset: u64) -> Self {
+        Self {
+            aio_lio_opcode: opcode,
+            aio_fildes: fd as _,
+            aio_buf: buf,
+            aio_nbytes: nbytes as _,
+            aio_offset: offset as _,
+            ..Default::default()
+        }
+    }
+
+    /// Read into the buffer at the offset.
+    pub fn pread(fd: RawFd, buf: *mut u8, len: usize, offset: u64) -> Self {
+        Self::new(IOCB_CMD_PREAD, fd, buf as _, len, offset)
+    }
+
+    /// Write the buffer at the offset.
+    pub fn pwrite(fd: RawFd, buf: *const u8, len: usize, offset: u64) -> Self {
+        Self::new(IOCB_CMD_PWRITE, fd, buf as _, len, offset)
+    }
+
+    /// Read into the `iovec`s at the offset.
+    pub fn preadv(fd: RawFd, iov: *const libc::iovec, len: usize, offset: u64) -> Self {
+        Self::new(IOCB_CMD_PREADV, fd, iov as _, len, offset)
+    }
+
+    /// Write the `iovec`s at the offset.
+    pub fn pwritev(fd: RawFd, iov: *const libc::iovec, len: usize, offset: u64) -> Self {
+        Self::new(IOCB_CMD_PWRITEV, fd, iov as _, len, offset)
+    }
+
+    pub(super) fn fd(&self) -> RawFd {
+        self.aio_fildes as _
+    }
+}
+
+/// Whether the fd is opened with `O_DIRECT`. The buffered IO of Linux AIO is
+/// actually blocking.
+pub(super) fn is_direct(fd: RawFd) -> bool {
+    syscall!(libc::fcntl(fd, libc::F_GETFL)).is_ok_and(|flags| flags & libc::O_DIRECT != 0)
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct IoEvent {
+    data: u64,
+    obj: u64,
+    res: i64,
+    res2: i64,
+}
+
+/// The AIO context of a driver.
+pub(super) struct LinuxAio {
+    ctx: libc::c_ulong,
+    eventfd: OwnedFd,
+    events: Vec<IoEvent>,
+}
+
+impl LinuxAio {
+    pub fn new(poll: &Poller, entries: u32) -> io::Result<Self> {
+        let eventfd = syscall!(libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK))?;
+        let eventfd = unsafe { OwnedFd::from_raw_fd(eventfd) };
+        let mut ctx: libc::c_ulong = 0;
+        syscall!(libc::syscall(
+            libc::SYS_io_setup,
+            entries.max(1) as libc::c_long,
+            &mut ctx
+        ))?;
+        let this = Self {
+            ctx,
+            eventfd,
+            events: vec![IoEvent::default(); entries.clamp(1, 256) as usize],
+        };
+        unsafe {
+            poll.add_with_mode(
+                this.eventfd.as_raw_fd(),
+                Event::readable(EVENT_KEY),
+                PollMode::Level,
+            )?;
+        }
+        Ok(this)
+    }
+
+    pub fn submit(&mut self, iocb: &mut Iocb, user_data: usize) -> io::Result<()> {
+        iocb.aio_data = user_data as _;
+        iocb.aio_flags = IOCB_FLAG_RESFD;
+        iocb.aio_resfd = self.eventfd.as_raw_fd() as _;
+        let mut iocbs = [iocb as *mut Iocb];
+        syscall!(libc::syscall(
+            libc::SYS_io_submit,
+            self.ctx,
+            1 as libc::c_long,
+            iocbs.as_mut_ptr()
+        ))?;
+        Ok(())
+    }
+
+    /// Try to cancel the operation. If it is cancelled, the completion is
+    /// still delivered with `ECANCELED`.
+    pub fn cancel(&mut self, iocb: *mut Iocb) {
+        let mut event = IoEvent::default();
+        syscall!(libc::syscall(
+            libc::SYS_io_cancel,
+            self.ctx,
+            iocb,
+            &mut event
+        ))
+        .ok();
+    }
+
+    /// Notify the completed operations.
+    ///
+    /// # Safety
+    /// The operations submitted should be valid.
+    pub unsafe fn complete(&mut self) -> io::Result<()> {
+        let mut count = 0u64;
+        syscall!(libc::read(
+            self.eventfd.as_raw_fd(),
+            (&mut count as *mut u64).cast(),
+            size_of::<u64>()
+        ))
+        .ok();
+        let mut timeout = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        loop {
+            let len = syscall!(libc::syscall(
+                libc::SYS_io_getevents,
+                self.ctx,
+                0 as libc::c_long,
+                self.events.len() as libc::c_long,
+                self.events.as_mut_ptr(),
+                &mut timeout
+            ))? as usize;
+            for event in &self.events[..len] {
+                let user_data = event.data as usize;
+                trace!("aio {} completed with {}", user_data, event.res);
+                let res = match event.res {
+                    res if res >= 0 => Ok(res as usize),
+                    res if res == -(libc::ECANCELED as i64) => Err(cancelled_error()),
+                    res => Err(io::Error::from_raw_os_error(-res as i32)),
+                };
+                Entry::new(user_data, res).notify();
+            }
+            if len < self.events.len() {
+                break Ok(());
+            }
+        }
+    }
+}
+
+impl Drop for LinuxAio {
+    fn drop(&mut self) {
+        // Waits for the operations in flight.
+        syscall!(libc::syscall(libc::SYS_io_destroy, self.ctx)).ok();
+    }
+}
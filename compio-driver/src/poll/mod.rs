@@ -1,27 +1,44 @@
+#[cfg(linux_aio)]
+use std::collections::HashSet;
 #[cfg_attr(all(doc, docsrs), doc(cfg(all())))]
 #[allow(unused_imports)]
 pub use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
-#[cfg(aio)]
+#[cfg(any(aio, linux_aio))]
 use std::ptr::NonNull;
 use std::{
     collections::{HashMap, VecDeque},
     io,
     num::NonZeroUsize,
+    os::fd::FromRawFd,
     pin::Pin,
     sync::Arc,
     task::Poll,
     time::Duration,
 };
 
-use compio_log::{instrument, trace};
+use compio_log::{instrument, trace, warn};
 use crossbeam_queue::SegQueue;
 pub(crate) use libc::{sockaddr_storage, socklen_t};
-use polling::{Event, Events, Poller};
-
-use crate::{AsyncifyPool, BufferPool, Entry, Key, ProactorBuilder, op::Interest, syscall};
+use polling::{Event, Events, PollMode, Poller};
+
+#[cfg(feature = "metrics")]
+use crate::DriverMetrics;
+use crate::{
+    BlockingOps, BufferPool, DispatchOptions, Entry, FixedFd, Key, ProactorBuilder,
+    RegisteredBuffers, RingMessage, SetupReport, cancelled_error,
+    metrics::{Counter, DriverCounters, SharedCounter},
+    op::{Interest, OpView},
+    syscall,
+    timer::TimerQueue,
+};
 
+#[cfg(linux_aio)]
+mod linux_aio;
 pub(crate) mod op;
 
+#[cfg(linux_aio)]
+pub use linux_aio::Iocb;
+
 /// Abstraction of operations.
 pub trait OpCode {
     /// Perform the operation before submit, and return [`Decision`] to
@@ -37,6 +54,22 @@ pub trait OpCode {
     /// event. If this operation is blocking, the return value should be
     /// [`Poll::Ready`].
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>>;
+
+    /// Where and how the operation is dispatched, if
+    /// [`OpCode::pre_submit`] returns [`Decision::Blocking`]. `None` for the
+    /// options of the built-in operations, see
+    /// [`ProactorBuilder::op_dispatch`].
+    fn dispatch_options(self: Pin<&mut Self>) -> Option<DispatchOptions> {
+        None
+    }
+
+    /// Describe the operation for a custom [`Backend`]. The operations which
+    /// are not described are [`OpView::Other`].
+    ///
+    /// [`Backend`]: crate::Backend
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        OpView::Other
+    }
 }
 
 /// Result of [`OpCode::pre_submit`].
@@ -48,9 +81,15 @@ pub enum Decision {
     Wait(WaitArg),
     /// Blocking operation, needs to be spawned in another thread
     Blocking,
+    /// Timer operation, completes after the duration elapses
+    Timer(Duration),
     /// AIO operation, needs to be spawned to the kernel.
     #[cfg(aio)]
     Aio(AioControl),
+    /// Linux AIO operation, needs to be submitted to the kernel if the fd is
+    /// attached and opened with `O_DIRECT`, otherwise it is blocking.
+    #[cfg(linux_aio)]
+    LinuxAio(NonNull<Iocb>),
 }
 
 impl Decision {
@@ -80,6 +119,14 @@ impl Decision {
             submit,
         })
     }
+
+    /// Decide to submit a Linux AIO operation. The operation is spawned to
+    /// another thread like [`Decision::Blocking`] if the fd is not attached,
+    /// not opened with `O_DIRECT`, or Linux AIO is not available.
+    #[cfg(linux_aio)]
+    pub fn linux_aio(cb: &mut Iocb) -> Self {
+        Self::LinuxAio(NonNull::from(cb))
+    }
 }
 
 /// Meta of polling operations.
@@ -101,6 +148,9 @@ pub struct AioControl {
     pub submit: unsafe extern "C" fn(*mut libc::aiocb) -> i32,
 }
 
+/// The queues of the operations waiting for an fd. In edge-triggered mode, the
+/// queue is kept in the registry after the operations complete, together with
+/// the registration in the poller.
 #[derive(Debug, Default)]
 struct FdQueue {
     read_queue: VecDeque<usize>,
@@ -122,21 +172,25 @@ impl FdQueue {
         }
     }
 
-    pub fn remove(&mut self, user_data: usize) {
+    // Return whether the op was waiting.
+    pub fn remove(&mut self, user_data: usize) -> bool {
+        let len = self.read_queue.len() + self.write_queue.len();
         self.read_queue.retain(|&k| k != user_data);
         self.write_queue.retain(|&k| k != user_data);
+        self.read_queue.len() + self.write_queue.len() != len
     }
 
-    pub fn event(&self) -> Event {
-        let mut event = Event::none(0);
-        if let Some(&key) = self.read_queue.front() {
-            event.readable = true;
-            event.key = key;
-        }
-        if let Some(&key) = self.write_queue.front() {
-            event.writable = true;
-            event.key = key;
+    pub fn is_waiting(&self, interest: Interest) -> bool {
+        match interest {
+            Interest::Readable => !self.read_queue.is_empty(),
+            Interest::Writable => !self.write_queue.is_empty(),
         }
+    }
+
+    pub fn event(&self, fd: RawFd) -> Event {
+        let mut event = Event::none(fd_key(fd));
+        event.readable = !self.read_queue.is_empty();
+        event.writable = !self.write_queue.is_empty();
         event
     }
 
@@ -153,6 +207,43 @@ impl FdQueue {
         }
         None
     }
+
+    /// Perform the operations in the queues of the event, until one of them is
+    /// not ready.
+    ///
+    /// # Safety
+    /// The operations in the queues should be valid.
+    pub unsafe fn drain(&mut self, event: &Event) {
+        if event.readable {
+            Self::drain_queue(&mut self.read_queue);
+        }
+        if event.writable {
+            Self::drain_queue(&mut self.write_queue);
+        }
+    }
+
+    unsafe fn drain_queue(queue: &mut VecDeque<usize>) {
+        while let Some(&user_data) = queue.front() {
+            let mut op = Key::<dyn crate::sys::OpCode>::new_unchecked(user_data);
+            match op.as_op_pin().operate() {
+                Poll::Pending => break,
+                Poll::Ready(res) => {
+                    queue.pop_front();
+                    Entry::new(user_data, res).notify();
+                }
+            }
+        }
+    }
+}
+
+/// The key of the events of an fd. The keys of AIO events are the user data,
+/// which are aligned pointers, so the lowest bit tells them apart.
+fn fd_key(fd: RawFd) -> usize {
+    ((fd as usize) << 1) | 1
+}
+
+fn key_fd(key: usize) -> Option<RawFd> {
+    (key & 1 == 1).then_some((key >> 1) as RawFd)
 }
 
 /// Represents the filter type of kqueue. `polling` crate doesn't expose such
@@ -164,6 +255,11 @@ pub enum OpType {
     /// The operation submits an AIO.
     #[cfg(aio)]
     Aio(NonNull<libc::aiocb>),
+    /// The operation submits a Linux AIO.
+    #[cfg(linux_aio)]
+    LinuxAio(NonNull<Iocb>),
+    /// The operation waits for a timer.
+    Timer,
 }
 
 /// Low-level driver of polling.
@@ -171,8 +267,22 @@ pub(crate) struct Driver {
     events: Events,
     poll: Arc<Poller>,
     registry: HashMap<RawFd, FdQueue>,
-    pool: AsyncifyPool,
+    // Whether the fds are registered persistently in edge-triggered mode. If
+    // not supported, they are registered in oneshot mode for every operation.
+    edge: bool,
+    timers: TimerQueue,
+    // Created when the first operation on an `O_DIRECT` fd is pushed, and
+    // `None` inside if Linux AIO is not available.
+    #[cfg(linux_aio)]
+    linux_aio: Option<Option<linux_aio::LinuxAio>>,
+    #[cfg(linux_aio)]
+    capacity: u32,
+    // The attached fds opened with `O_DIRECT`.
+    #[cfg(linux_aio)]
+    direct_fds: HashSet<RawFd>,
+    blocking: BlockingOps,
     pool_completed: Arc<SegQueue<Entry>>,
+    counters: DriverCounters,
 }
 
 impl Driver {
@@ -187,13 +297,29 @@ impl Driver {
         };
 
         let poll = Arc::new(Poller::new()?);
+        let edge = poll.supports_edge();
+        let counters = DriverCounters::default();
+        // Notified when the pools could accept the operations in the backlog.
+        let handle = NotifyHandle::new(poll.clone(), counters.notified.clone());
+        let blocking = BlockingOps::new(builder, move || {
+            handle.notify().ok();
+        });
 
         Ok(Self {
             events,
             poll,
             registry: HashMap::new(),
-            pool: builder.create_or_get_thread_pool(),
+            edge,
+            timers: TimerQueue::new(),
+            #[cfg(linux_aio)]
+            linux_aio: None,
+            #[cfg(linux_aio)]
+            capacity: builder.capacity,
+            #[cfg(linux_aio)]
+            direct_fds: HashSet::new(),
+            blocking,
             pool_completed: Arc::new(SegQueue::new()),
+            counters,
         })
     }
 
@@ -204,25 +330,68 @@ impl Driver {
     /// # Safety
     /// The input fd should be valid.
     unsafe fn submit(&mut self, user_data: usize, arg: WaitArg) -> io::Result<()> {
-        let need_add = !self.registry.contains_key(&arg.fd);
+        let registered = self.registry.contains_key(&arg.fd);
         let queue = self.registry.entry(arg.fd).or_default();
+        let waiting = queue.is_waiting(arg.interest);
         queue.push_back_interest(user_data, arg.interest);
-        let event = queue.event();
-        if need_add {
-            self.poll.add(arg.fd, event)?;
+        let res = if !self.edge {
+            let event = queue.event(arg.fd);
+            self.counters.registry_updates.inc();
+            if registered {
+                self.poll.modify(BorrowedFd::borrow_raw(arg.fd), event)
+            } else {
+                self.poll.add(arg.fd, event)
+            }
+        } else if !waiting {
+            // The edges are ignored when no operation waits for them, so the
+            // readiness should be reported again.
+            self.arm(arg.fd, registered)
         } else {
-            let fd = BorrowedFd::borrow_raw(arg.fd);
-            self.poll.modify(fd, event)?;
+            // The operations in front of it haven't seen the edge yet.
+            Ok(())
+        };
+        if res.is_err() {
+            if registered {
+                if let Some(queue) = self.registry.get_mut(&arg.fd) {
+                    queue.remove(user_data);
+                }
+            } else {
+                self.registry.remove(&arg.fd);
+            }
         }
-        Ok(())
+        res
+    }
+
+    /// Register the fd in edge-triggered mode, or modify the existing
+    /// registration to report the current readiness.
+    ///
+    /// # Safety
+    /// The input fd should be valid.
+    unsafe fn arm(&mut self, fd: RawFd, registered: bool) -> io::Result<()> {
+        let event = Event::all(fd_key(fd));
+        if registered {
+            self.counters.registry_updates.inc();
+            match self
+                .poll
+                .modify_with_mode(BorrowedFd::borrow_raw(fd), event, PollMode::Edge)
+            {
+                // The fd was closed without `cancel_fd`, and the number is reused.
+                Err(e) if e.raw_os_error() == Some(libc::ENOENT) => {}
+                res => return res,
+            }
+        }
+        self.counters.registry_updates.inc();
+        self.poll.add_with_mode(fd, event, PollMode::Edge)
     }
 
     fn renew(
         poll: &Poller,
         registry: &mut HashMap<RawFd, FdQueue>,
+        updates: &mut Counter,
         fd: BorrowedFd,
         renew_event: Event,
     ) -> io::Result<()> {
+        updates.inc();
         if !renew_event.readable && !renew_event.writable {
             poll.delete(fd)?;
             registry.remove(&fd.as_raw_fd());
@@ -233,30 +402,81 @@ impl Driver {
     }
 
     pub fn attach(&mut self, _fd: RawFd) -> io::Result<()> {
+        // The fd number may be reused, so the flag is refreshed on every attach.
+        #[cfg(linux_aio)]
+        if linux_aio::is_direct(_fd) {
+            self.direct_fds.insert(_fd);
+        } else {
+            self.direct_fds.remove(&_fd);
+        }
         Ok(())
     }
 
+    pub fn register_buffers(&mut self, _buffers: &RegisteredBuffers) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn supports(&self, _opcode: u8) -> bool {
+        false
+    }
+
+    pub fn setup_report(&self) -> SetupReport {
+        SetupReport::default()
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> DriverMetrics {
+        self.counters.snapshot(self.blocking.pool())
+    }
+
+    pub fn pop_message(&mut self) -> Option<RingMessage> {
+        None
+    }
+
+    pub fn register_fd(&mut self, fd: OwnedFd) -> io::Result<FixedFd> {
+        Ok(FixedFd::from_fd(fd))
+    }
+
+    pub fn direct_fd(&mut self, res: usize) -> FixedFd {
+        FixedFd::from_fd(unsafe { OwnedFd::from_raw_fd(res as _) })
+    }
+
     pub fn cancel(&mut self, op: &mut Key<dyn crate::sys::OpCode>) {
         let op_pin = op.as_op_pin();
         match op_pin.op_type() {
-            None => {}
+            None => {
+                // Only a queued blocking operation could be cancelled.
+                if !op.is_blocking() {
+                    return;
+                }
+                let user_data = op.user_data();
+                let options = OpCode::dispatch_options(op.as_op_pin());
+                if self.blocking.cancel(user_data, options.as_ref()) {
+                    self.pool_completed.push(entry_cancelled(user_data));
+                }
+            }
             Some(OpType::Fd(fd)) => {
-                let queue = self
-                    .registry
-                    .get_mut(&fd)
-                    .expect("the fd should be attached");
-                queue.remove(op.user_data());
-                let renew_event = queue.event();
-                if Self::renew(
-                    &self.poll,
-                    &mut self.registry,
-                    unsafe { BorrowedFd::borrow_raw(fd) },
-                    renew_event,
-                )
-                .is_ok()
-                {
-                    self.pool_completed.push(entry_cancelled(op.user_data()));
+                let Some(queue) = self.registry.get_mut(&fd) else {
+                    return;
+                };
+                // The op may have been cancelled or completed.
+                if !queue.remove(op.user_data()) {
+                    return;
+                }
+                let renew_event = queue.event(fd);
+                // The persistent registration is kept in edge-triggered mode.
+                if !self.edge {
+                    if let Err(_e) = Self::renew(
+                        &self.poll,
+                        &mut self.registry,
+                        &mut self.counters.registry_updates,
+                        unsafe { BorrowedFd::borrow_raw(fd) },
+                        renew_event,
+                    ) {
+                        warn!("cannot renew the registration of fd {fd}: {_e}");
+                    }
                 }
+                self.pool_completed.push(entry_cancelled(op.user_data()));
             }
             #[cfg(aio)]
             Some(OpType::Aio(aiocbp)) => {
@@ -264,9 +484,53 @@ impl Driver {
                 let fd = aiocb.aio_fildes;
                 syscall!(libc::aio_cancel(fd, aiocbp.as_ptr())).ok();
             }
+            #[cfg(linux_aio)]
+            Some(OpType::LinuxAio(iocbp)) => {
+                if let Some(Some(aio)) = &mut self.linux_aio {
+                    aio.cancel(iocbp.as_ptr());
+                }
+            }
+            Some(OpType::Timer) => {
+                let user_data = op.user_data();
+                if self.timers.remove(user_data) {
+                    self.pool_completed.push(entry_cancelled(user_data));
+                }
+            }
         }
     }
 
+    pub fn cancel_fd(&mut self, fd: RawFd) {
+        trace!("cancel fd {fd}");
+        #[cfg(linux_aio)]
+        self.direct_fds.remove(&fd);
+        if let Some(queue) = self.registry.remove(&fd) {
+            // SAFETY: the fd is registered, and still open.
+            self.poll.delete(unsafe { BorrowedFd::borrow_raw(fd) }).ok();
+            for user_data in queue.read_queue.into_iter().chain(queue.write_queue) {
+                self.pool_completed
+                    .push(Entry::new(user_data, Err(cancelled_error())));
+            }
+        }
+        #[cfg(aio)]
+        unsafe {
+            libc::aio_cancel(fd, std::ptr::null_mut());
+        }
+    }
+
+    /// The operations are tried eagerly one by one, and only the ones not ready
+    /// are registered.
+    pub fn push_batch(
+        &mut self,
+        ops: &mut [Key<dyn crate::sys::OpCode>],
+    ) -> Vec<Poll<io::Result<usize>>> {
+        ops.iter_mut().map(|op| self.push(op)).collect()
+    }
+
+    pub fn push_link(&mut self, _ops: &[crate::LinkedOp]) -> io::Result<bool> {
+        // Linked operations are emulated by the proactor.
+        Ok(false)
+    }
+
     pub fn push(&mut self, op: &mut Key<dyn crate::sys::OpCode>) -> Poll<io::Result<usize>> {
         instrument!(compio_log::Level::TRACE, "push", ?op);
         let user_data = op.user_data();
@@ -281,7 +545,11 @@ impl Driver {
                 Poll::Pending
             }
             Decision::Completed(res) => Poll::Ready(Ok(res)),
-            Decision::Blocking => self.push_blocking(user_data),
+            Decision::Blocking => self.push_blocking(op),
+            Decision::Timer(delay) => {
+                self.timers.insert(user_data, delay);
+                Poll::Pending
+            }
             #[cfg(aio)]
             Decision::Aio(AioControl { mut aiocbp, submit }) => {
                 let aiocb = unsafe { aiocbp.as_mut() };
@@ -317,7 +585,31 @@ impl Driver {
                             Some(libc::EOPNOTSUPP) | Some(libc::EAGAIN)
                         ) =>
                     {
-                        self.push_blocking(user_data)
+                        self.push_blocking(op)
+                    }
+                    Err(e) => Poll::Ready(Err(e)),
+                }
+            }
+            #[cfg(linux_aio)]
+            Decision::LinuxAio(mut iocbp) => {
+                let iocb = unsafe { iocbp.as_mut() };
+                if !self.direct_fds.contains(&iocb.fd()) {
+                    return self.push_blocking(op);
+                }
+                let Some(aio) = self.linux_aio() else {
+                    return self.push_blocking(op);
+                };
+                match aio.submit(iocb, user_data) {
+                    Ok(()) => Poll::Pending,
+                    // * EAGAIN: The context is full.
+                    // * EINVAL & EOPNOTSUPP: The file doesn't support AIO.
+                    Err(e)
+                        if matches!(
+                            e.raw_os_error(),
+                            Some(libc::EAGAIN) | Some(libc::EINVAL) | Some(libc::EOPNOTSUPP)
+                        ) =>
+                    {
+                        self.push_blocking(op)
                     }
                     Err(e) => Poll::Ready(Err(e)),
                 }
@@ -325,10 +617,24 @@ impl Driver {
         }
     }
 
-    fn push_blocking(&mut self, user_data: usize) -> Poll<io::Result<usize>> {
-        let poll = self.poll.clone();
+    #[cfg(linux_aio)]
+    fn linux_aio(&mut self) -> Option<&mut linux_aio::LinuxAio> {
+        self.linux_aio
+            .get_or_insert_with(|| {
+                linux_aio::LinuxAio::new(&self.poll, self.capacity)
+                    .map_err(|_e| {
+                        trace!("Linux AIO is not available: {_e}");
+                    })
+                    .ok()
+            })
+            .as_mut()
+    }
+
+    fn push_blocking(&mut self, op: &mut Key<dyn crate::sys::OpCode>) -> Poll<io::Result<usize>> {
+        let user_data = op.user_data();
+        let handle = self.handle();
         let completed = self.pool_completed.clone();
-        let mut closure = move || {
+        let closure = move || {
             let mut op = unsafe { Key::<dyn crate::sys::OpCode>::new_unchecked(user_data) };
             let op_pin = op.as_op_pin();
             let res = match op_pin.operate() {
@@ -336,17 +642,14 @@ impl Driver {
                 Poll::Ready(res) => res,
             };
             completed.push(Entry::new(user_data, res));
-            poll.notify().ok();
+            handle.notify().ok();
         };
-        loop {
-            match self.pool.dispatch(closure) {
-                Ok(()) => return Poll::Pending,
-                Err(e) => {
-                    closure = e.0;
-                    self.poll_blocking();
-                }
-            }
-        }
+        let options = OpCode::dispatch_options(op.as_op_pin());
+        op.set_blocking();
+        // The operation waits in the backlog if the pool is full.
+        let dispatched = self.blocking.dispatch(user_data, options, closure);
+        self.counters.blocking.add(dispatched);
+        Poll::Pending
     }
 
     fn poll_blocking(&mut self) -> bool {
@@ -361,34 +664,49 @@ impl Driver {
         true
     }
 
+    fn poll_timers(&mut self) -> bool {
+        let expired = self.timers.pop_expired();
+        let has_expired = !expired.is_empty();
+        for user_data in expired {
+            unsafe {
+                Entry::new(user_data, Ok(0)).notify();
+            }
+        }
+        has_expired
+    }
+
     pub unsafe fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()> {
         instrument!(compio_log::Level::TRACE, "poll", ?timeout);
+        let dispatched = self.blocking.retry();
+        self.counters.blocking.add(dispatched);
         if self.poll_blocking() {
             return Ok(());
         }
         self.events.clear();
-        self.poll.wait(&mut self.events, timeout)?;
-        if self.events.is_empty() && timeout.is_some() {
+        self.poll
+            .wait(&mut self.events, self.timers.wait_timeout(timeout))?;
+        let has_expired = self.poll_timers();
+        if self.events.is_empty() && !has_expired && timeout.is_some() {
             return Err(io::Error::from_raw_os_error(libc::ETIMEDOUT));
         }
         for event in self.events.iter() {
-            let user_data = event.key;
-            trace!("receive {} for {:?}", user_data, event);
-            let mut op = Key::<dyn crate::sys::OpCode>::new_unchecked(user_data);
-            let op = op.as_op_pin();
-            match op.op_type() {
-                None => {
-                    // On epoll, multiple event may be received even if it is registered as
-                    // one-shot. It is safe to ignore it.
-                    trace!("op {} is completed", user_data);
+            trace!("receive {:?}", event);
+            #[cfg(linux_aio)]
+            if event.key == linux_aio::EVENT_KEY {
+                if let Some(Some(aio)) = &mut self.linux_aio {
+                    aio.complete()?;
                 }
-                Some(OpType::Fd(fd)) => {
-                    // If it's an FD op, the returned user_data is only for calling `op_type`. We
-                    // need to pop the real user_data from the queue.
-                    let queue = self
-                        .registry
-                        .get_mut(&fd)
-                        .expect("the fd should be attached");
+                continue;
+            }
+            if let Some(fd) = key_fd(event.key) {
+                // The fd may have been deregistered by `cancel_fd`, and the
+                // event is stale.
+                let Some(queue) = self.registry.get_mut(&fd) else {
+                    continue;
+                };
+                if self.edge {
+                    queue.drain(&event);
+                } else {
                     if let Some((user_data, interest)) = queue.pop_interest(&event) {
                         let mut op = Key::<dyn crate::sys::OpCode>::new_unchecked(user_data);
                         let op = op.as_op_pin();
@@ -404,14 +722,31 @@ impl Driver {
                             Entry::new(user_data, res).notify();
                         }
                     }
-                    let renew_event = queue.event();
+                    let renew_event = queue.event(fd);
                     Self::renew(
                         &self.poll,
                         &mut self.registry,
+                        &mut self.counters.registry_updates,
                         BorrowedFd::borrow_raw(fd),
                         renew_event,
                     )?;
                 }
+                continue;
+            }
+            let user_data = event.key;
+            let mut op = Key::<dyn crate::sys::OpCode>::new_unchecked(user_data);
+            let op = op.as_op_pin();
+            match op.op_type() {
+                None | Some(OpType::Fd(_)) => {
+                    trace!("op {} is completed", user_data);
+                }
+                Some(OpType::Timer) => {
+                    trace!("op {} is a timer", user_data);
+                }
+                #[cfg(linux_aio)]
+                Some(OpType::LinuxAio(_)) => {
+                    trace!("op {} is notified by the eventfd", user_data);
+                }
                 #[cfg(aio)]
                 Some(OpType::Aio(aiocbp)) => {
                     let err = unsafe { libc::aio_error(aiocbp.as_ptr()) };
@@ -427,7 +762,7 @@ impl Driver {
                         libc::ECANCELED => {
                             // Remove the aiocb from kqueue.
                             libc::aio_return(aiocbp.as_ptr());
-                            Err(io::Error::from_raw_os_error(libc::ETIMEDOUT))
+                            Err(cancelled_error())
                         }
                         _ => syscall!(libc::aio_return(aiocbp.as_ptr())).map(|res| res as usize),
                     };
@@ -439,7 +774,11 @@ impl Driver {
     }
 
     pub fn handle(&self) -> NotifyHandle {
-        NotifyHandle::new(self.poll.clone())
+        NotifyHandle::new(self.poll.clone(), self.counters.notified.clone())
+    }
+
+    pub fn next_timeout(&self) -> Option<Duration> {
+        self.timers.min_timeout()
     }
 
     pub fn create_buffer_pool(
@@ -486,24 +825,23 @@ impl Drop for Driver {
 }
 
 fn entry_cancelled(user_data: usize) -> Entry {
-    Entry::new(
-        user_data,
-        Err(io::Error::from_raw_os_error(libc::ETIMEDOUT)),
-    )
+    Entry::new(user_data, Err(cancelled_error()))
 }
 
 /// A notify handle to the inner driver.
 pub struct NotifyHandle {
     poll: Arc<Poller>,
+    notified: SharedCounter,
 }
 
 impl NotifyHandle {
-    fn new(poll: Arc<Poller>) -> Self {
-        Self { poll }
+    fn new(poll: Arc<Poller>, notified: SharedCounter) -> Self {
+        Self { poll, notified }
     }
 
     /// Notify the inner driver.
     pub fn notify(&self) -> io::Result<()> {
+        self.notified.inc();
         self.poll.notify()
     }
 }
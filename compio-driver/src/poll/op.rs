@@ -1,4 +1,4 @@
-#[cfg(aio)]
+#[cfg(any(aio, linux_aio))]
 use std::ptr::NonNull;
 use std::{
     ffi::CString,
@@ -22,15 +22,19 @@ use libc::{pread, preadv, pwrite, pwritev};
 use libc::{pread64 as pread, preadv64 as preadv, pwrite64 as pwrite, pwritev64 as pwritev};
 use socket2::{SockAddr, Socket as Socket2};
 
+#[cfg(linux_aio)]
+use super::Iocb;
 use super::{AsFd, Decision, OpCode, OpType, sockaddr_storage, socklen_t, syscall};
-use crate::op::*;
 pub use crate::unix::op::*;
+use crate::{AsFdTarget, DispatchOptions, IoFixedBuf, RawFd, op::*};
 
 impl<
     D: std::marker::Send + 'static,
     F: (FnOnce() -> BufResult<usize, D>) + std::marker::Send + 'static,
 > OpCode for Asyncify<F, D>
 {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -46,9 +50,15 @@ impl<
         this.data = Some(data);
         Poll::Ready(res)
     }
+
+    fn dispatch_options(self: Pin<&mut Self>) -> Option<DispatchOptions> {
+        Some(self.options.clone())
+    }
 }
 
 impl OpCode for OpenFile {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -63,6 +73,8 @@ impl OpCode for OpenFile {
 }
 
 impl OpCode for CloseFile {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -88,7 +100,7 @@ impl<S> FileStat<S> {
     }
 }
 
-impl<S: AsFd> OpCode for FileStat<S> {
+impl<S: AsFdTarget> OpCode for FileStat<S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -100,7 +112,7 @@ impl<S: AsFd> OpCode for FileStat<S> {
             let mut s: libc::statx = unsafe { std::mem::zeroed() };
             static EMPTY_NAME: &[u8] = b"\0";
             syscall!(libc::statx(
-                this.fd.as_fd().as_raw_fd(),
+                this.fd.as_fd_target().as_raw_fd(),
                 EMPTY_NAME.as_ptr().cast(),
                 libc::AT_EMPTY_PATH,
                 0,
@@ -111,9 +123,10 @@ impl<S: AsFd> OpCode for FileStat<S> {
         }
         #[cfg(not(gnulinux))]
         {
-            Poll::Ready(Ok(
-                syscall!(libc::fstat(this.fd.as_fd().as_raw_fd(), &mut this.stat))? as _,
-            ))
+            Poll::Ready(Ok(syscall!(libc::fstat(
+                this.fd.as_fd_target().as_raw_fd(),
+                &mut this.stat
+            ))? as _))
         }
     }
 }
@@ -187,21 +200,31 @@ impl IntoInner for PathStat {
     }
 }
 
-impl<T: IoBufMut, S: AsFd> OpCode for ReadAt<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> OpCode for ReadAt<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         #[cfg(aio)]
         {
             let this = unsafe { self.get_unchecked_mut() };
             let slice = this.buffer.as_mut_slice();
 
-            this.aiocb.aio_fildes = this.fd.as_fd().as_raw_fd();
+            this.aiocb.aio_fildes = this.fd.as_fd_target().as_raw_fd();
             this.aiocb.aio_offset = this.offset as _;
             this.aiocb.aio_buf = slice.as_mut_ptr().cast();
             this.aiocb.aio_nbytes = slice.len();
 
             Ok(Decision::aio(&mut this.aiocb, libc::aio_read))
         }
-        #[cfg(not(aio))]
+        #[cfg(linux_aio)]
+        {
+            let this = unsafe { self.get_unchecked_mut() };
+            let fd = this.fd.as_fd_target().as_raw_fd();
+            let slice = this.buffer.as_mut_slice();
+            this.iocb = Iocb::pread(fd, slice.as_mut_ptr().cast(), slice.len(), this.offset);
+            Ok(Decision::linux_aio(&mut this.iocb))
+        }
+        #[cfg(not(any(aio, linux_aio)))]
         {
             Ok(Decision::Blocking)
         }
@@ -214,29 +237,50 @@ impl<T: IoBufMut, S: AsFd> OpCode for ReadAt<T, S> {
         )))
     }
 
+    #[cfg(linux_aio)]
+    fn op_type(self: Pin<&mut Self>) -> Option<crate::OpType> {
+        Some(OpType::LinuxAio(NonNull::from(
+            &mut unsafe { self.get_unchecked_mut() }.iocb,
+        )))
+    }
+
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
-        let fd = self.fd.as_fd().as_raw_fd();
+        let fd = self.fd.as_fd_target().as_raw_fd();
         let offset = self.offset;
         let slice = unsafe { self.get_unchecked_mut() }.buffer.as_mut_slice();
         syscall!(break pread(fd, slice.as_mut_ptr() as _, slice.len() as _, offset as _,))
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> OpCode for ReadVectoredAt<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> OpCode for ReadVectoredAt<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         #[cfg(freebsd)]
         {
             let this = unsafe { self.get_unchecked_mut() };
             this.slices = unsafe { this.buffer.io_slices_mut() };
 
-            this.aiocb.aio_fildes = this.fd.as_fd().as_raw_fd();
+            this.aiocb.aio_fildes = this.fd.as_fd_target().as_raw_fd();
             this.aiocb.aio_offset = this.offset as _;
             this.aiocb.aio_buf = this.slices.as_mut_ptr().cast();
             this.aiocb.aio_nbytes = this.slices.len();
 
             Ok(Decision::aio(&mut this.aiocb, libc::aio_readv))
         }
-        #[cfg(not(freebsd))]
+        #[cfg(linux_aio)]
+        {
+            let this = unsafe { self.get_unchecked_mut() };
+            this.slices = unsafe { this.buffer.io_slices_mut() };
+            this.iocb = Iocb::preadv(
+                this.fd.as_fd_target().as_raw_fd(),
+                this.slices.as_ptr().cast(),
+                this.slices.len(),
+                this.offset,
+            );
+            Ok(Decision::linux_aio(&mut this.iocb))
+        }
+        #[cfg(not(any(freebsd, linux_aio)))]
         {
             Ok(Decision::Blocking)
         }
@@ -249,12 +293,19 @@ impl<T: IoVectoredBufMut, S: AsFd> OpCode for ReadVectoredAt<T, S> {
         )))
     }
 
+    #[cfg(linux_aio)]
+    fn op_type(self: Pin<&mut Self>) -> Option<crate::OpType> {
+        Some(OpType::LinuxAio(NonNull::from(
+            &mut unsafe { self.get_unchecked_mut() }.iocb,
+        )))
+    }
+
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         let this = unsafe { self.get_unchecked_mut() };
         this.slices = unsafe { this.buffer.io_slices_mut() };
         syscall!(
             break preadv(
-                this.fd.as_fd().as_raw_fd(),
+                this.fd.as_fd_target().as_raw_fd(),
                 this.slices.as_ptr() as _,
                 this.slices.len() as _,
                 this.offset as _,
@@ -263,21 +314,31 @@ impl<T: IoVectoredBufMut, S: AsFd> OpCode for ReadVectoredAt<T, S> {
     }
 }
 
-impl<T: IoBuf, S: AsFd> OpCode for WriteAt<T, S> {
+impl<T: IoBuf, S: AsFdTarget> OpCode for WriteAt<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         #[cfg(aio)]
         {
             let this = unsafe { self.get_unchecked_mut() };
             let slice = this.buffer.as_slice();
 
-            this.aiocb.aio_fildes = this.fd.as_fd().as_raw_fd();
+            this.aiocb.aio_fildes = this.fd.as_fd_target().as_raw_fd();
             this.aiocb.aio_offset = this.offset as _;
             this.aiocb.aio_buf = slice.as_ptr().cast_mut().cast();
             this.aiocb.aio_nbytes = slice.len();
 
             Ok(Decision::aio(&mut this.aiocb, libc::aio_write))
         }
-        #[cfg(not(aio))]
+        #[cfg(linux_aio)]
+        {
+            let this = unsafe { self.get_unchecked_mut() };
+            let fd = this.fd.as_fd_target().as_raw_fd();
+            let slice = this.buffer.as_slice();
+            this.iocb = Iocb::pwrite(fd, slice.as_ptr().cast(), slice.len(), this.offset);
+            Ok(Decision::linux_aio(&mut this.iocb))
+        }
+        #[cfg(not(any(aio, linux_aio)))]
         {
             Ok(Decision::Blocking)
         }
@@ -290,11 +351,18 @@ impl<T: IoBuf, S: AsFd> OpCode for WriteAt<T, S> {
         )))
     }
 
+    #[cfg(linux_aio)]
+    fn op_type(self: Pin<&mut Self>) -> Option<crate::OpType> {
+        Some(OpType::LinuxAio(NonNull::from(
+            &mut unsafe { self.get_unchecked_mut() }.iocb,
+        )))
+    }
+
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         let slice = self.buffer.as_slice();
         syscall!(
             break pwrite(
-                self.fd.as_fd().as_raw_fd(),
+                self.fd.as_fd_target().as_raw_fd(),
                 slice.as_ptr() as _,
                 slice.len() as _,
                 self.offset as _,
@@ -303,21 +371,35 @@ impl<T: IoBuf, S: AsFd> OpCode for WriteAt<T, S> {
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> OpCode for WriteVectoredAt<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> OpCode for WriteVectoredAt<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         #[cfg(freebsd)]
         {
             let this = unsafe { self.get_unchecked_mut() };
             this.slices = unsafe { this.buffer.io_slices() };
 
-            this.aiocb.aio_fildes = this.fd.as_fd().as_raw_fd();
+            this.aiocb.aio_fildes = this.fd.as_fd_target().as_raw_fd();
             this.aiocb.aio_offset = this.offset as _;
             this.aiocb.aio_buf = this.slices.as_ptr().cast_mut().cast();
             this.aiocb.aio_nbytes = this.slices.len();
 
             Ok(Decision::aio(&mut this.aiocb, libc::aio_writev))
         }
-        #[cfg(not(freebsd))]
+        #[cfg(linux_aio)]
+        {
+            let this = unsafe { self.get_unchecked_mut() };
+            this.slices = unsafe { this.buffer.io_slices() };
+            this.iocb = Iocb::pwritev(
+                this.fd.as_fd_target().as_raw_fd(),
+                this.slices.as_ptr().cast(),
+                this.slices.len(),
+                this.offset,
+            );
+            Ok(Decision::linux_aio(&mut this.iocb))
+        }
+        #[cfg(not(any(freebsd, linux_aio)))]
         {
             Ok(Decision::Blocking)
         }
@@ -330,12 +412,19 @@ impl<T: IoVectoredBuf, S: AsFd> OpCode for WriteVectoredAt<T, S> {
         )))
     }
 
+    #[cfg(linux_aio)]
+    fn op_type(self: Pin<&mut Self>) -> Option<crate::OpType> {
+        Some(OpType::LinuxAio(NonNull::from(
+            &mut unsafe { self.get_unchecked_mut() }.iocb,
+        )))
+    }
+
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         let this = unsafe { self.get_unchecked_mut() };
         this.slices = unsafe { this.buffer.io_slices() };
         syscall!(
             break pwritev(
-                this.fd.as_fd().as_raw_fd(),
+                this.fd.as_fd_target().as_raw_fd(),
                 this.slices.as_ptr() as _,
                 this.slices.len() as _,
                 this.offset as _,
@@ -344,7 +433,7 @@ impl<T: IoVectoredBuf, S: AsFd> OpCode for WriteVectoredAt<T, S> {
     }
 }
 
-impl<S: AsFd> OpCode for crate::op::managed::ReadManagedAt<S> {
+impl<S: AsFdTarget> OpCode for crate::op::managed::ReadManagedAt<S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
     }
@@ -358,7 +447,9 @@ impl<S: AsFd> OpCode for crate::op::managed::ReadManagedAt<S> {
     }
 }
 
-impl<S: AsFd> OpCode for Sync<S> {
+impl<S: AsFdTarget> OpCode for Sync<S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         #[cfg(aio)]
         {
@@ -370,7 +461,7 @@ impl<S: AsFd> OpCode for Sync<S> {
             }
 
             let this = unsafe { self.get_unchecked_mut() };
-            this.aiocb.aio_fildes = this.fd.as_fd().as_raw_fd();
+            this.aiocb.aio_fildes = this.fd.as_fd_target().as_raw_fd();
 
             let f = if this.datasync {
                 aio_fdatasync
@@ -397,19 +488,23 @@ impl<S: AsFd> OpCode for Sync<S> {
         #[cfg(datasync)]
         {
             Poll::Ready(Ok(syscall!(if self.datasync {
-                libc::fdatasync(self.fd.as_fd().as_raw_fd())
+                libc::fdatasync(self.fd.as_fd_target().as_raw_fd())
             } else {
-                libc::fsync(self.fd.as_fd().as_raw_fd())
+                libc::fsync(self.fd.as_fd_target().as_raw_fd())
             })? as _))
         }
         #[cfg(not(datasync))]
         {
-            Poll::Ready(Ok(syscall!(libc::fsync(self.fd.as_fd().as_raw_fd()))? as _))
+            Poll::Ready(Ok(
+                syscall!(libc::fsync(self.fd.as_fd_target().as_raw_fd()))? as _,
+            ))
         }
     }
 }
 
 impl OpCode for Unlink {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -425,6 +520,8 @@ impl OpCode for Unlink {
 }
 
 impl OpCode for CreateDir {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -436,6 +533,8 @@ impl OpCode for CreateDir {
 }
 
 impl OpCode for Rename {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -529,6 +628,8 @@ impl CreateSocket {
 }
 
 impl OpCode for CreateSocket {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -538,19 +639,24 @@ impl OpCode for CreateSocket {
     }
 }
 
-impl<S: AsFd> OpCode for ShutdownSocket<S> {
+impl<S: AsFdTarget> OpCode for ShutdownSocket<S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
-        Poll::Ready(Ok(
-            syscall!(libc::shutdown(self.fd.as_fd().as_raw_fd(), self.how()))? as _,
-        ))
+        Poll::Ready(Ok(syscall!(libc::shutdown(
+            self.fd.as_fd_target().as_raw_fd(),
+            self.how()
+        ))? as _))
     }
 }
 
 impl OpCode for CloseSocket {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::Blocking)
     }
@@ -560,7 +666,7 @@ impl OpCode for CloseSocket {
     }
 }
 
-impl<S: AsFd> Accept<S> {
+impl<S: AsFdTarget> Accept<S> {
     unsafe fn call(self: Pin<&mut Self>) -> libc::c_int {
         let this = self.get_unchecked_mut();
         #[cfg(any(
@@ -576,7 +682,7 @@ impl<S: AsFd> Accept<S> {
         ))]
         {
             libc::accept4(
-                this.fd.as_fd().as_raw_fd(),
+                this.fd.as_fd_target().as_raw_fd(),
                 &mut this.buffer as *mut _ as *mut _,
                 &mut this.addr_len,
                 libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
@@ -596,7 +702,7 @@ impl<S: AsFd> Accept<S> {
         {
             || -> io::Result<libc::c_int> {
                 let fd = syscall!(libc::accept(
-                    this.fd.as_fd().as_raw_fd(),
+                    this.fd.as_fd_target().as_raw_fd(),
                     &mut this.buffer as *mut _ as *mut _,
                     &mut this.addr_len,
                 ))?;
@@ -610,41 +716,62 @@ impl<S: AsFd> Accept<S> {
     }
 }
 
-impl<S: AsFd> OpCode for Accept<S> {
+impl<S: AsFdTarget> OpCode for Accept<S> {
+    op_view!();
+
     fn pre_submit(mut self: Pin<&mut Self>) -> io::Result<Decision> {
-        let fd = self.fd.as_fd().as_raw_fd();
+        let fd = self.fd.as_fd_target().as_raw_fd();
         syscall!(self.as_mut().call(), wait_readable(fd))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(mut self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         let res = syscall!(break self.as_mut().call());
+        // A direct descriptor is owned by the `FixedFd` created from the result.
         if let Poll::Ready(Ok(fd)) = res {
-            unsafe {
-                self.get_unchecked_mut().accepted_fd = Some(OwnedFd::from_raw_fd(fd as _));
+            if !self.direct {
+                unsafe {
+                    self.get_unchecked_mut().accepted_fd = Some(OwnedFd::from_raw_fd(fd as _));
+                }
             }
         }
         res
     }
 }
 
-impl<S: AsFd> OpCode for Connect<S> {
+impl<S: AsFdTarget> OpCode for AcceptMulti<S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+impl<S: AsFdTarget> OpCode for Connect<S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         syscall!(
             libc::connect(
-                self.fd.as_fd().as_raw_fd(),
+                self.fd.as_fd_target().as_raw_fd(),
                 self.addr.as_ptr(),
                 self.addr.len()
             ),
-            wait_writable(self.fd.as_fd().as_raw_fd())
+            wait_writable(self.fd.as_fd_target().as_raw_fd())
         )
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
@@ -652,7 +779,7 @@ impl<S: AsFd> OpCode for Connect<S> {
         let mut err_len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
 
         syscall!(libc::getsockopt(
-            self.fd.as_fd().as_raw_fd(),
+            self.fd.as_fd_target().as_raw_fd(),
             libc::SOL_SOCKET,
             libc::SO_ERROR,
             &mut err as *mut _ as *mut _,
@@ -668,29 +795,33 @@ impl<S: AsFd> OpCode for Connect<S> {
     }
 }
 
-impl<T: IoBufMut, S: AsFd> OpCode for Recv<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> OpCode for Recv<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
-        Ok(Decision::wait_readable(self.fd.as_fd().as_raw_fd()))
+        Ok(Decision::wait_readable(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
-        let fd = self.fd.as_fd().as_raw_fd();
+        let fd = self.fd.as_fd_target().as_raw_fd();
         let slice = unsafe { self.get_unchecked_mut() }.buffer.as_mut_slice();
         syscall!(break libc::read(fd, slice.as_mut_ptr() as _, slice.len()))
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> OpCode for RecvVectored<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> OpCode for RecvVectored<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
-        Ok(Decision::wait_readable(self.fd.as_fd().as_raw_fd()))
+        Ok(Decision::wait_readable(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
@@ -698,7 +829,7 @@ impl<T: IoVectoredBufMut, S: AsFd> OpCode for RecvVectored<T, S> {
         this.slices = unsafe { this.buffer.io_slices_mut() };
         syscall!(
             break libc::readv(
-                this.fd.as_fd().as_raw_fd(),
+                this.fd.as_fd_target().as_raw_fd(),
                 this.slices.as_ptr() as _,
                 this.slices.len() as _
             )
@@ -706,20 +837,22 @@ impl<T: IoVectoredBufMut, S: AsFd> OpCode for RecvVectored<T, S> {
     }
 }
 
-impl<T: IoBuf, S: AsFd> OpCode for Send<T, S> {
+impl<T: IoBuf, S: AsFdTarget> OpCode for Send<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
-        Ok(Decision::wait_writable(self.fd.as_fd().as_raw_fd()))
+        Ok(Decision::wait_writable(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         let slice = self.buffer.as_slice();
         syscall!(
             break libc::write(
-                self.fd.as_fd().as_raw_fd(),
+                self.fd.as_fd_target().as_raw_fd(),
                 slice.as_ptr() as _,
                 slice.len()
             )
@@ -727,13 +860,15 @@ impl<T: IoBuf, S: AsFd> OpCode for Send<T, S> {
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> OpCode for SendVectored<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> OpCode for SendVectored<T, S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
-        Ok(Decision::wait_writable(self.fd.as_fd().as_raw_fd()))
+        Ok(Decision::wait_writable(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
@@ -741,7 +876,7 @@ impl<T: IoVectoredBuf, S: AsFd> OpCode for SendVectored<T, S> {
         this.slices = unsafe { this.buffer.io_slices() };
         syscall!(
             break libc::writev(
-                this.fd.as_fd().as_raw_fd(),
+                this.fd.as_fd_target().as_raw_fd(),
                 this.slices.as_ptr() as _,
                 this.slices.len() as _
             )
@@ -749,7 +884,21 @@ impl<T: IoVectoredBuf, S: AsFd> OpCode for SendVectored<T, S> {
     }
 }
 
-impl<S: AsFd> OpCode for crate::op::managed::RecvManaged<S> {
+impl<S: AsFdTarget> OpCode for crate::op::managed::RecvManaged<S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<crate::OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+impl<S: AsFdTarget> OpCode for crate::op::managed::RecvMulti<S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
     }
@@ -785,10 +934,10 @@ impl<T: IoBufMut, S> RecvFrom<T, S> {
     }
 }
 
-impl<T: IoBufMut, S: AsFd> RecvFrom<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> RecvFrom<T, S> {
     unsafe fn call(self: Pin<&mut Self>) -> libc::ssize_t {
         let this = self.get_unchecked_mut();
-        let fd = this.fd.as_fd().as_raw_fd();
+        let fd = this.fd.as_fd_target().as_raw_fd();
         let slice = this.buffer.as_mut_slice();
         libc::recvfrom(
             fd,
@@ -801,19 +950,28 @@ impl<T: IoBufMut, S: AsFd> RecvFrom<T, S> {
     }
 }
 
-impl<T: IoBufMut, S: AsFd> OpCode for RecvFrom<T, S> {
+impl<T: IoBufMut, S: AsFdTarget> OpCode for RecvFrom<T, S> {
     fn pre_submit(mut self: Pin<&mut Self>) -> io::Result<Decision> {
-        let fd = self.fd.as_fd().as_raw_fd();
+        let fd = self.fd.as_fd_target().as_raw_fd();
         syscall!(self.as_mut().call(), wait_readable(fd))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(mut self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         syscall!(break self.as_mut().call())
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::RecvFrom {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            bufs: vec![this.buffer.as_mut_slice()],
+            addr: AddrMut::new(&mut this.addr, &mut this.addr_len),
+        }
+    }
 }
 
 impl<T: IoBufMut, S> IntoInner for RecvFrom<T, S> {
@@ -848,7 +1006,7 @@ impl<T: IoVectoredBufMut, S> RecvFromVectored<T, S> {
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> RecvFromVectored<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> RecvFromVectored<T, S> {
     fn set_msg(&mut self) {
         self.slices = unsafe { self.buffer.io_slices_mut() };
         self.msg = libc::msghdr {
@@ -863,25 +1021,37 @@ impl<T: IoVectoredBufMut, S: AsFd> RecvFromVectored<T, S> {
     }
 
     unsafe fn call(&mut self) -> libc::ssize_t {
-        libc::recvmsg(self.fd.as_fd().as_raw_fd(), &mut self.msg, 0)
+        libc::recvmsg(self.fd.as_fd_target().as_raw_fd(), &mut self.msg, 0)
     }
 }
 
-impl<T: IoVectoredBufMut, S: AsFd> OpCode for RecvFromVectored<T, S> {
+impl<T: IoVectoredBufMut, S: AsFdTarget> OpCode for RecvFromVectored<T, S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         let this = unsafe { self.get_unchecked_mut() };
         this.set_msg();
-        syscall!(this.call(), wait_readable(this.fd.as_fd().as_raw_fd()))
+        syscall!(
+            this.call(),
+            wait_readable(this.fd.as_fd_target().as_raw_fd())
+        )
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         let this = unsafe { self.get_unchecked_mut() };
         syscall!(break this.call())
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::RecvFrom {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            bufs: bufs_mut(unsafe { this.buffer.io_slices_mut() }),
+            addr: AddrMut::new(&mut this.addr, &mut this.msg.msg_namelen),
+        }
+    }
 }
 
 impl<T: IoVectoredBufMut, S> IntoInner for RecvFromVectored<T, S> {
@@ -912,11 +1082,11 @@ impl<T: IoBuf, S> SendTo<T, S> {
     }
 }
 
-impl<T: IoBuf, S: AsFd> SendTo<T, S> {
+impl<T: IoBuf, S: AsFdTarget> SendTo<T, S> {
     unsafe fn call(&self) -> libc::ssize_t {
         let slice = self.buffer.as_slice();
         libc::sendto(
-            self.fd.as_fd().as_raw_fd(),
+            self.fd.as_fd_target().as_raw_fd(),
             slice.as_ptr() as _,
             slice.len(),
             0,
@@ -926,18 +1096,30 @@ impl<T: IoBuf, S: AsFd> SendTo<T, S> {
     }
 }
 
-impl<T: IoBuf, S: AsFd> OpCode for SendTo<T, S> {
+impl<T: IoBuf, S: AsFdTarget> OpCode for SendTo<T, S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
-        syscall!(self.call(), wait_writable(self.fd.as_fd().as_raw_fd()))
+        syscall!(
+            self.call(),
+            wait_writable(self.fd.as_fd_target().as_raw_fd())
+        )
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         syscall!(break self.call())
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::SendTo {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            bufs: vec![this.buffer.as_slice()],
+            addr: &this.addr,
+        }
+    }
 }
 
 impl<T: IoBuf, S> IntoInner for SendTo<T, S> {
@@ -972,7 +1154,7 @@ impl<T: IoVectoredBuf, S> SendToVectored<T, S> {
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> SendToVectored<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> SendToVectored<T, S> {
     fn set_msg(&mut self) {
         self.slices = unsafe { self.buffer.io_slices() };
         self.msg = libc::msghdr {
@@ -987,24 +1169,36 @@ impl<T: IoVectoredBuf, S: AsFd> SendToVectored<T, S> {
     }
 
     unsafe fn call(&self) -> libc::ssize_t {
-        libc::sendmsg(self.fd.as_fd().as_raw_fd(), &self.msg, 0)
+        libc::sendmsg(self.fd.as_fd_target().as_raw_fd(), &self.msg, 0)
     }
 }
 
-impl<T: IoVectoredBuf, S: AsFd> OpCode for SendToVectored<T, S> {
+impl<T: IoVectoredBuf, S: AsFdTarget> OpCode for SendToVectored<T, S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         let this = unsafe { self.get_unchecked_mut() };
         this.set_msg();
-        syscall!(this.call(), wait_writable(this.fd.as_fd().as_raw_fd()))
+        syscall!(
+            this.call(),
+            wait_writable(this.fd.as_fd_target().as_raw_fd())
+        )
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         syscall!(break self.call())
     }
+
+    fn view(self: Pin<&mut Self>) -> OpView<'_> {
+        let this = unsafe { self.get_unchecked_mut() };
+        OpView::SendTo {
+            fd: this.fd.as_fd_target().as_raw_fd(),
+            bufs: bufs(unsafe { this.buffer.io_slices() }),
+            addr: &this.addr,
+        }
+    }
 }
 
 impl<T: IoVectoredBuf, S> IntoInner for SendToVectored<T, S> {
@@ -1015,21 +1209,24 @@ impl<T: IoVectoredBuf, S> IntoInner for SendToVectored<T, S> {
     }
 }
 
-impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFd> RecvMsg<T, C, S> {
+impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFdTarget> RecvMsg<T, C, S> {
     unsafe fn call(&mut self) -> libc::ssize_t {
-        libc::recvmsg(self.fd.as_fd().as_raw_fd(), &mut self.msg, 0)
+        libc::recvmsg(self.fd.as_fd_target().as_raw_fd(), &mut self.msg, 0)
     }
 }
 
-impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFd> OpCode for RecvMsg<T, C, S> {
+impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFdTarget> OpCode for RecvMsg<T, C, S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         let this = unsafe { self.get_unchecked_mut() };
         unsafe { this.set_msg() };
-        syscall!(this.call(), wait_readable(this.fd.as_fd().as_raw_fd()))
+        syscall!(
+            this.call(),
+            wait_readable(this.fd.as_fd_target().as_raw_fd())
+        )
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
@@ -1038,21 +1235,24 @@ impl<T: IoVectoredBufMut, C: IoBufMut, S: AsFd> OpCode for RecvMsg<T, C, S> {
     }
 }
 
-impl<T: IoVectoredBuf, C: IoBuf, S: AsFd> SendMsg<T, C, S> {
+impl<T: IoVectoredBuf, C: IoBuf, S: AsFdTarget> SendMsg<T, C, S> {
     unsafe fn call(&self) -> libc::ssize_t {
-        libc::sendmsg(self.fd.as_fd().as_raw_fd(), &self.msg, 0)
+        libc::sendmsg(self.fd.as_fd_target().as_raw_fd(), &self.msg, 0)
     }
 }
 
-impl<T: IoVectoredBuf, C: IoBuf, S: AsFd> OpCode for SendMsg<T, C, S> {
+impl<T: IoVectoredBuf, C: IoBuf, S: AsFdTarget> OpCode for SendMsg<T, C, S> {
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         let this = unsafe { self.get_unchecked_mut() };
         unsafe { this.set_msg() };
-        syscall!(this.call(), wait_writable(this.fd.as_fd().as_raw_fd()))
+        syscall!(
+            this.call(),
+            wait_writable(this.fd.as_fd_target().as_raw_fd())
+        )
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
@@ -1060,19 +1260,231 @@ impl<T: IoVectoredBuf, C: IoBuf, S: AsFd> OpCode for SendMsg<T, C, S> {
     }
 }
 
-impl<S: AsFd> OpCode for PollOnce<S> {
+impl<S: AsFdTarget> OpCode for PollOnce<S> {
+    op_view!();
+
     fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
         Ok(Decision::wait_for(
-            self.fd.as_fd().as_raw_fd(),
+            self.fd.as_fd_target().as_raw_fd(),
             self.interest,
         ))
     }
 
     fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
-        Some(OpType::Fd(self.fd.as_fd().as_raw_fd()))
+        Some(OpType::Fd(self.fd.as_fd_target().as_raw_fd()))
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
+}
+
+impl OpCode for Timeout {
+    op_view!();
+
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        Ok(Decision::Timer(self.delay))
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        Some(OpType::Timer)
     }
 
     fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
         Poll::Ready(Ok(0))
     }
 }
+
+impl<T: IoBufMut + IoFixedBuf, S: AsFdTarget> OpCode for ReadFixedAt<T, S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+impl<T: IoFixedBuf, S: AsFdTarget> OpCode for WriteFixedAt<T, S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+impl<T: IoFixedBuf, S: AsFdTarget> OpCode for SendFixed<T, S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+impl<T: IoBuf, S: AsFdTarget> OpCode for SendZc<T, S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+impl<T: IoVectoredBuf, C: IoBuf, S: AsFdTarget> OpCode for SendMsgZc<T, C, S> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.pre_submit()
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.op_type()
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.op) }.operate()
+    }
+}
+
+/// Decide which fd of a splice to wait for, after it returns `EAGAIN`. If the
+/// input is readable, the output must be full.
+#[cfg(linux_all)]
+fn splice_wait(fd_in: RawFd, fd_out: RawFd) -> (RawFd, Decision) {
+    let mut pollfd = libc::pollfd {
+        fd: fd_in,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let readable = syscall!(libc::poll(&mut pollfd, 1, 0)).is_ok_and(|n| n > 0);
+    if readable {
+        (fd_out, Decision::wait_writable(fd_out))
+    } else {
+        (fd_in, Decision::wait_readable(fd_in))
+    }
+}
+
+#[cfg(linux_all)]
+impl<S1: AsFdTarget, S2: AsFdTarget> Splice<S1, S2> {
+    unsafe fn call(&mut self) -> libc::ssize_t {
+        let offset_ptr = |offset: &mut i64| {
+            if *offset < 0 {
+                std::ptr::null_mut()
+            } else {
+                offset as *mut _
+            }
+        };
+        libc::splice(
+            self.fd_in.as_fd_target().as_raw_fd(),
+            offset_ptr(&mut self.offset_in),
+            self.fd_out.as_fd_target().as_raw_fd(),
+            offset_ptr(&mut self.offset_out),
+            self.len,
+            libc::SPLICE_F_NONBLOCK,
+        )
+    }
+}
+
+#[cfg(linux_all)]
+impl<S1: AsFdTarget, S2: AsFdTarget> OpCode for Splice<S1, S2> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        let this = unsafe { self.get_unchecked_mut() };
+        match syscall!(break this.call()) {
+            Poll::Ready(res) => res.map(Decision::Completed),
+            Poll::Pending => {
+                let decision;
+                (this.wait_fd, decision) = splice_wait(
+                    this.fd_in.as_fd_target().as_raw_fd(),
+                    this.fd_out.as_fd_target().as_raw_fd(),
+                );
+                Ok(decision)
+            }
+        }
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        Some(OpType::Fd(self.wait_fd))
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        let this = unsafe { self.get_unchecked_mut() };
+        syscall!(break this.call())
+    }
+}
+
+#[cfg(linux_all)]
+impl<S1: AsFdTarget, S2: AsFdTarget> Tee<S1, S2> {
+    unsafe fn call(&mut self) -> libc::ssize_t {
+        libc::tee(
+            self.fd_in.as_fd_target().as_raw_fd(),
+            self.fd_out.as_fd_target().as_raw_fd(),
+            self.len,
+            libc::SPLICE_F_NONBLOCK,
+        )
+    }
+}
+
+#[cfg(linux_all)]
+impl<S1: AsFdTarget, S2: AsFdTarget> OpCode for Tee<S1, S2> {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        let this = unsafe { self.get_unchecked_mut() };
+        match syscall!(break this.call()) {
+            Poll::Ready(res) => res.map(Decision::Completed),
+            Poll::Pending => {
+                let decision;
+                (this.wait_fd, decision) = splice_wait(
+                    this.fd_in.as_fd_target().as_raw_fd(),
+                    this.fd_out.as_fd_target().as_raw_fd(),
+                );
+                Ok(decision)
+            }
+        }
+    }
+
+    fn op_type(self: Pin<&mut Self>) -> Option<OpType> {
+        Some(OpType::Fd(self.wait_fd))
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        let this = unsafe { self.get_unchecked_mut() };
+        syscall!(break this.call())
+    }
+}
+
+impl OpCode for MsgRing {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::Unsupported.into()))
+    }
+}
+
+impl OpCode for MsgRingFd {
+    fn pre_submit(self: Pin<&mut Self>) -> io::Result<Decision> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn operate(self: Pin<&mut Self>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::Unsupported.into()))
+    }
+}
@@ -0,0 +1,140 @@
+//! The in-memory file system of the simulation.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    ffi::{CStr, CString},
+    io,
+    rc::Rc,
+};
+
+use super::{Object, Sim, err, placeholder};
+
+pub(super) type Node = Rc<RefCell<Vec<u8>>>;
+
+#[derive(Default)]
+pub(super) struct Fs {
+    files: BTreeMap<CString, Node>,
+    dirs: BTreeSet<CString>,
+}
+
+impl Fs {
+    fn exists(&self, path: &CStr) -> bool {
+        self.files.contains_key(path) || self.dirs.contains(path)
+    }
+
+    fn has_children(&self, path: &CStr) -> bool {
+        let mut prefix = path.to_bytes().to_vec();
+        prefix.push(b'/');
+        self.files
+            .keys()
+            .chain(self.dirs.iter())
+            .any(|p| p.to_bytes().starts_with(&prefix))
+    }
+}
+
+impl Sim {
+    pub(super) fn open(&mut self, path: &CStr, flags: i32) -> io::Result<usize> {
+        if self.fs.dirs.contains(path) {
+            return err(libc::EISDIR);
+        }
+        let node = match self.fs.files.get(path) {
+            Some(_) if flags & libc::O_CREAT != 0 && flags & libc::O_EXCL != 0 => {
+                return err(libc::EEXIST);
+            }
+            Some(node) => node.clone(),
+            None if flags & libc::O_CREAT != 0 => {
+                let node = Node::default();
+                self.fs.files.insert(path.to_owned(), node.clone());
+                node
+            }
+            None => return err(libc::ENOENT),
+        };
+        let access = flags & libc::O_ACCMODE;
+        let read = access != libc::O_WRONLY;
+        let write = access != libc::O_RDONLY;
+        if write && flags & libc::O_TRUNC != 0 {
+            node.borrow_mut().clear();
+        }
+        let object = Object::File {
+            node,
+            pos: 0,
+            read,
+            write,
+            append: flags & libc::O_APPEND != 0,
+        };
+        self.insert(placeholder()?, object)
+    }
+
+    pub(super) fn create_dir(&mut self, path: &CStr) -> io::Result<usize> {
+        if self.fs.exists(path) {
+            return err(libc::EEXIST);
+        }
+        self.fs.dirs.insert(path.to_owned());
+        Ok(0)
+    }
+
+    pub(super) fn unlink(&mut self, path: &CStr, dir: bool) -> io::Result<usize> {
+        if dir {
+            if !self.fs.dirs.contains(path) {
+                return err(if self.fs.files.contains_key(path) {
+                    libc::ENOTDIR
+                } else {
+                    libc::ENOENT
+                });
+            }
+            if self.fs.has_children(path) {
+                return err(libc::ENOTEMPTY);
+            }
+            self.fs.dirs.remove(path);
+        } else if self.fs.files.remove(path).is_none() {
+            return err(if self.fs.dirs.contains(path) {
+                libc::EISDIR
+            } else {
+                libc::ENOENT
+            });
+        }
+        Ok(0)
+    }
+
+    pub(super) fn rename(&mut self, from: &CStr, to: &CStr) -> io::Result<usize> {
+        if let Some(node) = self.fs.files.remove(from) {
+            if self.fs.dirs.contains(to) {
+                self.fs.files.insert(from.to_owned(), node);
+                return err(libc::EISDIR);
+            }
+            self.fs.files.insert(to.to_owned(), node);
+            return Ok(0);
+        }
+        if !self.fs.dirs.contains(from) {
+            return err(libc::ENOENT);
+        }
+        if self.fs.files.contains_key(to) || self.fs.has_children(to) {
+            return err(libc::ENOTEMPTY);
+        }
+        let mut prefix = from.to_bytes().to_vec();
+        prefix.push(b'/');
+        let moved = |p: &CString| -> Option<CString> {
+            let bytes = p.to_bytes();
+            if bytes == from.to_bytes() {
+                Some(to.to_owned())
+            } else {
+                bytes.strip_prefix(prefix.as_slice()).map(|rest| {
+                    let mut path = to.to_bytes().to_vec();
+                    path.push(b'/');
+                    path.extend_from_slice(rest);
+                    CString::new(path).expect("the path contains no nul")
+                })
+            }
+        };
+        self.fs.files = std::mem::take(&mut self.fs.files)
+            .into_iter()
+            .map(|(p, node)| (moved(&p).unwrap_or(p), node))
+            .collect();
+        self.fs.dirs = std::mem::take(&mut self.fs.dirs)
+            .into_iter()
+            .map(|p| moved(&p).unwrap_or(p))
+            .collect();
+        Ok(0)
+    }
+}
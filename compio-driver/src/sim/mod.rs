@@ -0,0 +1,553 @@
+//! A deterministic simulation [`Backend`], whose files, sockets and time are
+//! in memory.
+//!
+//! The operations are queued when pushed, and performed when the proactor is
+//! polled, in the order they are pushed. Each completion is delayed by a
+//! random latency on a virtual clock, and the completions at the same time are
+//! shuffled. The randomness comes from the seed only, so a run is reproduced
+//! with the same seed. The timers run on the virtual clock, which jumps to the
+//! next completion instead of sleeping.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use compio_driver::{ProactorBuilder, sim::Sim};
+//!
+//! let mut builder = ProactorBuilder::new();
+//! builder.backend(|| {
+//!     Sim::builder()
+//!         .seed(42)
+//!         .latency(Duration::from_millis(1))
+//!         .build()
+//! });
+//! let mut driver = builder.build().unwrap();
+//! ```
+//!
+//! ## Limitations
+//!
+//! * Only the operations described by [`OpView`] are simulated, and the others
+//!   fail with [`io::ErrorKind::Unsupported`], e.g., [`FileStat`].
+//! * The fds are real placeholders, so that they could be owned and closed as
+//!   usual. A file is an unbound unix socket, and a network socket is a real
+//!   socket of the requested domain and type, so the synchronous `bind(2)`,
+//!   `listen(2)` and `getsockname(2)` work. The data never goes through them,
+//!   and `getpeername(2)` or other synchronous I/O don't reflect the
+//!   simulation.
+//! * The file system is flat: the parent directories are not checked, and there
+//!   is no metadata.
+//! * Only the fds created by the simulation are known, and the others fail with
+//!   `EBADF`.
+//!
+//! [`FileStat`]: crate::op::FileStat
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io,
+    mem::MaybeUninit,
+    os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
+    sync::{Arc, Condvar, Mutex},
+    task::Poll,
+    time::Duration,
+};
+
+use socket2::{Domain, SockAddr, Socket, Type};
+
+use crate::{
+    Backend, Key, NotifyHandle, OpCode, cancelled_error,
+    op::{Interest, OpView},
+};
+
+mod fs;
+use fs::{Fs, Node};
+
+mod net;
+use net::{Incoming, StreamEnd, sock};
+
+/// Builder of [`Sim`].
+#[derive(Debug, Clone, Default)]
+pub struct SimBuilder {
+    seed: u64,
+    latency: Duration,
+}
+
+impl SimBuilder {
+    /// Create the builder with seed 0 and no latency.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the seed of the randomness.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the max latency of the completions on the virtual clock. Each
+    /// completion is delayed by a random duration up to it.
+    pub fn latency(&mut self, latency: Duration) -> &mut Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Build the [`Sim`].
+    pub fn build(&self) -> io::Result<Sim> {
+        Ok(Sim {
+            fd: placeholder()?,
+            rng: self.seed,
+            latency: self.latency,
+            now: Duration::ZERO,
+            seq: 0,
+            pending: VecDeque::new(),
+            scheduled: BTreeMap::new(),
+            objects: BTreeMap::new(),
+            fs: Fs::default(),
+            notify: Arc::new(Notify::default()),
+        })
+    }
+}
+
+/// The simulation backend, see the [module-level docs](self).
+pub struct Sim {
+    fd: OwnedFd,
+    rng: u64,
+    latency: Duration,
+    now: Duration,
+    seq: u64,
+    pending: VecDeque<Key<dyn OpCode>>,
+    scheduled: BTreeMap<(Duration, u64, u64), Completion>,
+    objects: BTreeMap<RawFd, (u64, Object)>,
+    fs: Fs,
+    notify: Arc<Notify>,
+}
+
+struct Completion {
+    key: Key<dyn OpCode>,
+    res: io::Result<usize>,
+    timer: bool,
+}
+
+#[derive(Default)]
+struct Notify {
+    notified: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl Notify {
+    fn notify(&self) {
+        *self.notified.lock().unwrap() = true;
+        self.cond.notify_one();
+    }
+
+    fn take(&self) -> bool {
+        std::mem::take(&mut *self.notified.lock().unwrap())
+    }
+
+    fn wait(&self) {
+        let mut notified = self.notified.lock().unwrap();
+        while !*notified {
+            notified = self.cond.wait(notified).unwrap();
+        }
+        *notified = false;
+    }
+}
+
+enum Object {
+    File {
+        node: Node,
+        pos: u64,
+        read: bool,
+        write: bool,
+        append: bool,
+    },
+    Stream(Option<StreamEnd>),
+    Listener(VecDeque<Incoming>),
+    Dgram(VecDeque<(Vec<u8>, SockAddr)>),
+}
+
+fn err<T>(errno: i32) -> io::Result<T> {
+    Err(io::Error::from_raw_os_error(errno))
+}
+
+fn placeholder() -> io::Result<OwnedFd> {
+    Ok(Socket::new(Domain::UNIX, Type::DGRAM, None)?.into())
+}
+
+fn inode(fd: RawFd) -> Option<u64> {
+    let mut stat = MaybeUninit::<libc::stat>::uninit();
+    if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } == 0 {
+        Some(unsafe { stat.assume_init() }.st_ino as _)
+    } else {
+        None
+    }
+}
+
+fn read_into(data: &[u8], bufs: &mut [&mut [MaybeUninit<u8>]]) -> usize {
+    let mut read = 0;
+    for buf in bufs {
+        let len = buf.len().min(data.len() - read);
+        for (dst, src) in buf.iter_mut().zip(&data[read..read + len]) {
+            dst.write(*src);
+        }
+        read += len;
+    }
+    read
+}
+
+fn view_fd(view: &OpView) -> Option<RawFd> {
+    match view {
+        OpView::Read { fd, .. }
+        | OpView::Write { fd, .. }
+        | OpView::Sync { fd, .. }
+        | OpView::Connect { fd, .. }
+        | OpView::Accept { fd, .. }
+        | OpView::RecvFrom { fd, .. }
+        | OpView::SendTo { fd, .. }
+        | OpView::Shutdown { fd, .. }
+        | OpView::PollOnce { fd, .. } => Some(*fd),
+        _ => None,
+    }
+}
+
+impl Sim {
+    /// Create a [`SimBuilder`].
+    pub fn builder() -> SimBuilder {
+        SimBuilder::new()
+    }
+
+    /// The elapsed time on the virtual clock.
+    pub fn elapsed(&self) -> Duration {
+        self.now
+    }
+
+    fn random(&mut self) -> u64 {
+        // splitmix64
+        self.rng = self.rng.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn schedule(
+        &mut self,
+        key: Key<dyn OpCode>,
+        res: io::Result<usize>,
+        due: Duration,
+        timer: bool,
+    ) {
+        let order = self.random();
+        self.seq += 1;
+        self.scheduled
+            .insert((due, order, self.seq), Completion { key, res, timer });
+    }
+
+    fn complete_later(&mut self, key: Key<dyn OpCode>, res: io::Result<usize>) {
+        let nanos = self.latency.as_nanos() as u64;
+        let delay = if nanos == 0 {
+            0
+        } else {
+            self.random() % (nanos + 1)
+        };
+        let due = self.now + Duration::from_nanos(delay);
+        self.schedule(key, res, due, false);
+    }
+
+    fn object(&mut self, fd: RawFd) -> io::Result<&mut Object> {
+        let ino = inode(fd);
+        match self.objects.get(&fd) {
+            Some((i, _)) if Some(*i) == ino => {}
+            Some(_) => {
+                // The fd has been closed synchronously, and maybe reused.
+                self.objects.remove(&fd);
+                return err(libc::EBADF);
+            }
+            None => return err(libc::EBADF),
+        }
+        Ok(&mut self.objects.get_mut(&fd).unwrap().1)
+    }
+
+    fn insert(&mut self, fd: OwnedFd, object: Object) -> io::Result<usize> {
+        let ino = inode(fd.as_raw_fd()).ok_or_else(io::Error::last_os_error)?;
+        let fd = fd.into_raw_fd();
+        self.objects.insert(fd, (ino, object));
+        Ok(fd as _)
+    }
+
+    /// Forget the objects whose fds have been closed synchronously.
+    fn sweep(&mut self) {
+        self.objects.retain(|fd, (ino, _)| inode(*fd) == Some(*ino));
+    }
+
+    /// Perform the operations that could proceed, until none could.
+    fn progress(&mut self) {
+        loop {
+            let mut progressed = false;
+            let mut blocked = VecDeque::new();
+            while let Some(mut key) = self.pending.pop_front() {
+                match self.operate(key.view()) {
+                    Poll::Ready(res) => {
+                        self.complete_later(key, res);
+                        progressed = true;
+                    }
+                    Poll::Pending => blocked.push_back(key),
+                }
+            }
+            self.pending = blocked;
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    fn operate(&mut self, view: OpView) -> Poll<io::Result<usize>> {
+        match view {
+            OpView::Blocking(f) => Poll::Ready(f()),
+            OpView::Open { path, flags, .. } => Poll::Ready(self.open(path, flags)),
+            OpView::Close(fd) => {
+                self.objects.remove(&fd);
+                // SAFETY: the fd is owned by the operation.
+                drop(unsafe { OwnedFd::from_raw_fd(fd) });
+                Poll::Ready(Ok(0))
+            }
+            OpView::Read { fd, offset, bufs } => self.read(fd, offset, bufs),
+            OpView::Write { fd, offset, bufs } => Poll::Ready(self.write(fd, offset, bufs)),
+            OpView::Sync { fd, .. } => Poll::Ready(match self.object(fd) {
+                Ok(Object::File { .. }) => Ok(0),
+                Ok(_) => err(libc::EINVAL),
+                Err(e) => Err(e),
+            }),
+            OpView::Unlink { path, dir } => Poll::Ready(self.unlink(path, dir)),
+            OpView::CreateDir { path, .. } => Poll::Ready(self.create_dir(path)),
+            OpView::Rename { from, to } => Poll::Ready(self.rename(from, to)),
+            OpView::Socket {
+                domain,
+                ty,
+                protocol,
+            } => Poll::Ready(self.socket(domain, ty, protocol)),
+            OpView::Connect { fd, addr } => Poll::Ready(self.connect(fd, addr)),
+            OpView::Accept { fd, addr } => self.accept(fd, addr),
+            OpView::RecvFrom { fd, bufs, addr } => self.recv_from(fd, bufs, addr),
+            OpView::SendTo { fd, bufs, addr } => Poll::Ready(self.send_to(fd, bufs, addr)),
+            OpView::Shutdown { fd, how } => Poll::Ready(self.shutdown(fd, how)),
+            OpView::PollOnce { fd, interest } => match self.object(fd) {
+                Ok(object) => {
+                    let ready = match (interest, object) {
+                        (Interest::Writable, _) => true,
+                        (_, Object::Stream(Some(end))) => {
+                            let rx = end.rx.borrow();
+                            !rx.data.is_empty() || rx.write_closed
+                        }
+                        (_, Object::Listener(backlog)) => !backlog.is_empty(),
+                        (_, Object::Dgram(queue)) => !queue.is_empty(),
+                        (_, Object::Stream(None)) => false,
+                        (_, Object::File { .. }) => true,
+                    };
+                    if ready {
+                        Poll::Ready(Ok(0))
+                    } else {
+                        Poll::Pending
+                    }
+                }
+                Err(e) => Poll::Ready(Err(e)),
+            },
+            OpView::Timeout(_) => unreachable!("timers are scheduled when pushed"),
+            _ => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the operation is not supported by the simulation",
+            ))),
+        }
+    }
+
+    fn read(
+        &mut self,
+        fd: RawFd,
+        offset: Option<u64>,
+        mut bufs: Vec<&mut [MaybeUninit<u8>]>,
+    ) -> Poll<io::Result<usize>> {
+        let object = match self.object(fd) {
+            Ok(object) => object,
+            Err(e) => return Poll::Ready(Err(e)),
+        };
+        match object {
+            Object::File {
+                node, pos, read, ..
+            } => {
+                if !*read {
+                    return Poll::Ready(err(libc::EBADF));
+                }
+                let data = node.borrow();
+                let start = (offset.unwrap_or(*pos) as usize).min(data.len());
+                let len = read_into(&data[start..], &mut bufs);
+                if offset.is_none() {
+                    *pos += len as u64;
+                }
+                Poll::Ready(Ok(len))
+            }
+            Object::Stream(Some(end)) => {
+                let mut rx = end.rx.borrow_mut();
+                if rx.data.is_empty() {
+                    return if rx.write_closed || rx.read_closed {
+                        Poll::Ready(Ok(0))
+                    } else {
+                        Poll::Pending
+                    };
+                }
+                let (a, b) = rx.data.as_slices();
+                let len = read_into(&[a, b].concat(), &mut bufs);
+                rx.data.drain(..len);
+                Poll::Ready(Ok(len))
+            }
+            Object::Dgram(queue) => match queue.pop_front() {
+                Some((data, _)) => Poll::Ready(Ok(read_into(&data, &mut bufs))),
+                None => Poll::Pending,
+            },
+            Object::Stream(None) | Object::Listener(_) => Poll::Ready(err(libc::ENOTCONN)),
+        }
+    }
+
+    fn write(&mut self, fd: RawFd, offset: Option<u64>, bufs: Vec<&[u8]>) -> io::Result<usize> {
+        match self.object(fd)? {
+            Object::File {
+                node,
+                pos,
+                write,
+                append,
+                ..
+            } => {
+                if !*write {
+                    return err(libc::EBADF);
+                }
+                let mut data = node.borrow_mut();
+                let start = if *append {
+                    data.len()
+                } else {
+                    offset.unwrap_or(*pos) as usize
+                };
+                let len = bufs.iter().map(|buf| buf.len()).sum::<usize>();
+                if data.len() < start + len {
+                    data.resize(start + len, 0);
+                }
+                let mut cur = start;
+                for buf in bufs {
+                    data[cur..cur + buf.len()].copy_from_slice(buf);
+                    cur += buf.len();
+                }
+                if offset.is_none() {
+                    *pos = cur as u64;
+                }
+                Ok(len)
+            }
+            Object::Stream(Some(end)) => {
+                let mut tx = end.tx.borrow_mut();
+                if tx.read_closed || tx.write_closed {
+                    return err(libc::EPIPE);
+                }
+                let mut len = 0;
+                for buf in bufs {
+                    tx.data.extend(buf);
+                    len += buf.len();
+                }
+                Ok(len)
+            }
+            Object::Dgram(_) => {
+                let peer = sock(fd)
+                    .peer_addr()
+                    .map_err(|_| io::Error::from_raw_os_error(libc::EDESTADDRREQ))?;
+                self.send_to(fd, bufs, &peer)
+            }
+            Object::Stream(None) | Object::Listener(_) => err(libc::ENOTCONN),
+        }
+    }
+}
+
+impl Backend for Sim {
+    fn push(&mut self, mut op: Key<dyn OpCode>) -> Poll<io::Result<usize>> {
+        let delay = match op.view() {
+            OpView::Timeout(delay) => Some(delay),
+            _ => None,
+        };
+        match delay {
+            Some(delay) => self.schedule(op, Ok(0), self.now + delay, true),
+            None => self.pending.push_back(op),
+        }
+        Poll::Pending
+    }
+
+    fn cancel(&mut self, op: &mut Key<dyn OpCode>) {
+        let user_data = op.user_data();
+        let key = if let Some(index) = self.pending.iter().position(|k| k.user_data() == user_data)
+        {
+            self.pending.remove(index)
+        } else {
+            let timer = self
+                .scheduled
+                .iter()
+                .find(|(_, c)| c.timer && c.key.user_data() == user_data)
+                .map(|(k, _)| *k);
+            timer.and_then(|k| self.scheduled.remove(&k)).map(|c| c.key)
+        };
+        // The operations already performed complete with their results.
+        if let Some(key) = key {
+            unsafe { key.complete(Err(cancelled_error())) };
+        }
+    }
+
+    fn cancel_fd(&mut self, fd: RawFd) {
+        for mut key in std::mem::take(&mut self.pending) {
+            if view_fd(&key.view()) == Some(fd) {
+                unsafe { key.complete(Err(cancelled_error())) };
+            } else {
+                self.pending.push_back(key);
+            }
+        }
+    }
+
+    fn poll(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        loop {
+            self.sweep();
+            self.progress();
+            if let Some(&(due, ..)) = self.scheduled.keys().next() {
+                if let Some(timeout) = timeout {
+                    if due > self.now + timeout {
+                        self.now += timeout;
+                        return Err(io::ErrorKind::TimedOut.into());
+                    }
+                }
+                self.now = self.now.max(due);
+                while let Some(entry) = self.scheduled.first_entry() {
+                    if entry.key().0 > self.now {
+                        break;
+                    }
+                    let completion = entry.remove();
+                    unsafe { completion.key.complete(completion.res) };
+                }
+                self.notify.take();
+                return Ok(());
+            }
+            if self.notify.take() {
+                return Ok(());
+            }
+            match timeout {
+                Some(timeout) => {
+                    self.now += timeout;
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+                None => self.notify.wait(),
+            }
+        }
+    }
+
+    fn handle(&self) -> NotifyHandle {
+        let notify = self.notify.clone();
+        NotifyHandle::from_fn(move || {
+            notify.notify();
+            Ok(())
+        })
+    }
+}
+
+impl AsRawFd for Sim {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
@@ -0,0 +1,239 @@
+//! The in-memory network of the simulation. The connections and datagrams
+//! are matched by the addresses the placeholder sockets are bound to.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io,
+    mem::{ManuallyDrop, MaybeUninit},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    os::fd::{FromRawFd, OwnedFd, RawFd},
+    rc::Rc,
+    task::Poll,
+};
+
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+
+use super::{Object, Sim, err, read_into};
+use crate::op::AddrMut;
+
+#[derive(Default)]
+pub(super) struct Pipe {
+    pub(super) data: VecDeque<u8>,
+    pub(super) write_closed: bool,
+    pub(super) read_closed: bool,
+}
+
+/// One end of a connected stream. Dropping it closes the connection.
+pub(super) struct StreamEnd {
+    pub(super) rx: Rc<RefCell<Pipe>>,
+    pub(super) tx: Rc<RefCell<Pipe>>,
+}
+
+impl StreamEnd {
+    fn pair() -> (Self, Self) {
+        let a = Rc::new(RefCell::new(Pipe::default()));
+        let b = Rc::new(RefCell::new(Pipe::default()));
+        (
+            Self {
+                rx: a.clone(),
+                tx: b.clone(),
+            },
+            Self { rx: b, tx: a },
+        )
+    }
+}
+
+impl Drop for StreamEnd {
+    fn drop(&mut self) {
+        self.rx.borrow_mut().read_closed = true;
+        self.tx.borrow_mut().write_closed = true;
+    }
+}
+
+pub(super) struct Incoming {
+    fd: OwnedFd,
+    addr: SockAddr,
+    end: StreamEnd,
+}
+
+pub(super) fn sock(fd: RawFd) -> ManuallyDrop<Socket> {
+    // SAFETY: the fd is valid during the operation, and it is not closed.
+    ManuallyDrop::new(unsafe { Socket::from_raw_fd(fd) })
+}
+
+fn local_addr(fd: RawFd) -> Option<SocketAddr> {
+    sock(fd).local_addr().ok()?.as_socket()
+}
+
+fn addr_matches(bound: SocketAddr, target: SocketAddr) -> bool {
+    bound.port() == target.port()
+        && (bound.ip() == target.ip()
+            || (bound.ip().is_unspecified() && bound.is_ipv4() == target.is_ipv4()))
+}
+
+/// Bind the socket to an ephemeral port if it is not bound, and get the local
+/// address.
+fn ensure_bound(fd: RawFd, target: SocketAddr) -> io::Result<SocketAddr> {
+    let socket = sock(fd);
+    if let Some(addr) = socket.local_addr()?.as_socket() {
+        if addr.port() != 0 {
+            return Ok(addr);
+        }
+    }
+    let unspecified = match target.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    socket
+        .bind(&SocketAddr::new(target.ip(), 0).into())
+        .or_else(|_| socket.bind(&SocketAddr::new(unspecified, 0).into()))?;
+    let mut addr = socket
+        .local_addr()?
+        .as_socket()
+        .ok_or_else(|| io::Error::from_raw_os_error(libc::EAFNOSUPPORT))?;
+    if addr.ip().is_unspecified() {
+        addr.set_ip(target.ip());
+    }
+    Ok(addr)
+}
+
+impl Sim {
+    pub(super) fn socket(&mut self, domain: i32, ty: i32, protocol: i32) -> io::Result<usize> {
+        #[cfg(linux_all)]
+        let ty = ty & !(libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK);
+        let object = match ty {
+            libc::SOCK_STREAM => Object::Stream(None),
+            libc::SOCK_DGRAM => Object::Dgram(VecDeque::new()),
+            _ => return err(libc::EPROTONOSUPPORT),
+        };
+        let protocol = (protocol != 0).then_some(Protocol::from(protocol));
+        let socket = Socket::new(Domain::from(domain), Type::from(ty), protocol)?;
+        self.insert(socket.into(), object)
+    }
+
+    pub(super) fn connect(&mut self, fd: RawFd, addr: &SockAddr) -> io::Result<usize> {
+        match self.object(fd)? {
+            Object::Stream(None) => {}
+            Object::Stream(Some(_)) => return err(libc::EISCONN),
+            Object::Dgram(_) => {
+                sock(fd).connect(addr)?;
+                return Ok(0);
+            }
+            _ => return err(libc::EINVAL),
+        }
+        let target = addr
+            .as_socket()
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EAFNOSUPPORT))?;
+        let listener = self.objects.iter().find_map(|(listener, (_, object))| {
+            let listening = matches!(object, Object::Stream(None) | Object::Listener(_))
+                && *listener != fd
+                && sock(*listener).is_listener().unwrap_or(false)
+                && local_addr(*listener).is_some_and(|bound| addr_matches(bound, target));
+            listening.then_some(*listener)
+        });
+        let Some(listener) = listener else {
+            return err(libc::ECONNREFUSED);
+        };
+        let local = ensure_bound(fd, target)?;
+        let domain = sock(listener).domain()?;
+        let server = Socket::new(domain, Type::STREAM, None)?;
+        let (client, end) = StreamEnd::pair();
+        let object = self.object(listener)?;
+        if matches!(object, Object::Stream(None)) {
+            *object = Object::Listener(VecDeque::new());
+        }
+        if let Object::Listener(backlog) = object {
+            backlog.push_back(Incoming {
+                fd: server.into(),
+                addr: local.into(),
+                end,
+            });
+        }
+        if let Object::Stream(stream) = self.object(fd)? {
+            *stream = Some(client);
+        }
+        Ok(0)
+    }
+
+    pub(super) fn send_to(
+        &mut self,
+        fd: RawFd,
+        bufs: Vec<&[u8]>,
+        addr: &SockAddr,
+    ) -> io::Result<usize> {
+        if !matches!(self.object(fd)?, Object::Dgram(_)) {
+            return err(libc::EOPNOTSUPP);
+        }
+        let target = addr
+            .as_socket()
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EAFNOSUPPORT))?;
+        let src = ensure_bound(fd, target)?;
+        let data = bufs.concat();
+        let len = data.len();
+        self.sweep();
+        let receiver = self.objects.iter_mut().find(|(receiver, (_, object))| {
+            matches!(object, Object::Dgram(_))
+                && local_addr(**receiver).is_some_and(|bound| addr_matches(bound, target))
+        });
+        // The datagram is lost silently if nobody receives it.
+        if let Some((_, (_, Object::Dgram(queue)))) = receiver {
+            queue.push_back((data, src.into()));
+        }
+        Ok(len)
+    }
+
+    pub(super) fn accept(&mut self, fd: RawFd, mut addr: AddrMut) -> Poll<io::Result<usize>> {
+        let object = match self.object(fd) {
+            Ok(object) => object,
+            Err(e) => return Poll::Ready(Err(e)),
+        };
+        if matches!(object, Object::Stream(None)) && sock(fd).is_listener().unwrap_or(false) {
+            *object = Object::Listener(VecDeque::new());
+        }
+        match object {
+            Object::Listener(backlog) => match backlog.pop_front() {
+                Some(incoming) => {
+                    addr.set(&incoming.addr);
+                    Poll::Ready(self.insert(incoming.fd, Object::Stream(Some(incoming.end))))
+                }
+                None => Poll::Pending,
+            },
+            _ => Poll::Ready(err(libc::EINVAL)),
+        }
+    }
+
+    pub(super) fn recv_from(
+        &mut self,
+        fd: RawFd,
+        mut bufs: Vec<&mut [MaybeUninit<u8>]>,
+        mut addr: AddrMut,
+    ) -> Poll<io::Result<usize>> {
+        match self.object(fd) {
+            Ok(Object::Dgram(queue)) => match queue.pop_front() {
+                Some((data, src)) => {
+                    addr.set(&src);
+                    Poll::Ready(Ok(read_into(&data, &mut bufs)))
+                }
+                None => Poll::Pending,
+            },
+            Ok(_) => Poll::Ready(err(libc::EOPNOTSUPP)),
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+
+    pub(super) fn shutdown(&mut self, fd: RawFd, how: i32) -> io::Result<usize> {
+        match self.object(fd)? {
+            Object::Stream(Some(end)) => {
+                if how != libc::SHUT_WR {
+                    end.rx.borrow_mut().read_closed = true;
+                }
+                if how != libc::SHUT_RD {
+                    end.tx.borrow_mut().write_closed = true;
+                }
+                Ok(0)
+            }
+            _ => err(libc::ENOTCONN),
+        }
+    }
+}
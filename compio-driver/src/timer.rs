@@ -0,0 +1,101 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+/// The timers of [`Timeout`] operations, for drivers without native timer
+/// support. The driver should wait at most [`TimerQueue::min_timeout`], and
+/// complete the expired operations after waiting.
+///
+/// [`Timeout`]: crate::op::Timeout
+#[derive(Debug, Default)]
+pub(crate) struct TimerQueue {
+    wheel: BTreeSet<(Instant, usize)>,
+    deadlines: HashMap<usize, Instant>,
+}
+
+impl TimerQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the op with `user_data`, which expires after `delay`.
+    pub fn insert(&mut self, user_data: usize, delay: Duration) {
+        let deadline = Instant::now() + delay;
+        self.wheel.insert((deadline, user_data));
+        self.deadlines.insert(user_data, deadline);
+    }
+
+    /// Remove the op with `user_data`. The return value indicates whether the
+    /// timer was still pending.
+    pub fn remove(&mut self, user_data: usize) -> bool {
+        if let Some(deadline) = self.deadlines.remove(&user_data) {
+            self.wheel.remove(&(deadline, user_data));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wheel.is_empty()
+    }
+
+    /// The duration until the earliest timer expires.
+    pub fn min_timeout(&self) -> Option<Duration> {
+        self.wheel
+            .first()
+            .map(|(deadline, _)| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// The duration to wait, the smaller of `timeout` and
+    /// [`TimerQueue::min_timeout`].
+    pub fn wait_timeout(&self, timeout: Option<Duration>) -> Option<Duration> {
+        match (timeout, self.min_timeout()) {
+            (Some(timeout), Some(min_timeout)) => Some(timeout.min(min_timeout)),
+            (timeout, min_timeout) => timeout.or(min_timeout),
+        }
+    }
+
+    /// Pop the `user_data` of the expired timers.
+    pub fn pop_expired(&mut self) -> Vec<usize> {
+        if self.is_empty() {
+            return vec![];
+        }
+        let now = Instant::now();
+        let mut expired = vec![];
+        while let Some(&(deadline, user_data)) = self.wheel.first() {
+            if deadline > now {
+                break;
+            }
+            self.wheel.pop_first();
+            self.deadlines.remove(&user_data);
+            expired.push(user_data);
+        }
+        expired
+    }
+}
+
+#[test]
+fn timer_min_timeout() {
+    let mut timers = TimerQueue::new();
+    assert_eq!(timers.min_timeout(), None);
+
+    timers.insert(0, Duration::from_secs(1));
+    timers.insert(1, Duration::from_secs(10));
+    let min_timeout = timers.min_timeout().unwrap().as_secs_f32();
+
+    assert!(min_timeout < 1.);
+}
+
+#[test]
+fn timer_remove() {
+    let mut timers = TimerQueue::new();
+    timers.insert(0, Duration::ZERO);
+    timers.insert(1, Duration::ZERO);
+    assert!(timers.remove(0));
+    assert!(!timers.remove(0));
+
+    assert_eq!(timers.pop_expired(), vec![1]);
+    assert!(timers.is_empty());
+}
@@ -1,4 +1,6 @@
-use std::{ffi::CString, marker::PhantomPinned, net::Shutdown, os::fd::OwnedFd};
+use std::{
+    collections::VecDeque, ffi::CString, marker::PhantomPinned, net::Shutdown, os::fd::OwnedFd,
+};
 
 use compio_buf::{
     IntoInner, IoBuf, IoBufMut, IoSlice, IoSliceMut, IoVectoredBuf, IoVectoredBufMut,
@@ -13,12 +15,35 @@ pub struct OpenFile {
     pub(crate) path: CString,
     pub(crate) flags: i32,
     pub(crate) mode: libc::mode_t,
+    #[cfg_attr(not(io_uring), allow(dead_code))]
+    pub(crate) direct: bool,
 }
 
 impl OpenFile {
     /// Create [`OpenFile`].
     pub fn new(path: CString, flags: i32, mode: libc::mode_t) -> Self {
-        Self { path, flags, mode }
+        Self {
+            path,
+            flags,
+            mode,
+            direct: false,
+        }
+    }
+
+    /// Create [`OpenFile`] which opens the file as a direct descriptor. The
+    /// result should be converted by [`Proactor::direct_fd`].
+    ///
+    /// ## Platform specific
+    /// * io-uring: the file is installed into the fixed file table, without a
+    ///   normal fd.
+    /// * polling: it is the same as [`OpenFile::new`].
+    ///
+    /// [`Proactor::direct_fd`]: crate::Proactor::direct_fd
+    pub fn new_direct(path: CString, flags: i32, mode: libc::mode_t) -> Self {
+        Self {
+            direct: true,
+            ..Self::new(path, flags, mode)
+        }
     }
 }
 
@@ -92,6 +117,8 @@ pub struct ReadVectoredAt<T: IoVectoredBufMut, S> {
     pub(crate) slices: Vec<IoSliceMut>,
     #[cfg(freebsd)]
     pub(crate) aiocb: libc::aiocb,
+    #[cfg(linux_aio)]
+    pub(crate) iocb: crate::sys::Iocb,
     _p: PhantomPinned,
 }
 
@@ -105,6 +132,8 @@ impl<T: IoVectoredBufMut, S> ReadVectoredAt<T, S> {
             slices: vec![],
             #[cfg(freebsd)]
             aiocb: unsafe { std::mem::zeroed() },
+            #[cfg(linux_aio)]
+            iocb: Default::default(),
             _p: PhantomPinned,
         }
     }
@@ -126,6 +155,8 @@ pub struct WriteVectoredAt<T: IoVectoredBuf, S> {
     pub(crate) slices: Vec<IoSlice>,
     #[cfg(freebsd)]
     pub(crate) aiocb: libc::aiocb,
+    #[cfg(linux_aio)]
+    pub(crate) iocb: crate::sys::Iocb,
     _p: PhantomPinned,
 }
 
@@ -139,6 +170,8 @@ impl<T: IoVectoredBuf, S> WriteVectoredAt<T, S> {
             slices: vec![],
             #[cfg(freebsd)]
             aiocb: unsafe { std::mem::zeroed() },
+            #[cfg(linux_aio)]
+            iocb: Default::default(),
             _p: PhantomPinned,
         }
     }
@@ -222,6 +255,8 @@ pub struct CreateSocket {
     pub(crate) domain: i32,
     pub(crate) socket_type: i32,
     pub(crate) protocol: i32,
+    #[cfg_attr(not(io_uring), allow(dead_code))]
+    pub(crate) direct: bool,
 }
 
 impl CreateSocket {
@@ -231,6 +266,16 @@ impl CreateSocket {
             domain,
             socket_type,
             protocol,
+            direct: false,
+        }
+    }
+
+    /// Create [`CreateSocket`] which creates the socket as a direct
+    /// descriptor. See [`OpenFile::new_direct`].
+    pub fn new_direct(domain: i32, socket_type: i32, protocol: i32) -> Self {
+        Self {
+            direct: true,
+            ..Self::new(domain, socket_type, protocol)
         }
     }
 }
@@ -251,6 +296,7 @@ pub struct Accept<S> {
     pub(crate) buffer: sockaddr_storage,
     pub(crate) addr_len: socklen_t,
     pub(crate) accepted_fd: Option<OwnedFd>,
+    pub(crate) direct: bool,
     _p: PhantomPinned,
 }
 
@@ -262,10 +308,20 @@ impl<S> Accept<S> {
             buffer: unsafe { std::mem::zeroed() },
             addr_len: std::mem::size_of::<sockaddr_storage>() as _,
             accepted_fd: None,
+            direct: false,
             _p: PhantomPinned,
         }
     }
 
+    /// Create [`Accept`] which accepts the connection as a direct descriptor.
+    /// See [`OpenFile::new_direct`].
+    pub fn new_direct(fd: S) -> Self {
+        Self {
+            direct: true,
+            ..Self::new(fd)
+        }
+    }
+
     /// Get the remote address from the inner buffer.
     pub fn into_addr(mut self) -> SockAddr {
         std::mem::forget(self.accepted_fd.take());
@@ -273,6 +329,50 @@ impl<S> Accept<S> {
     }
 }
 
+/// Accept connections with one submission, see [`Multishot`].
+///
+/// The intermediate results are the accepted fds, and the final result is the
+/// last accepted fd or an error. The operation could complete at any time, and
+/// it should be submitted again to continue accepting.
+///
+/// ## Platform specific
+/// * io-uring: it is `IORING_OP_ACCEPT` with `IORING_ACCEPT_MULTISHOT` since
+///   Linux 5.19. On older kernels, it accepts only one connection.
+/// * polling: it accepts only one connection, the same as [`Accept`].
+pub struct AcceptMulti<S> {
+    pub(crate) op: Accept<S>,
+    pub(crate) accepted: VecDeque<OwnedFd>,
+}
+
+impl<S> AcceptMulti<S> {
+    /// Create [`AcceptMulti`].
+    pub fn new(fd: S) -> Self {
+        Self {
+            op: Accept::new(fd),
+            accepted: VecDeque::new(),
+        }
+    }
+}
+
+impl<S> Multishot for AcceptMulti<S> {
+    type Item = OwnedFd;
+
+    fn pop_multishot(&mut self) -> Option<Self::Item> {
+        self.accepted.pop_front()
+    }
+}
+
+impl<S> IntoInner for AcceptMulti<S> {
+    type Inner = VecDeque<OwnedFd>;
+
+    /// Get the intermediate results which are not taken. The fd of the final
+    /// result is not owned by the operation anymore.
+    fn into_inner(mut self) -> Self::Inner {
+        std::mem::forget(self.op.accepted_fd.take());
+        self.accepted
+    }
+}
+
 /// Receive data from remote.
 pub struct Recv<T: IoBufMut, S> {
     pub(crate) fd: S,
@@ -459,7 +559,7 @@ impl<T: IoVectoredBuf, C: IoBuf, S> SendMsg<T, C, S> {
     /// This function will panic if the control message buffer is misaligned.
     pub fn new(fd: S, buffer: T, control: C, addr: SockAddr) -> Self {
         assert!(
-            control.as_buf_ptr().cast::<libc::cmsghdr>().is_aligned(),
+            control.buf_len() == 0 || control.as_buf_ptr().cast::<libc::cmsghdr>().is_aligned(),
             "misaligned control message buffer"
         );
         Self {
@@ -514,3 +614,73 @@ impl<S> PollOnce<S> {
         Self { fd, interest }
     }
 }
+
+/// Move data between two fds without copying it to the user space, see
+/// `splice(2)`. One of the fds should be a pipe.
+///
+/// ## Platform specific
+/// * io-uring: it is `IORING_OP_SPLICE`.
+/// * polling: it is a non-blocking `splice(2)`, which waits for the fd that
+///   blocks.
+#[cfg(linux_all)]
+pub struct Splice<S1, S2> {
+    pub(crate) fd_in: S1,
+    pub(crate) offset_in: i64,
+    pub(crate) fd_out: S2,
+    pub(crate) offset_out: i64,
+    pub(crate) len: usize,
+    #[cfg(any(not(io_uring), fusion))]
+    pub(crate) wait_fd: libc::c_int,
+}
+
+#[cfg(linux_all)]
+impl<S1, S2> Splice<S1, S2> {
+    /// Create [`Splice`]. If the offset is `None`, the current position of the
+    /// fd is used and updated. The offset of a pipe should be `None`.
+    pub fn new(
+        fd_in: S1,
+        offset_in: Option<u64>,
+        fd_out: S2,
+        offset_out: Option<u64>,
+        len: usize,
+    ) -> Self {
+        Self {
+            fd_in,
+            offset_in: offset_in.map_or(-1, |offset| offset as _),
+            fd_out,
+            offset_out: offset_out.map_or(-1, |offset| offset as _),
+            len,
+            #[cfg(any(not(io_uring), fusion))]
+            wait_fd: -1,
+        }
+    }
+}
+
+/// Duplicate data between two pipes without consuming it, see `tee(2)`.
+///
+/// ## Platform specific
+/// * io-uring: it is `IORING_OP_TEE`.
+/// * polling: it is a non-blocking `tee(2)`, which waits for the pipe that
+///   blocks.
+#[cfg(linux_all)]
+pub struct Tee<S1, S2> {
+    pub(crate) fd_in: S1,
+    pub(crate) fd_out: S2,
+    pub(crate) len: usize,
+    #[cfg(any(not(io_uring), fusion))]
+    pub(crate) wait_fd: libc::c_int,
+}
+
+#[cfg(linux_all)]
+impl<S1, S2> Tee<S1, S2> {
+    /// Create [`Tee`].
+    pub fn new(fd_in: S1, fd_out: S2, len: usize) -> Self {
+        Self {
+            fd_in,
+            fd_out,
+            len,
+            #[cfg(any(not(io_uring), fusion))]
+            wait_fd: -1,
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use compio_buf::{BufResult, IntoInner};
+use compio_driver::{
+    AsyncifyPool, DispatchOptions, Key, Priority, Proactor, PushEntry, op::Asyncify,
+};
+
+/// Occupy a thread of the pool until the returned sender is dropped.
+fn block(pool: &AsyncifyPool) -> mpsc::Sender<()> {
+    let (tx, rx) = mpsc::channel::<()>();
+    let (started_tx, started_rx) = mpsc::channel();
+    pool.dispatch(move || {
+        started_tx.send(()).unwrap();
+        rx.recv().ok();
+    })
+    .unwrap();
+    started_rx.recv().unwrap();
+    tx
+}
+
+fn blocking_op(
+    started: mpsc::Sender<()>,
+) -> (
+    mpsc::Sender<()>,
+    Asyncify<impl FnOnce() -> BufResult<usize, ()>, ()>,
+) {
+    let (tx, rx) = mpsc::channel::<()>();
+    let op = Asyncify::new(move || {
+        started.send(()).unwrap();
+        rx.recv().ok();
+        BufResult(Ok(0), ())
+    });
+    (tx, op)
+}
+
+fn wait<O>(driver: &mut Proactor, mut key: Key<O>) -> BufResult<usize, O> {
+    loop {
+        match driver.pop(key) {
+            PushEntry::Pending(k) => key = k,
+            PushEntry::Ready((res, _)) => break res,
+        }
+        driver.poll(None).unwrap();
+    }
+}
+
+fn push<O: compio_driver::OpCode + 'static>(driver: &mut Proactor, op: O) -> Key<O> {
+    match driver.push(op) {
+        PushEntry::Pending(key) => key,
+        PushEntry::Ready(_) => unreachable!("blocking operations are never ready"),
+    }
+}
+
+#[derive(Default)]
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+#[test]
+fn priority() {
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(8)
+        .build();
+    let blocker = block(&pool);
+
+    let order = Arc::new(Mutex::new(vec![]));
+    let (done_tx, done_rx) = mpsc::channel();
+    for priority in [Priority::Low, Priority::Normal, Priority::High] {
+        let order = order.clone();
+        let done_tx = done_tx.clone();
+        pool.dispatch_with(
+            move || {
+                order.lock().unwrap().push(priority);
+                done_tx.send(()).unwrap();
+            },
+            priority,
+        )
+        .unwrap();
+    }
+    assert_eq!(pool.queue_len(), 3);
+    drop(blocker);
+
+    for _ in 0..3 {
+        done_rx.recv().unwrap();
+    }
+    assert_eq!(
+        *order.lock().unwrap(),
+        [Priority::High, Priority::Normal, Priority::Low]
+    );
+}
+
+#[test]
+fn queue_capacity() {
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(1)
+        .build();
+    let blocker = block(&pool);
+
+    let (done_tx, done_rx) = mpsc::channel();
+    let tx = done_tx.clone();
+    pool.dispatch(move || tx.send(()).unwrap()).unwrap();
+    let f = pool
+        .dispatch(move || done_tx.send(()).unwrap())
+        .unwrap_err()
+        .0;
+
+    let flag = Arc::new(Flag::default());
+    let waker = Waker::from(flag.clone());
+    assert!(
+        pool.poll_ready(&mut Context::from_waker(&waker))
+            .is_pending()
+    );
+    drop(blocker);
+
+    // The queued one is taken by the thread.
+    let start = Instant::now();
+    while !flag.0.load(Ordering::Acquire) {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(
+        pool.poll_ready(&mut Context::from_waker(&waker)),
+        Poll::Ready(())
+    );
+    pool.dispatch(f).unwrap();
+    done_rx.recv().unwrap();
+    done_rx.recv().unwrap();
+}
+
+#[test]
+fn cancel() {
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(4)
+        .build();
+    let blocker = block(&pool);
+
+    let ran = Arc::new(AtomicBool::new(false));
+    let flag = ran.clone();
+    let id = pool
+        .dispatch_with(
+            move || flag.store(true, Ordering::Release),
+            Priority::Normal,
+        )
+        .unwrap();
+    assert!(pool.cancel(id));
+    assert!(!pool.cancel(id));
+    assert_eq!(pool.queue_len(), 0);
+    drop(blocker);
+
+    let (tx, rx) = mpsc::channel();
+    pool.dispatch(move || tx.send(()).unwrap()).unwrap();
+    rx.recv().unwrap();
+    assert!(!ran.load(Ordering::Acquire));
+}
+
+#[test]
+fn named() {
+    let pool = AsyncifyPool::builder()
+        .name("asyncify-test")
+        .recv_timeout(Duration::from_millis(10))
+        .build();
+    assert_eq!(pool.name(), Some("asyncify-test"));
+
+    let (tx, rx) = mpsc::channel();
+    pool.dispatch(move || tx.send(thread::current().name().map(String::from)).unwrap())
+        .unwrap();
+    assert_eq!(rx.recv().unwrap().as_deref(), Some("asyncify-test"));
+
+    // The idle thread exits after the timeout.
+    let start = Instant::now();
+    while pool.thread_count() > 0 {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn backlog() {
+    let mut driver = Proactor::builder().thread_pool_limit(1).build().unwrap();
+
+    let (started_tx, started_rx) = mpsc::channel();
+    let (tx, op) = blocking_op(started_tx);
+    let blocker = push(&mut driver, op);
+    started_rx.recv().unwrap();
+
+    // The pool is full, and the operations wait in the driver instead of
+    // blocking the push.
+    let count = Arc::new(AtomicUsize::new(0));
+    let keys = (0..4)
+        .map(|_| {
+            let count = count.clone();
+            push(
+                &mut driver,
+                Asyncify::new(move || {
+                    count.fetch_add(1, Ordering::AcqRel);
+                    BufResult(Ok(0), ())
+                }),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(count.load(Ordering::Acquire), 0);
+    tx.send(()).unwrap();
+
+    wait(&mut driver, blocker).unwrap();
+    for key in keys {
+        wait(&mut driver, key).unwrap();
+    }
+    assert_eq!(count.load(Ordering::Acquire), 4);
+}
+
+#[test]
+fn cancel_op() {
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(1)
+        .build();
+    let mut driver = Proactor::builder()
+        .reuse_thread_pool(pool.clone())
+        .build()
+        .unwrap();
+
+    let (started_tx, started_rx) = mpsc::channel();
+    let (tx, op) = blocking_op(started_tx);
+    let blocker = push(&mut driver, op);
+    started_rx.recv().unwrap();
+
+    let ran = Arc::new(AtomicBool::new(false));
+    let op = || {
+        let ran = ran.clone();
+        Asyncify::new(move || {
+            ran.store(true, Ordering::Release);
+            BufResult(Ok(0), ())
+        })
+        .with_options(DispatchOptions::new().cancel_queued(true))
+    };
+    // One is queued in the pool, and the other waits in the driver.
+    let queued = push(&mut driver, op());
+    let backlogged = push(&mut driver, op());
+    assert_eq!(pool.queue_len(), 1);
+    assert!(driver.cancel(backlogged).is_none());
+    assert!(driver.cancel(queued).is_none());
+    assert_eq!(pool.queue_len(), 0);
+    tx.send(()).unwrap();
+
+    wait(&mut driver, blocker).unwrap();
+    let key = push(&mut driver, Asyncify::new(|| BufResult(Ok(1), ())));
+    assert_eq!(wait(&mut driver, key).0.unwrap(), 1);
+    assert!(!ran.load(Ordering::Acquire));
+}
+
+#[test]
+fn dedicated_pool() {
+    let mut driver = Proactor::builder().thread_pool_limit(1).build().unwrap();
+
+    let (started_tx, started_rx) = mpsc::channel();
+    let (tx, op) = blocking_op(started_tx);
+    let blocker = push(&mut driver, op);
+    started_rx.recv().unwrap();
+
+    // Not blocked by the full pool of the proactor.
+    let pool = AsyncifyPool::builder().name("dedicated").build();
+    let op = Asyncify::new(|| BufResult(Ok(0), thread::current().name().map(String::from)))
+        .with_options(DispatchOptions::new().pool(pool).priority(Priority::High));
+    let key = push(&mut driver, op);
+    let name = wait(&mut driver, key).into_inner().unwrap().1;
+    assert_eq!(name.as_deref(), Some("dedicated"));
+
+    tx.send(()).unwrap();
+    wait(&mut driver, blocker).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn op_dispatch() {
+    use std::{ffi::CString, os::fd::FromRawFd};
+
+    use compio_driver::{DriverType, OwnedFd, op::OpenFile};
+
+    if DriverType::is_iouring() {
+        // The file operations are not blocking with io-uring.
+        return;
+    }
+
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(1)
+        .build();
+    let mut driver = Proactor::builder()
+        .op_dispatch(DispatchOptions::new().pool(pool.clone()))
+        .build()
+        .unwrap();
+    let blocker = block(&pool);
+
+    // The built-in operation is queued in the dedicated pool.
+    let op = OpenFile::new(
+        CString::new("Cargo.toml").unwrap(),
+        libc::O_CLOEXEC | libc::O_RDONLY,
+        0o666,
+    );
+    let key = push(&mut driver, op);
+    assert_eq!(pool.queue_len(), 1);
+    drop(blocker);
+
+    let fd = wait(&mut driver, key).0.unwrap();
+    drop(unsafe { OwnedFd::from_raw_fd(fd as _) });
+}
@@ -1,9 +1,16 @@
-use std::{io, time::Duration};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
 
-use compio_buf::BufResult;
+use compio_buf::{BufResult, IntoInner};
 use compio_driver::{
-    AsRawFd, OpCode, OwnedFd, Proactor, PushEntry, SharedFd, TakeBuffer,
-    op::{Asyncify, CloseFile, ReadAt, ReadManagedAt},
+    AsRawFd, DriverType, Key, OpCode, OwnedFd, Proactor, ProactorBuilder, PushEntry, RingMessage,
+    SetupReport, SharedFd, TakeBuffer,
+    op::{
+        Asyncify, BufResultExt, CloseFile, MsgRing, MsgRingFd, ReadAt, ReadFixedAt, ReadManagedAt,
+        Timeout,
+    },
 };
 
 #[cfg(windows)]
@@ -64,6 +71,16 @@ fn push_and_wait<O: OpCode + 'static>(driver: &mut Proactor, op: O) -> BufResult
     push_and_wait_flags(driver, op).0
 }
 
+fn wait<O>(driver: &mut Proactor, mut key: Key<O>) -> BufResult<usize, O> {
+    loop {
+        match driver.pop(key) {
+            PushEntry::Pending(k) => key = k,
+            PushEntry::Ready((res, _)) => break res,
+        }
+        driver.poll(None).unwrap();
+    }
+}
+
 #[test]
 fn timeout() {
     let mut driver = Proactor::new().unwrap();
@@ -72,6 +89,205 @@ fn timeout() {
     assert_eq!(err.kind(), io::ErrorKind::TimedOut);
 }
 
+#[test]
+fn timeout_op() {
+    let mut driver = Proactor::new().unwrap();
+
+    let start = Instant::now();
+    let (res, _) = push_and_wait(&mut driver, Timeout::new(Duration::from_millis(100))).unwrap();
+    assert_eq!(res, 0);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn timeout_op_fallback() {
+    let mut driver = Proactor::builder()
+        .max_kernel_version(5, 15)
+        .build()
+        .unwrap();
+    assert!(!driver.setup_report().timeout_etime_success);
+
+    let start = Instant::now();
+    let (res, _) = push_and_wait(&mut driver, Timeout::new(Duration::from_millis(100))).unwrap();
+    assert_eq!(res, 0);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    // The expired timer doesn't break the chain.
+    let mut link = driver.link();
+    let timer = link.push(Timeout::new(Duration::from_millis(1)));
+    let next = link.push(Timeout::new(Duration::from_millis(1)));
+    link.submit();
+    wait(&mut driver, timer).unwrap();
+    wait(&mut driver, next).unwrap();
+}
+
+#[test]
+fn register_fd() {
+    let mut driver = Proactor::builder().fixed_files(16).build().unwrap();
+
+    let fd = open_file(&mut driver);
+    driver.attach(fd.as_raw_fd()).unwrap();
+    let fd = SharedFd::from(driver.register_fd(fd).unwrap());
+
+    let (res, _) =
+        push_and_wait(&mut driver, ReadAt::new(fd, 0, Vec::with_capacity(1024))).unwrap();
+    assert!(res > 0);
+}
+
+#[test]
+fn fixed_files_fallback() {
+    // Larger than the limit of the kernel, and the table is disabled.
+    let mut driver = Proactor::builder().fixed_files(1 << 24).build().unwrap();
+    assert!(!driver.setup_report().fixed_files);
+
+    let fd = open_file(&mut driver);
+    driver.attach(fd.as_raw_fd()).unwrap();
+    let fd = SharedFd::from(driver.register_fd(fd).unwrap());
+    let (res, _) =
+        push_and_wait(&mut driver, ReadAt::new(fd, 0, Vec::with_capacity(1024))).unwrap();
+    assert!(res > 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn direct_fd() {
+    use std::ffi::CString;
+
+    use compio_driver::op::OpenFile;
+
+    let mut driver = Proactor::builder().fixed_files(16).build().unwrap();
+
+    let op = OpenFile::new_direct(
+        CString::new("Cargo.toml").unwrap(),
+        libc::O_CLOEXEC | libc::O_RDONLY,
+        0o666,
+    );
+    let (res, _) = push_and_wait(&mut driver, op).unwrap();
+    let fd = SharedFd::from(unsafe { driver.direct_fd(res) });
+
+    let (res, _) =
+        push_and_wait(&mut driver, ReadAt::new(fd, 0, Vec::with_capacity(1024))).unwrap();
+    assert!(res > 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn accept_direct() {
+    use std::{
+        net::TcpListener,
+        os::fd::{FromRawFd, IntoRawFd},
+    };
+
+    use compio_driver::op::Accept;
+
+    let mut driver = Proactor::builder().fixed_files(16).build().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener = SharedFd::new(unsafe { OwnedFd::from_raw_fd(listener.into_raw_fd()) });
+    driver.attach(listener.as_raw_fd()).unwrap();
+
+    let key = match driver.push(Accept::new_direct(listener)) {
+        PushEntry::Pending(key) => key,
+        PushEntry::Ready(_) => unreachable!("no connection to accept"),
+    };
+    let _stream = std::net::TcpStream::connect(addr).unwrap();
+    let (res, op) = wait(&mut driver, key).unwrap();
+    let fd = unsafe { driver.direct_fd(res) };
+    // The accepted fd is owned by the `FixedFd` only.
+    drop(op);
+    if fd.index().is_none() {
+        assert!(unsafe { libc::fcntl(res as _, libc::F_GETFD) } >= 0);
+    }
+}
+
+#[test]
+fn registered_buffers() {
+    let mut driver = Proactor::builder()
+        .registered_buffers(2, 4096)
+        .build()
+        .unwrap();
+
+    let fd = open_file(&mut driver);
+    driver.attach(fd.as_raw_fd()).unwrap();
+    let fd = SharedFd::new(fd);
+
+    let buffers = driver.registered_buffers().unwrap().clone();
+    let first = buffers.take().unwrap();
+    let second = buffers.take().unwrap();
+    assert!(buffers.take().is_none());
+    drop(first);
+
+    let BufResult(res, buffer) = push_and_wait(&mut driver, ReadFixedAt::new(fd, 0, second))
+        .into_inner()
+        .map_advanced();
+    let res = res.unwrap();
+    assert!(res > 0);
+    assert_eq!(buffer.len(), res);
+    assert_eq!(&buffer[..], &std::fs::read("Cargo.toml").unwrap()[..res]);
+}
+
+#[test]
+fn link() {
+    let mut driver = Proactor::new().unwrap();
+
+    let fd = open_file(&mut driver);
+    let fd = SharedFd::new(fd);
+    driver.attach(fd.as_raw_fd()).unwrap();
+
+    let mut link = driver.link();
+    let read = link.push(ReadAt::new(fd.clone(), 0, Vec::with_capacity(1024)));
+    let timer = link.push(Timeout::new(Duration::from_millis(1)));
+    let next = link.push(Timeout::new(Duration::from_millis(1)));
+    link.submit();
+
+    let (res, _) = wait(&mut driver, read).unwrap();
+    assert!(res > 0);
+    wait(&mut driver, timer).unwrap();
+    wait(&mut driver, next).unwrap();
+}
+
+#[test]
+fn link_blocking() {
+    let mut driver = Proactor::new().unwrap();
+
+    let mut link = driver.link();
+    let timer = link.push(Timeout::new(Duration::from_millis(1)));
+    let blocking = link.push(Asyncify::new(|| BufResult(Ok(114514), ())));
+    let failed = link.push(Asyncify::new(|| {
+        BufResult(Err(io::Error::from(io::ErrorKind::Other)), ())
+    }));
+    let next = link.push(Timeout::new(Duration::from_millis(1)));
+    link.submit();
+
+    wait(&mut driver, timer).unwrap();
+    let (res, _) = wait(&mut driver, blocking).unwrap();
+    assert_eq!(res, 114514);
+    assert!(wait(&mut driver, failed).0.is_err());
+    let err = wait(&mut driver, next).0.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn link_timeout() {
+    let mut driver = Proactor::new().unwrap();
+
+    let start = Instant::now();
+    let mut link = driver.link();
+    let slow = link.push_with_timeout(
+        Timeout::new(Duration::from_secs(10)),
+        Duration::from_millis(100),
+    );
+    let next = link.push(Timeout::new(Duration::from_millis(1)));
+    link.submit();
+
+    let err = wait(&mut driver, slow).0.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    let err = wait(&mut driver, next).0.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
 #[test]
 fn register_multiple() {
     const TASK_LEN: usize = 5;
@@ -103,6 +319,110 @@ fn register_multiple() {
     // Don't async close because the reading operations may have not completed.
 }
 
+#[test]
+fn supports() {
+    /// `IORING_OP_NOP`, which is supported by all kernels with io-uring.
+    const NOP: u8 = 0;
+
+    let driver = Proactor::new().unwrap();
+    if DriverType::is_iouring() {
+        assert!(driver.supports(NOP));
+        assert!(!driver.supports(u8::MAX));
+    } else {
+        assert!(!driver.supports(NOP));
+    }
+}
+
+#[test]
+fn setup_report() {
+    let first = Proactor::new().unwrap();
+    let mut driver = Proactor::builder()
+        .single_issuer(true)
+        .defer_taskrun(true)
+        .submit_all(true)
+        .attach_wq(first.as_raw_fd())
+        .register_ring_fd(true)
+        // Less than the capacity, which is rejected.
+        .cqsize(1)
+        .build()
+        .unwrap();
+    let report = driver.setup_report();
+    if DriverType::is_iouring() {
+        assert!(report.attach_wq);
+        assert!(!report.cqsize);
+        assert!(!report.sqpoll);
+    } else {
+        assert_eq!(report, SetupReport::default());
+    }
+
+    let fd = open_file(&mut driver);
+    let fd = SharedFd::new(fd);
+    driver.attach(fd.as_raw_fd()).unwrap();
+    let op = ReadAt::new(fd, 0, Vec::with_capacity(1024));
+    let (n, _) = push_and_wait(&mut driver, op).unwrap();
+    assert!(n > 0);
+
+    let start = Instant::now();
+    push_and_wait(&mut driver, Timeout::new(Duration::from_millis(10))).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+#[test]
+fn msg_ring() {
+    let mut sender = Proactor::builder().fixed_files(16).build().unwrap();
+    let mut receiver = Proactor::builder().fixed_files(16).build().unwrap();
+
+    let op = MsgRing::new(receiver.as_raw_fd(), 42);
+    if !sender.supports(MsgRing::OPCODE) {
+        let err = push_and_wait(&mut sender, op).0.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        return;
+    }
+    push_and_wait(&mut sender, op).unwrap();
+    receiver.poll(Some(Duration::ZERO)).ok();
+    assert!(matches!(
+        receiver.pop_message(),
+        Some(RingMessage::Data(42))
+    ));
+    assert!(receiver.pop_message().is_none());
+
+    let fd = open_file(&mut sender);
+    let fd = sender.register_fd(fd).unwrap();
+    push_and_wait(&mut sender, MsgRingFd::new(receiver.as_raw_fd(), fd)).unwrap();
+    receiver.poll(Some(Duration::ZERO)).ok();
+    let Some(RingMessage::Fd(fd)) = receiver.pop_message() else {
+        panic!("the fd should be received");
+    };
+    let op = ReadAt::new(fd, 0, Vec::with_capacity(1024));
+    let (n, _) = push_and_wait(&mut receiver, op).unwrap();
+    assert!(n > 0);
+}
+
+#[test]
+fn push_batch() {
+    const CHUNK: usize = 16;
+
+    let mut driver = Proactor::new().unwrap();
+
+    let fd = open_file(&mut driver);
+    let fd = SharedFd::new(fd);
+    driver.attach(fd.as_raw_fd()).unwrap();
+
+    let expected = std::fs::read("Cargo.toml").unwrap();
+    let ops = (0..expected.len().div_ceil(CHUNK))
+        .map(|i| ReadAt::new(fd.clone(), (i * CHUNK) as u64, Vec::with_capacity(CHUNK)));
+    let mut content = vec![];
+    for entry in driver.push_batch(ops) {
+        let res = match entry {
+            PushEntry::Pending(key) => wait(&mut driver, key),
+            PushEntry::Ready(res) => res,
+        };
+        let (_, buffer) = res.into_inner().map_advanced().unwrap();
+        content.extend_from_slice(&buffer);
+    }
+    assert_eq!(content, expected);
+}
+
 #[test]
 fn notify() {
     let mut driver = Proactor::new().unwrap();
@@ -147,3 +467,92 @@ fn managed() {
     let op = CloseFile::new(fd.try_unwrap().unwrap());
     push_and_wait(&mut driver, op).unwrap();
 }
+
+#[test]
+fn managed_other_driver() {
+    if !(DriverType::Poll.is_available() && DriverType::IoUring.is_available()) {
+        return;
+    }
+    let mut poll = ProactorBuilder::new();
+    poll.driver(DriverType::Poll);
+    let mut poll = poll.build().unwrap();
+    let mut iour = ProactorBuilder::new();
+    iour.driver(DriverType::IoUring);
+    let mut iour = iour.build().unwrap();
+
+    let fd = SharedFd::new(open_file(&mut poll));
+    poll.attach(fd.as_raw_fd()).unwrap();
+    iour.attach(fd.as_raw_fd()).unwrap();
+
+    // The op is created for the driver of the pool, not of the thread.
+    let pool = poll.create_buffer_pool(4, 1024).unwrap();
+    let op = ReadManagedAt::new(fd.clone(), 0, &pool, 1024).unwrap();
+    let (BufResult(res, op), flags) = push_and_wait_flags(&mut poll, op);
+    assert!(!op.take_buffer(&pool, res, flags).unwrap().is_empty());
+
+    let op = ReadManagedAt::new(fd.clone(), 0, &pool, 1024).unwrap();
+    let BufResult(res, _) = push_and_wait(&mut iour, op);
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+    let op = CloseFile::new(fd.try_unwrap().unwrap());
+    push_and_wait(&mut poll, op).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn fused_op_any_driver() {
+    use compio_driver::op::FileStat;
+
+    for driver_type in [DriverType::Poll, DriverType::IoUring] {
+        if !driver_type.is_available() {
+            continue;
+        }
+        let mut builder = ProactorBuilder::new();
+        builder.driver(driver_type);
+        let mut driver = builder.build().unwrap();
+        let fd = SharedFd::new(open_file(&mut driver));
+        driver.attach(fd.as_raw_fd()).unwrap();
+
+        // The op is created outside the scope of the driver type.
+        let op = FileStat::new(fd.clone());
+        let (res, _) = push_and_wait(&mut driver, op).unwrap();
+        assert_eq!(res, 0);
+
+        let op = CloseFile::new(fd.try_unwrap().unwrap());
+        push_and_wait(&mut driver, op).unwrap();
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn splice_tee() {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    use compio_driver::op::{Splice, Tee};
+
+    fn pipe() -> (OwnedFd, OwnedFd) {
+        let mut fds = [0; 2];
+        let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) };
+        assert_eq!(res, 0);
+        unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) }
+    }
+
+    let mut driver = Proactor::new().unwrap();
+
+    let fd = SharedFd::new(open_file(&mut driver));
+    let (rx1, tx1) = pipe();
+    let (rx2, tx2) = pipe();
+
+    let op = Splice::new(fd, Some(0), tx1, None, 1024);
+    let (n, _) = push_and_wait(&mut driver, op).unwrap();
+    assert!(n > 0);
+
+    let op = Tee::new(rx1, tx2, 1024);
+    let (tee_n, _) = push_and_wait(&mut driver, op).unwrap();
+    assert_eq!(tee_n, n);
+
+    let mut buf = vec![0u8; 1024];
+    let read = unsafe { libc::read(rx2.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(read as usize, n);
+    assert!(buf.starts_with(b"[package]"));
+}
@@ -0,0 +1,142 @@
+use std::{
+    ffi::CString,
+    os::fd::FromRawFd,
+    time::{Duration, Instant},
+};
+
+use compio_buf::{BufResult, IntoInner};
+use compio_driver::{
+    Key, OpCode, OwnedFd, Proactor, ProactorBuilder, PushEntry, SharedFd,
+    op::{Asyncify, BufResultExt, OpenFile, ReadAt, Timeout, Unlink, WriteAt},
+    sim::Sim,
+};
+
+fn sim(seed: u64, latency: Duration) -> Proactor {
+    ProactorBuilder::new()
+        .backend(move || Sim::builder().seed(seed).latency(latency).build())
+        .build()
+        .unwrap()
+}
+
+fn push_and_wait<O: OpCode + 'static>(driver: &mut Proactor, op: O) -> BufResult<usize, O> {
+    match driver.push(op) {
+        PushEntry::Ready(res) => res,
+        PushEntry::Pending(mut key) => loop {
+            driver.poll(None).unwrap();
+            match driver.pop(key) {
+                PushEntry::Pending(k) => key = k,
+                PushEntry::Ready((res, _)) => break res,
+            }
+        },
+    }
+}
+
+fn pending<K, R>(entry: PushEntry<K, R>) -> K {
+    match entry {
+        PushEntry::Pending(key) => key,
+        PushEntry::Ready(_) => panic!("the operation should be pending"),
+    }
+}
+
+fn open(driver: &mut Proactor, path: &str, flags: i32) -> std::io::Result<OwnedFd> {
+    let op = OpenFile::new(CString::new(path).unwrap(), flags | libc::O_CLOEXEC, 0o666);
+    let BufResult(res, _) = push_and_wait(driver, op);
+    Ok(unsafe { OwnedFd::from_raw_fd(res? as _) })
+}
+
+#[test]
+fn file() {
+    let mut driver = sim(0, Duration::from_millis(1));
+
+    let err = open(&mut driver, "/sim/missing", libc::O_RDONLY).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+    let fd = SharedFd::new(open(&mut driver, "/sim/file", libc::O_WRONLY | libc::O_CREAT).unwrap());
+    let BufResult(res, _) = push_and_wait(&mut driver, WriteAt::new(fd.clone(), 5, b"world"));
+    assert_eq!(res.unwrap(), 5);
+    let BufResult(res, _) = push_and_wait(&mut driver, WriteAt::new(fd.clone(), 0, b"hey, "));
+    assert_eq!(res.unwrap(), 5);
+    drop(fd);
+
+    let fd = SharedFd::new(open(&mut driver, "/sim/file", libc::O_RDONLY).unwrap());
+    let BufResult(res, buffer) = push_and_wait(
+        &mut driver,
+        ReadAt::new(fd.clone(), 0, Vec::with_capacity(32)),
+    )
+    .into_inner()
+    .map_advanced();
+    assert_eq!(res.unwrap(), 10);
+    assert_eq!(buffer, b"hey, world");
+
+    let BufResult(res, _) = push_and_wait(&mut driver, WriteAt::new(fd.clone(), 0, b"no"));
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+
+    let BufResult(res, _) = push_and_wait(
+        &mut driver,
+        Unlink::new(CString::new("/sim/file").unwrap(), false),
+    );
+    res.unwrap();
+    // The opened file is still readable after unlinked.
+    let BufResult(res, _) = push_and_wait(
+        &mut driver,
+        ReadAt::new(fd.clone(), 5, Vec::with_capacity(32)),
+    );
+    assert_eq!(res.unwrap(), 5);
+    let err = open(&mut driver, "/sim/file", libc::O_RDONLY).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+}
+
+#[test]
+fn timer() {
+    let mut driver = sim(0, Duration::ZERO);
+
+    let start = Instant::now();
+    let long = pending(driver.push(Timeout::new(Duration::from_secs(60))));
+    let short = pending(driver.push(Timeout::new(Duration::from_secs(30))));
+
+    driver.poll(None).unwrap();
+    let long = match driver.pop(long) {
+        PushEntry::Pending(long) => long,
+        PushEntry::Ready(_) => panic!("the longer timer completes first"),
+    };
+    let short = driver.pop(short);
+    assert!(short.is_ready());
+
+    // The virtual clock doesn't reach the timer.
+    let err = driver.poll(Some(Duration::from_secs(10))).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    let long = pending(driver.pop(long));
+
+    driver.poll(None).unwrap();
+    assert!(driver.pop(long).is_ready());
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+fn completion_order(seed: u64) -> Vec<usize> {
+    let mut driver = sim(seed, Duration::from_millis(10));
+    let mut keys = (0..8)
+        .map(|i| {
+            let op = Asyncify::new(move || BufResult(Ok(i), ()));
+            Some(pending(driver.push(op)))
+        })
+        .collect::<Vec<Option<Key<_>>>>();
+    let mut order = vec![];
+    while order.len() < keys.len() {
+        driver.poll(None).unwrap();
+        for key in keys.iter_mut() {
+            if let Some(k) = key.take() {
+                match driver.pop(k) {
+                    PushEntry::Pending(k) => *key = Some(k),
+                    PushEntry::Ready((BufResult(res, _), _)) => order.push(res.unwrap()),
+                }
+            }
+        }
+    }
+    order
+}
+
+#[test]
+fn deterministic() {
+    assert_eq!(completion_order(1), completion_order(1));
+    assert!((2..16).any(|seed| completion_order(seed) != completion_order(1)));
+}
@@ -4,11 +4,11 @@ use compio_buf::{BufResult, IntoInner, IoBuf, IoBufMut};
 #[cfg(unix)]
 use compio_driver::op::FileStat;
 use compio_driver::{
-    ToSharedFd, impl_raw_fd,
+    AsRawFd, ToSharedFd, impl_raw_fd,
     op::{BufResultExt, CloseFile, ReadAt, ReadManagedAt, ResultTakeBuffer, Sync, WriteAt},
 };
 use compio_io::{AsyncReadAt, AsyncReadManagedAt, AsyncWriteAt, util::Splittable};
-use compio_runtime::{Attacher, BorrowedBuffer, BufferPool};
+use compio_runtime::{Attacher, BorrowedBuffer, BufferPool, Runtime};
 #[cfg(all(unix, not(solarish)))]
 use {
     compio_buf::{IoVectoredBuf, IoVectoredBufMut},
@@ -87,6 +87,8 @@ impl File {
         // `close` should be cancelled.
         let this = ManuallyDrop::new(self);
         async move {
+            // Abort the pending operations, which hold the other clones of the fd.
+            Runtime::with_current(|r| r.cancel_fd(this.inner.as_raw_fd()));
             let fd = ManuallyDrop::into_inner(this)
                 .inner
                 .into_inner()
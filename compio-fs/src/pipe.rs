@@ -546,7 +546,7 @@ async fn is_fifo(file: &File) -> io::Result<bool> {
 /// Sets file's flags with O_NONBLOCK by fcntl.
 fn set_nonblocking(file: &impl AsRawFd) -> io::Result<()> {
     if cfg!(not(all(target_os = "linux", feature = "io-uring")))
-        || compio_driver::DriverType::is_polling()
+        || compio_driver::DriverType::current() == compio_driver::DriverType::Poll
     {
         let fd = file.as_raw_fd();
         let current_flags = syscall!(libc::fcntl(fd, libc::F_GETFL))?;
@@ -0,0 +1,75 @@
+#![cfg(target_os = "linux")]
+
+use compio_buf::IoBuf;
+use compio_driver::{DriverType, ProactorBuilder};
+use compio_fs::OpenOptions;
+use compio_io::{AsyncReadAt, AsyncWriteAt};
+use compio_runtime::Runtime;
+
+const ALIGN: usize = 4096;
+
+/// A zeroed buffer whose slice `off..off + ALIGN` is aligned for `O_DIRECT`.
+fn aligned() -> (Vec<u8>, usize) {
+    let buffer = vec![0u8; ALIGN * 2];
+    let off = buffer.as_ptr().align_offset(ALIGN);
+    (buffer, off)
+}
+
+#[test]
+fn direct_read_write() {
+    let runtime = if DriverType::Poll.is_available() {
+        let mut proactor = ProactorBuilder::new();
+        proactor.driver(DriverType::Poll);
+        Runtime::builder().with_proactor(proactor).build().unwrap()
+    } else {
+        Runtime::new().unwrap()
+    };
+    let dir = tempfile::tempdir_in(env!("CARGO_TARGET_TMPDIR")).unwrap();
+    let path = dir.path().join("direct");
+    runtime.block_on(async {
+        let mut file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(&path)
+            .await
+        {
+            Ok(file) => file,
+            // The filesystem doesn't support `O_DIRECT`.
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+            Err(e) => panic!("{e}"),
+        };
+        let blocking = runtime.metrics().driver.blocking_dispatched;
+
+        let (mut buffer, off) = aligned();
+        buffer[off..off + ALIGN].fill(b'x');
+        let (n, _) = file
+            .write_at(buffer.slice(off..off + ALIGN), ALIGN as u64)
+            .await
+            .unwrap();
+        assert_eq!(n, ALIGN);
+
+        let (buffer, off) = aligned();
+        let (n, buffer) = file
+            .read_at(buffer.slice(off..off + ALIGN), ALIGN as u64)
+            .await
+            .unwrap();
+        assert_eq!(n, ALIGN);
+        assert!(buffer.iter().all(|&b| b == b'x'));
+
+        // Reads after the end of file.
+        let (buffer, off) = aligned();
+        let (n, _) = file
+            .read_at(buffer.slice(off..off + ALIGN), 4 * ALIGN as u64)
+            .await
+            .unwrap();
+        assert_eq!(n, 0);
+
+        if runtime.driver_type() == DriverType::Poll {
+            // The operations are submitted by Linux AIO.
+            assert_eq!(runtime.metrics().driver.blocking_dispatched, blocking);
+        }
+        file.close().await.unwrap();
+    });
+}
@@ -0,0 +1,42 @@
+#![cfg(unix)]
+
+use std::time::{Duration, Instant};
+
+use compio_driver::{ProactorBuilder, sim::Sim};
+use compio_fs::File;
+use compio_runtime::Runtime;
+
+fn runtime(seed: u64) -> Runtime {
+    let mut proactor = ProactorBuilder::new();
+    proactor.backend(move || Sim::builder().seed(seed).build());
+    Runtime::builder().with_proactor(proactor).build().unwrap()
+}
+
+#[test]
+fn read_write() {
+    runtime(0).block_on(async {
+        compio_fs::create_dir("/sim").await.unwrap();
+        compio_fs::write("/sim/a.txt", "hello").await.unwrap();
+        compio_fs::rename("/sim", "/moved").await.unwrap();
+        assert_eq!(compio_fs::read("/moved/a.txt").await.unwrap(), b"hello");
+        assert!(compio_fs::read("/sim/a.txt").await.is_err());
+
+        let err = compio_fs::remove_dir("/moved").await.unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOTEMPTY));
+        compio_fs::remove_file("/moved/a.txt").await.unwrap();
+        compio_fs::remove_dir("/moved").await.unwrap();
+
+        let file = File::create("/b.txt").await.unwrap();
+        file.sync_all().await.unwrap();
+        file.close().await.unwrap();
+    });
+}
+
+#[test]
+fn virtual_time() {
+    let start = Instant::now();
+    runtime(0).block_on(async {
+        compio_runtime::time::sleep(Duration::from_secs(3600)).await;
+    });
+    assert!(start.elapsed() < Duration::from_secs(60));
+}
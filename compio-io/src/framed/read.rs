@@ -35,14 +35,15 @@ where
 
                     // First try decode from the buffer
                     if let Some(frame) = this.framer.extract(buf.slice()) {
-                        let decoded = this.codec.decode(frame.payload(buf.slice()))?;
+                        let decoded = this.codec.decode(frame.payload(buf.slice()));
                         buf.advance(frame.len());
 
                         if buf.all_done() {
                             buf.reset();
                         }
 
-                        return Poll::Ready(Some(Ok(decoded)));
+                        this.read_state = State::Idle(Some((io, buf)));
+                        return Poll::Ready(Some(decoded));
                     }
 
                     buf.reserve(16);
@@ -18,6 +18,11 @@ pub(crate) use internal::*;
 pub mod split;
 pub use split::Splittable;
 
+#[cfg(all(feature = "splice", any(target_os = "linux", target_os = "android")))]
+mod splice;
+#[cfg(all(feature = "splice", any(target_os = "linux", target_os = "android")))]
+pub use splice::{copy_bidirectional_splice, splice_copy};
+
 /// Asynchronously copies the entire contents of a reader into a writer.
 ///
 /// This function returns a future that will continuously read data from
@@ -0,0 +1,101 @@
+use std::{
+    io,
+    net::Shutdown,
+    os::fd::{AsFd, FromRawFd, OwnedFd},
+};
+
+use compio_buf::BufResult;
+use compio_driver::{
+    SharedFd, ToSharedFd,
+    op::{ShutdownSocket, Splice},
+};
+
+use crate::IoResult;
+
+/// The max length of one splice, which is the default capacity of a pipe.
+const SPLICE_LEN: usize = 64 * 1024;
+
+fn pipe() -> io::Result<(SharedFd<OwnedFd>, SharedFd<OwnedFd>)> {
+    let mut fds = [0; 2];
+    let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) };
+    if res != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let (rx, tx) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    Ok((SharedFd::new(rx), SharedFd::new(tx)))
+}
+
+/// Asynchronously copies the entire contents of a reader into a writer with
+/// `splice(2)`, until the reader returns EOF or fails.
+///
+/// The data is moved through a pipe in the kernel, and never copied to the
+/// user space. The reader could be a socket, a pipe or a file, and the writer
+/// could be a socket or a pipe. The position of a file is used and updated.
+///
+/// On success, the total number of bytes that were copied is returned.
+pub async fn splice_copy<R: AsFd + 'static, W: AsFd + 'static>(
+    reader: &impl ToSharedFd<R>,
+    writer: &impl ToSharedFd<W>,
+) -> IoResult<u64> {
+    let (reader, writer) = (reader.to_shared_fd(), writer.to_shared_fd());
+    let (pipe_rx, pipe_tx) = pipe()?;
+    let mut total = 0u64;
+
+    loop {
+        let op = Splice::new(reader.clone(), None, pipe_tx.clone(), None, SPLICE_LEN);
+        let mut len = match compio_runtime::submit(op).await {
+            BufResult(Ok(0), _) => break,
+            BufResult(Ok(len), _) => len,
+            BufResult(Err(e), _) if e.kind() == io::ErrorKind::Interrupted => continue,
+            BufResult(Err(e), _) => return Err(e),
+        };
+        // Drain the pipe before the next round.
+        while len > 0 {
+            let op = Splice::new(pipe_rx.clone(), None, writer.clone(), None, len);
+            match compio_runtime::submit(op).await.0 {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    len -= written;
+                    total += written as u64;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// Copies data in both directions between `a` and `b` with [`splice_copy`].
+///
+/// When one side reaches EOF, the write half of the other side is shut down if
+/// it is a socket. The future completes when both directions are finished,
+/// and returns the bytes copied from `a` to `b`, and from `b` to `a`.
+pub async fn copy_bidirectional_splice<A: AsFd + 'static, B: AsFd + 'static>(
+    a: &impl ToSharedFd<A>,
+    b: &impl ToSharedFd<B>,
+) -> IoResult<(u64, u64)> {
+    futures_util::future::try_join(
+        async {
+            let len = splice_copy(a, b).await?;
+            shutdown(b.to_shared_fd()).await?;
+            Ok(len)
+        },
+        async {
+            let len = splice_copy(b, a).await?;
+            shutdown(a.to_shared_fd()).await?;
+            Ok(len)
+        },
+    )
+    .await
+}
+
+async fn shutdown<S: AsFd + 'static>(fd: SharedFd<S>) -> IoResult<()> {
+    let op = ShutdownSocket::new(fd, Shutdown::Write);
+    match compio_runtime::submit(op).await.0 {
+        // Not a socket, or the peer has gone.
+        Err(e) if matches!(e.raw_os_error(), Some(libc::ENOTSOCK | libc::ENOTCONN)) => Ok(()),
+        res => res.map(|_| ()),
+    }
+}
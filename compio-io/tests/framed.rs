@@ -0,0 +1,29 @@
+use std::io;
+
+use compio_io::framed::{Framed, codec::Decoder, frame::LengthDelimited};
+use futures_executor::block_on;
+use futures_util::StreamExt;
+
+struct StringCodec;
+
+impl Decoder<String> for StringCodec {
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &[u8]) -> io::Result<String> {
+        String::from_utf8(buf.to_vec()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[test]
+fn framed_read() {
+    block_on(async {
+        // The first frame is not valid UTF-8, and the reader goes on after it.
+        let src: &'static [u8] = &[0, 0, 0, 1, 0xFF, 0, 0, 0, 2, b'h', b'i', 0, 0, 0, 0];
+        let mut framed =
+            Framed::new::<String, String>(StringCodec, LengthDelimited::new()).with_reader(src);
+        let err = framed.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(framed.next().await.unwrap().unwrap(), "hi");
+        assert_eq!(framed.next().await.unwrap().unwrap(), "");
+    })
+}
@@ -0,0 +1,57 @@
+#![cfg(any(target_os = "linux", target_os = "android"))]
+
+use std::{
+    io::{Read, Write},
+    net::Shutdown,
+    os::unix::net::UnixStream,
+};
+
+use compio_driver::SharedFd;
+use compio_io::util::{copy_bidirectional_splice, splice_copy};
+
+fn pair() -> (UnixStream, UnixStream) {
+    let (a, b) = UnixStream::pair().unwrap();
+    a.set_nonblocking(true).unwrap();
+    (a, b)
+}
+
+#[compio_macros::test]
+async fn splice_socket() {
+    let data = (0..16 * 1024).map(|i| i as u8).collect::<Vec<_>>();
+
+    let (src, mut src_peer) = pair();
+    let (dst, mut dst_peer) = pair();
+    src_peer.write_all(&data).unwrap();
+    src_peer.shutdown(Shutdown::Write).unwrap();
+
+    let len = splice_copy(&SharedFd::new(src), &SharedFd::new(dst))
+        .await
+        .unwrap();
+    assert_eq!(len, data.len() as u64);
+
+    let mut received = vec![0; data.len()];
+    dst_peer.read_exact(&mut received).unwrap();
+    assert_eq!(received, data);
+}
+
+#[compio_macros::test]
+async fn splice_bidirectional() {
+    let (a, mut a_peer) = pair();
+    let (b, mut b_peer) = pair();
+    a_peer.write_all(b"ping").unwrap();
+    a_peer.shutdown(Shutdown::Write).unwrap();
+    b_peer.write_all(b"pong").unwrap();
+    b_peer.shutdown(Shutdown::Write).unwrap();
+
+    let lens = copy_bidirectional_splice(&SharedFd::new(a), &SharedFd::new(b))
+        .await
+        .unwrap();
+    assert_eq!(lens, (4, 4));
+
+    let mut received = String::new();
+    a_peer.read_to_string(&mut received).unwrap();
+    assert_eq!(received, "pong");
+    received.clear();
+    b_peer.read_to_string(&mut received).unwrap();
+    assert_eq!(received, "ping");
+}
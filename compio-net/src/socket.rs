@@ -11,12 +11,13 @@ use compio_driver::{
     AsRawFd, ToSharedFd, impl_raw_fd,
     op::{
         Accept, BufResultExt, CloseSocket, Connect, Recv, RecvFrom, RecvFromVectored, RecvManaged,
-        RecvMsg, RecvResultExt, RecvVectored, ResultTakeBuffer, Send, SendMsg, SendTo,
-        SendToVectored, SendVectored, ShutdownSocket,
+        RecvMsg, RecvResultExt, RecvVectored, ResultTakeBuffer, Send, SendMsg, SendMsgZc, SendTo,
+        SendToVectored, SendVectored, SendZc, ShutdownSocket,
     },
     syscall,
 };
-use compio_runtime::{Attacher, BorrowedBuffer, BufferPool};
+use compio_runtime::{Attacher, BorrowedBuffer, BufferPool, Runtime};
+use futures_util::Stream;
 use socket2::{Domain, Protocol, SockAddr, Socket as Socket2, Type};
 
 use crate::PollFd;
@@ -115,6 +116,38 @@ impl Socket {
         Ok((accept_sock, addr))
     }
 
+    #[cfg(unix)]
+    pub fn incoming(&self) -> impl Stream<Item = io::Result<Self>> + '_ {
+        use std::os::fd::FromRawFd;
+
+        use compio_driver::op::AcceptMulti;
+        use compio_runtime::{MultishotItem, submit_multishot};
+        use futures_util::StreamExt;
+
+        futures_util::stream::unfold(None, move |mut op| async move {
+            loop {
+                let stream = op
+                    .get_or_insert_with(|| submit_multishot(AcceptMulti::new(self.to_shared_fd())));
+                match stream.next().await {
+                    Some(MultishotItem::More(fd)) => {
+                        return Some((Self::from_socket2(Socket2::from(fd)), op));
+                    }
+                    Some(MultishotItem::Done(BufResult(res, accept), _)) => {
+                        // Submit again on next polling.
+                        op = None;
+                        let res = res.and_then(|fd| {
+                            // The final fd is not owned by the operation anymore.
+                            accept.into_inner();
+                            Self::from_socket2(unsafe { Socket2::from_raw_fd(fd as _) })
+                        });
+                        return Some((res, op));
+                    }
+                    None => op = None,
+                }
+            }
+        })
+    }
+
     #[cfg(windows)]
     pub async fn accept(&self) -> io::Result<(Self, SockAddr)> {
         use std::panic::resume_unwind;
@@ -135,12 +168,21 @@ impl Socket {
         Ok((Self::from_socket2(accept_sock)?, addr))
     }
 
+    #[cfg(windows)]
+    pub fn incoming(&self) -> impl Stream<Item = io::Result<Self>> + '_ {
+        futures_util::stream::unfold((), move |_| async move {
+            Some((self.accept().await.map(|(socket, _)| socket), ()))
+        })
+    }
+
     pub fn close(self) -> impl Future<Output = io::Result<()>> {
         // Make sure that self won't be dropped after `close` called.
         // Users may call this method and drop the future immediately. In that way the
         // `close` should be cancelled.
         let this = ManuallyDrop::new(self);
         async move {
+            // Abort the pending operations, which hold the other clones of the fd.
+            Runtime::with_current(|r| r.cancel_fd(this.socket.as_raw_fd()));
             let fd = ManuallyDrop::into_inner(this)
                 .socket
                 .into_inner()
@@ -185,6 +227,68 @@ impl Socket {
             .take_buffer(buffer_pool)
     }
 
+    pub fn recv_multi<'a>(
+        &'a self,
+        buffer_pool: &'a BufferPool,
+    ) -> impl Stream<Item = io::Result<BorrowedBuffer<'a>>> + 'a {
+        use compio_driver::{TakeBuffer, op::RecvMulti};
+        use compio_runtime::{MultishotItem, submit_multishot};
+        use futures_util::{StreamExt, future::poll_fn};
+
+        // The state is `None` after the stream ends. The inner is the running op, with
+        // the count of returned buffers when it is submitted.
+        futures_util::stream::unfold(Some(None), move |state| async move {
+            let mut op = state?;
+            let buffer_pool = match buffer_pool.try_inner() {
+                Ok(buffer_pool) => buffer_pool,
+                Err(e) => return Some((Err(e), None)),
+            };
+            loop {
+                let (stream, returned) = match &mut op {
+                    Some(op) => op,
+                    None => {
+                        let returned = buffer_pool.returned();
+                        match RecvMulti::new(self.to_shared_fd(), buffer_pool) {
+                            Ok(recv) => op.insert((submit_multishot(recv), returned)),
+                            // No buffer is available in the fallback pool.
+                            Err(e) if is_no_buffer(&e) => {
+                                poll_fn(|cx| buffer_pool.poll_returned(cx, returned)).await;
+                                continue;
+                            }
+                            Err(e) => return Some((Err(e), None)),
+                        }
+                    }
+                };
+                match stream.next().await {
+                    Some(MultishotItem::More(buffer)) => {
+                        return Some((buffer.take(buffer_pool), Some(op)));
+                    }
+                    Some(MultishotItem::Done(BufResult(res, recv), flags)) => {
+                        let returned = *returned;
+                        op = None;
+                        match res {
+                            Ok(0) => {
+                                // Return the buffer if selected.
+                                recv.take_buffer(buffer_pool, Ok(0), flags).ok();
+                                return None;
+                            }
+                            // Submit again after the buffers come back.
+                            Err(e) if is_no_buffer(&e) => {
+                                poll_fn(|cx| buffer_pool.poll_returned(cx, returned)).await;
+                            }
+                            res => {
+                                let res = recv.take_buffer(buffer_pool, res, flags);
+                                let state = res.is_ok().then_some(None);
+                                return Some((res, state));
+                            }
+                        }
+                    }
+                    None => op = None,
+                }
+            }
+        })
+    }
+
     pub async fn send<T: IoBuf>(&self, buffer: T) -> BufResult<usize, T> {
         let fd = self.to_shared_fd();
         let op = Send::new(fd, buffer);
@@ -197,6 +301,12 @@ impl Socket {
         compio_runtime::submit(op).await.into_inner()
     }
 
+    pub async fn send_zc<T: IoBuf>(&self, buffer: T) -> BufResult<usize, T> {
+        let fd = self.to_shared_fd();
+        let op = SendZc::new(fd, buffer);
+        compio_runtime::submit(op).await.into_inner()
+    }
+
     pub async fn recv_from<T: IoBufMut>(&self, buffer: T) -> BufResult<(usize, SockAddr), T> {
         let fd = self.to_shared_fd();
         let op = RecvFrom::new(fd, buffer);
@@ -260,6 +370,15 @@ impl Socket {
         compio_runtime::submit(op).await.into_inner()
     }
 
+    pub async fn send_to_zc<T: IoBuf>(&self, buffer: T, addr: &SockAddr) -> BufResult<usize, T> {
+        let fd = self.to_shared_fd();
+        let op = SendMsgZc::new(fd, [buffer], [], addr.clone());
+        compio_runtime::submit(op)
+            .await
+            .into_inner()
+            .map_buffer(|([buffer], _)| buffer)
+    }
+
     pub async fn send_msg<T: IoBuf, C: IoBuf>(
         &self,
         buffer: T,
@@ -360,3 +479,15 @@ impl Socket {
 }
 
 impl_raw_fd!(Socket, Socket2, socket, socket);
+
+/// Whether the buffer pool runs out of buffers.
+fn is_no_buffer(e: &io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        e.raw_os_error() == Some(libc::ENOBUFS)
+    }
+    #[cfg(windows)]
+    {
+        e.raw_os_error() == Some(windows_sys::Win32::Networking::WinSock::WSAENOBUFS)
+    }
+}
@@ -4,6 +4,7 @@ use compio_buf::{BufResult, IoBuf, IoBufMut, IoVectoredBuf, IoVectoredBufMut};
 use compio_driver::impl_raw_fd;
 use compio_io::{AsyncRead, AsyncReadManaged, AsyncWrite, util::Splittable};
 use compio_runtime::{BorrowedBuffer, BufferPool};
+use futures_util::{Stream, StreamExt};
 use socket2::{Protocol, SockAddr, Socket as Socket2, Type};
 
 use crate::{
@@ -113,6 +114,17 @@ impl TcpListener {
         Ok((stream, addr.as_socket().expect("should be SocketAddr")))
     }
 
+    /// Returns a stream of the incoming connections.
+    ///
+    /// With io-uring, the connections are accepted by a multishot accept with
+    /// one submission. The peer addresses are not reported; use
+    /// [`TcpStream::peer_addr`] if needed. An error doesn't end the stream.
+    pub fn incoming(&self) -> impl Stream<Item = io::Result<TcpStream>> + '_ {
+        self.inner
+            .incoming()
+            .map(|res| res.map(|inner| TcpStream { inner }))
+    }
+
     /// Returns the local address that this listener is bound to.
     ///
     /// This can be useful, for example, when binding to port 0 to
@@ -298,6 +310,33 @@ impl TcpStream {
         crate::into_split(self)
     }
 
+    /// Returns a stream of the received data with [`BufferPool`].
+    ///
+    /// With io-uring, the data is received by a multishot receive with one
+    /// submission, and the kernel selects the buffers from the pool. If the
+    /// pool runs out of buffers, the receive is submitted again after some
+    /// of the buffers are dropped. The stream ends when the peer shuts
+    /// down, or after an error.
+    pub fn recv_multi<'a>(
+        &'a self,
+        buffer_pool: &'a BufferPool,
+    ) -> impl Stream<Item = io::Result<BorrowedBuffer<'a>>> + 'a {
+        self.inner.recv_multi(buffer_pool)
+    }
+
+    /// Sends some data to the peer without copying the buffer into the kernel,
+    /// returning the original buffer and quantity of data sent.
+    ///
+    /// With io-uring, the buffer is returned after the kernel releases it,
+    /// which is usually after the peer acknowledges the data. It is only
+    /// worth it for large buffers. On other drivers, the data is copied as
+    /// [`AsyncWrite::write`] does.
+    ///
+    /// [`AsyncWrite::write`]: compio_io::AsyncWrite::write
+    pub async fn send_zc<T: IoBuf>(&self, buffer: T) -> BufResult<usize, T> {
+        self.inner.send_zc(buffer).await
+    }
+
     /// Create [`PollFd`] from inner socket.
     pub fn to_poll_fd(&self) -> io::Result<PollFd<Socket2>> {
         self.inner.to_poll_fd()
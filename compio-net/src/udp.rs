@@ -3,6 +3,7 @@ use std::{future::Future, io, net::SocketAddr};
 use compio_buf::{BufResult, IoBuf, IoBufMut, IoVectoredBuf, IoVectoredBufMut};
 use compio_driver::impl_raw_fd;
 use compio_runtime::{BorrowedBuffer, BufferPool};
+use futures_util::Stream;
 use socket2::{Protocol, SockAddr, Socket as Socket2, Type};
 
 use crate::{Socket, ToSocketAddrsAsync};
@@ -210,6 +211,17 @@ impl UdpSocket {
         self.inner.recv_managed(buffer_pool, len).await
     }
 
+    /// Returns a stream of the received packets with [`BufferPool`]. See
+    /// [`TcpStream::recv_multi`].
+    ///
+    /// [`TcpStream::recv_multi`]: crate::TcpStream::recv_multi
+    pub fn recv_multi<'a>(
+        &'a self,
+        buffer_pool: &'a BufferPool,
+    ) -> impl Stream<Item = io::Result<BorrowedBuffer<'a>>> + 'a {
+        self.inner.recv_multi(buffer_pool)
+    }
+
     /// Sends some data to the socket from the buffer, returning the original
     /// buffer and quantity of data sent.
     pub async fn send<T: IoBuf>(&self, buffer: T) -> BufResult<usize, T> {
@@ -222,6 +234,14 @@ impl UdpSocket {
         self.inner.send_vectored(buffer).await
     }
 
+    /// Sends some data to the socket without copying the buffer into the
+    /// kernel. See [`TcpStream::send_zc`].
+    ///
+    /// [`TcpStream::send_zc`]: crate::TcpStream::send_zc
+    pub async fn send_zc<T: IoBuf>(&self, buffer: T) -> BufResult<usize, T> {
+        self.inner.send_zc(buffer).await
+    }
+
     /// Receives a single datagram message on the socket. On success, returns
     /// the number of bytes received and the origin.
     pub async fn recv_from<T: IoBufMut>(&self, buffer: T) -> BufResult<(usize, SocketAddr), T> {
@@ -282,6 +302,21 @@ impl UdpSocket {
         .await
     }
 
+    /// Sends data on the socket to the given address without copying the
+    /// buffer into the kernel. See [`TcpStream::send_zc`].
+    ///
+    /// [`TcpStream::send_zc`]: crate::TcpStream::send_zc
+    pub async fn send_to_zc<T: IoBuf>(
+        &self,
+        buffer: T,
+        addr: impl ToSocketAddrsAsync,
+    ) -> BufResult<usize, T> {
+        super::first_addr_buf(addr, buffer, |addr, buffer| async move {
+            self.inner.send_to_zc(buffer, &SockAddr::from(addr)).await
+        })
+        .await
+    }
+
     /// Sends data on the socket to the given address. On success, returns the
     /// number of bytes sent.
     pub async fn send_to_vectored<T: IoVectoredBuf>(
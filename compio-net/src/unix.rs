@@ -4,6 +4,7 @@ use compio_buf::{BufResult, IoBuf, IoBufMut, IoVectoredBuf, IoVectoredBufMut};
 use compio_driver::impl_raw_fd;
 use compio_io::{AsyncRead, AsyncReadManaged, AsyncWrite, util::Splittable};
 use compio_runtime::{BorrowedBuffer, BufferPool};
+use futures_util::{Stream, StreamExt};
 use socket2::{SockAddr, Socket as Socket2, Type};
 
 use crate::{OwnedReadHalf, OwnedWriteHalf, PollFd, ReadHalf, Socket, WriteHalf};
@@ -90,6 +91,16 @@ impl UnixListener {
         Ok((stream, addr))
     }
 
+    /// Returns a stream of the incoming connections. See
+    /// [`TcpListener::incoming`].
+    ///
+    /// [`TcpListener::incoming`]: crate::TcpListener::incoming
+    pub fn incoming(&self) -> impl Stream<Item = io::Result<UnixStream>> + '_ {
+        self.inner
+            .incoming()
+            .map(|res| res.map(|inner| UnixStream { inner }))
+    }
+
     /// Returns the local address that this listener is bound to.
     pub fn local_addr(&self) -> io::Result<SockAddr> {
         self.inner.local_addr()
@@ -205,6 +216,17 @@ impl UnixStream {
         crate::into_split(self)
     }
 
+    /// Returns a stream of the received data with [`BufferPool`]. See
+    /// [`TcpStream::recv_multi`].
+    ///
+    /// [`TcpStream::recv_multi`]: crate::TcpStream::recv_multi
+    pub fn recv_multi<'a>(
+        &'a self,
+        buffer_pool: &'a BufferPool,
+    ) -> impl Stream<Item = io::Result<BorrowedBuffer<'a>>> + 'a {
+        self.inner.recv_multi(buffer_pool)
+    }
+
     /// Create [`PollFd`] from inner socket.
     pub fn to_poll_fd(&self) -> io::Result<PollFd<Socket2>> {
         self.inner.to_poll_fd()
@@ -88,3 +88,96 @@ async fn test_uds_recv_buffer_pool() {
             .is_empty()
     );
 }
+
+#[compio_macros::test]
+async fn test_tcp_recv_multi() {
+    tcp_recv_multi().await;
+}
+
+/// Receive once per submission, as on the kernels without multishot receive.
+#[cfg(unix)]
+#[test]
+fn test_tcp_recv_multi_fallback() {
+    let mut proactor = compio_driver::ProactorBuilder::new();
+    proactor.max_kernel_version(5, 19);
+    compio_runtime::Runtime::builder()
+        .with_proactor(proactor)
+        .build()
+        .unwrap()
+        .block_on(tcp_recv_multi());
+}
+
+async fn tcp_recv_multi() {
+    use futures_util::StreamExt;
+
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let data = (0..64u8).collect::<Vec<_>>();
+
+    let task = compio_runtime::spawn({
+        let data = data.clone();
+        async move {
+            let mut stream = listener.accept().await.unwrap().0;
+            stream.write_all(data).await.unwrap();
+        }
+    });
+
+    // Fewer buffers than the data, so that the receive needs re-arming.
+    let buffer_pool = BufferPool::new(2, 4).unwrap();
+    let stream = TcpStream::connect(addr).await.unwrap();
+    task.await.unwrap();
+
+    let mut received = vec![];
+    let chunks = stream.recv_multi(&buffer_pool);
+    let mut chunks = std::pin::pin!(chunks);
+    while let Some(buffer) = chunks.next().await {
+        received.extend_from_slice(&buffer.unwrap());
+    }
+    assert_eq!(received, data);
+}
+
+/// The selected buffers dropped without being taken are returned to the pool.
+#[cfg(unix)]
+#[compio_macros::test]
+async fn test_tcp_recv_multi_recycle() {
+    use std::time::Duration;
+
+    use compio_driver::{DriverType, ToSharedFd, op::RecvMulti};
+    use compio_runtime::{MultishotItem, submit_multishot, time::timeout};
+    use futures_util::StreamExt;
+
+    if !DriverType::is_iouring() {
+        return;
+    }
+
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (mut tx, (rx, _)) =
+        futures_util::try_join!(TcpStream::connect(addr), listener.accept()).unwrap();
+
+    // Select all the buffers, and drop them.
+    let buffer_pool = BufferPool::new(2, 4).unwrap();
+    tx.write_all(vec![0u8; 8]).await.unwrap();
+    let op = RecvMulti::new(rx.to_shared_fd(), buffer_pool.try_inner().unwrap()).unwrap();
+    let mut stream = submit_multishot(op);
+    for _ in 0..2 {
+        let item = stream.next().await.unwrap();
+        assert!(matches!(item, MultishotItem::More(_)));
+    }
+    drop(stream);
+
+    tx.write_all(b"hello").await.unwrap();
+    drop(tx);
+    let received = timeout(Duration::from_secs(5), async {
+        let chunks = rx.recv_multi(&buffer_pool);
+        let mut chunks = std::pin::pin!(chunks);
+        let mut received = vec![];
+        while let Some(buffer) = chunks.next().await {
+            received.extend_from_slice(&buffer.unwrap());
+        }
+        received
+    })
+    .await
+    .unwrap();
+    assert_eq!(received, b"hello");
+}
@@ -0,0 +1,64 @@
+use compio_driver::{DriverType, ProactorBuilder};
+use compio_io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use compio_net::{TcpListener, TcpStream, UdpSocket};
+use compio_runtime::Runtime;
+
+fn runtime(driver_type: DriverType) -> Option<Runtime> {
+    if !driver_type.is_available() {
+        let mut proactor = ProactorBuilder::new();
+        proactor.driver(driver_type);
+        let err = Runtime::builder()
+            .with_proactor(proactor)
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+        return None;
+    }
+    let mut proactor = ProactorBuilder::new();
+    proactor.driver(driver_type);
+    let runtime = Runtime::builder().with_proactor(proactor).build().unwrap();
+    assert_eq!(runtime.driver_type(), driver_type);
+    Some(runtime)
+}
+
+async fn echo() {
+    let passive = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let passive_addr = passive.local_addr().unwrap();
+    let active = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    active.send_to("ping", passive_addr).await.unwrap();
+    let ((len, _), buffer) = passive.recv_from(Vec::with_capacity(8)).await.unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(buffer, b"ping");
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (mut tx, (mut rx, _)) =
+        futures_util::try_join!(TcpStream::connect(&addr), listener.accept()).unwrap();
+    tx.write_all("hello").await.unwrap();
+    tx.shutdown().await.unwrap();
+    let (_, buffer) = rx.read_to_end(vec![]).await.unwrap();
+    assert_eq!(buffer, b"hello");
+}
+
+#[test]
+fn side_by_side() {
+    let runtimes = [DriverType::Poll, DriverType::IoUring, DriverType::IOCP]
+        .into_iter()
+        .filter_map(runtime)
+        .collect::<Vec<_>>();
+    assert!(!runtimes.is_empty());
+
+    for runtime in &runtimes {
+        runtime.block_on(async {
+            assert_eq!(DriverType::current(), runtime.driver_type());
+            echo().await
+        });
+    }
+
+    std::thread::scope(|s| {
+        for driver_type in runtimes.iter().map(|r| r.driver_type()) {
+            s.spawn(move || runtime(driver_type).unwrap().block_on(echo()));
+        }
+    });
+}
@@ -0,0 +1,173 @@
+#![cfg(unix)]
+
+use std::{
+    io,
+    panic::resume_unwind,
+    time::{Duration, Instant},
+};
+
+use compio_driver::{Fault, FaultRule, FaultRules};
+use compio_io::{
+    AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+    framed::{
+        Framed,
+        codec::{Decoder, Encoder},
+        frame::LengthDelimited,
+    },
+};
+use compio_net::{TcpListener, TcpStream};
+use compio_runtime::Runtime;
+use futures_util::{SinkExt, StreamExt};
+
+fn runtime(rules: &FaultRules) -> Runtime {
+    Runtime::builder().faults(rules.clone()).build().unwrap()
+}
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, (rx, _)) =
+        futures_util::try_join!(TcpStream::connect(&addr), listener.accept()).unwrap();
+    (tx, rx)
+}
+
+#[test]
+fn write_all() {
+    let mut rules = FaultRules::new();
+    rules
+        .add(FaultRule::new("Send", Fault::Spurious(io::ErrorKind::Interrupted)).times(2))
+        .add(FaultRule::new("Send", Fault::Short(3)));
+    runtime(&rules).block_on(async {
+        let (mut tx, mut rx) = pair().await;
+        let task = compio_runtime::spawn(async move {
+            let (_, buffer) = rx.read_to_end(vec![]).await.unwrap();
+            buffer
+        });
+
+        let data = b"hello world, from short writes".repeat(4);
+        tx.write_all(data.clone()).await.unwrap();
+        tx.shutdown().await.unwrap();
+
+        let buffer = task.await.unwrap_or_else(|e| resume_unwind(e));
+        assert_eq!(buffer, data);
+    });
+}
+
+#[test]
+fn write_error() {
+    let mut rules = FaultRules::new();
+    rules
+        .add(FaultRule::new("Send", Fault::Spurious(io::ErrorKind::WouldBlock)).times(1))
+        .add(FaultRule::new("Send", Fault::Error(libc::EPIPE)).times(1));
+    runtime(&rules).block_on(async {
+        let (mut tx, _rx) = pair().await;
+
+        let err = tx.write_all("hello").await.0.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        let err = tx.write_all("hello").await.0.unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EPIPE));
+        tx.write_all("hello").await.unwrap();
+    });
+}
+
+#[test]
+fn read_delay() {
+    let mut rules = FaultRules::new();
+    rules.add(FaultRule::new("Recv", Fault::Delay(Duration::from_millis(100))).times(1));
+    runtime(&rules).block_on(async {
+        let (mut tx, mut rx) = pair().await;
+        tx.write_all("hello").await.unwrap();
+
+        let start = Instant::now();
+        let (n, buffer) = rx.read(Vec::with_capacity(16)).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(&buffer[..n], b"hello");
+    });
+}
+
+#[test]
+fn submit_all() {
+    use compio_driver::op::Timeout;
+
+    let mut rules = FaultRules::new();
+    rules.add(
+        FaultRule::new("Timeout", Fault::Error(libc::ECANCELED))
+            .skip(1)
+            .times(1),
+    );
+    runtime(&rules).block_on(async {
+        let ops = (1..=3).map(|i| Timeout::new(Duration::from_millis(i)));
+        let results = futures_util::future::join_all(compio_runtime::submit_all(ops)).await;
+        let errors = results
+            .into_iter()
+            .map(|res| res.0.err().and_then(|e| e.raw_os_error()))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, [None, Some(libc::ECANCELED), None]);
+    });
+}
+
+struct StringCodec;
+
+impl Encoder<String> for StringCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: String, buf: &mut Vec<u8>) -> io::Result<()> {
+        buf.extend_from_slice(item.as_bytes());
+        Ok(())
+    }
+}
+
+impl Decoder<String> for StringCodec {
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &[u8]) -> io::Result<String> {
+        String::from_utf8(buf.to_vec()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn framed<R: AsyncRead, W: AsyncWrite>(
+    reader: R,
+    writer: W,
+) -> Framed<R, W, StringCodec, LengthDelimited, String, String> {
+    Framed::symmetric::<String>(StringCodec, LengthDelimited::new())
+        .with_reader(reader)
+        .with_writer(writer)
+}
+
+#[test]
+fn framed_short_read() {
+    let mut rules = FaultRules::new();
+    rules.add(FaultRule::new("Recv", Fault::Short(3)));
+    runtime(&rules).block_on(async {
+        let (tx, rx) = pair().await;
+        let mut framed = framed(rx, tx);
+
+        let items = ["a frame split by short reads", "", "another frame"];
+        for item in items {
+            framed.send(item.to_string()).await.unwrap();
+        }
+        for item in items {
+            assert_eq!(framed.next().await.unwrap().unwrap(), item);
+        }
+    });
+}
+
+#[test]
+fn framed_read_error() {
+    let mut rules = FaultRules::new();
+    rules
+        .add(
+            FaultRule::new("Recv", Fault::Error(libc::ECONNRESET))
+                .skip(2)
+                .times(1),
+        )
+        .add(FaultRule::new("Recv", Fault::Short(4)));
+    runtime(&rules).block_on(async {
+        let (tx, rx) = pair().await;
+        let mut framed = framed(rx, tx);
+
+        framed.send("hello".to_string()).await.unwrap();
+        let err = framed.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    });
+}
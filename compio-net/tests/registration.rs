@@ -0,0 +1,89 @@
+#![cfg(unix)]
+
+use std::{panic::resume_unwind, time::Duration};
+
+use compio_driver::{DriverType, ProactorBuilder};
+use compio_io::{AsyncReadExt, AsyncWriteExt};
+use compio_net::{TcpListener, TcpStream};
+use compio_runtime::Runtime;
+
+fn runtime() -> Option<Runtime> {
+    if !DriverType::Poll.is_available() {
+        return None;
+    }
+    let mut proactor = ProactorBuilder::new();
+    proactor.driver(DriverType::Poll).op_metrics(true);
+    Some(Runtime::builder().with_proactor(proactor).build().unwrap())
+}
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, (rx, _)) =
+        futures_util::try_join!(TcpStream::connect(&addr), listener.accept()).unwrap();
+    (tx, rx)
+}
+
+#[test]
+fn request_response() {
+    const ROUNDS: u64 = 100;
+
+    let Some(runtime) = runtime() else {
+        return;
+    };
+    runtime.block_on(async {
+        let (mut client, mut server) = pair().await;
+        let task = compio_runtime::spawn(async move {
+            let mut buffer = Vec::with_capacity(4);
+            for _ in 0..ROUNDS {
+                buffer = server.read_exact(buffer).await.unwrap().1;
+                buffer = server.write_all(buffer).await.unwrap().1;
+            }
+        });
+        let mut buffer = Vec::with_capacity(4);
+        for i in 0..ROUNDS as u32 {
+            client.write_all(i.to_le_bytes()).await.unwrap();
+            buffer.clear();
+            buffer = client.read_exact(buffer).await.unwrap().1;
+            assert_eq!(buffer, i.to_le_bytes());
+        }
+        task.await.unwrap_or_else(|e| resume_unwind(e));
+    });
+
+    let driver = runtime.metrics().driver;
+    let waits = driver
+        .ops
+        .iter()
+        .filter(|(name, _)| name.ends_with("::Recv") || name.ends_with("::Send"))
+        .map(|(_, op)| op.pushed)
+        .sum::<u64>();
+    assert!(waits >= 4 * ROUNDS);
+    // The fds are registered once, and modified at most once per operation.
+    assert!(
+        driver.registry_updates <= waits + 8,
+        "{} updates for {} operations",
+        driver.registry_updates,
+        waits
+    );
+}
+
+#[test]
+fn reuse_fd() {
+    let Some(runtime) = runtime() else {
+        return;
+    };
+    runtime.block_on(async {
+        for _ in 0..16 {
+            // The streams are closed without cancelling the fds in the driver, and the
+            // numbers are reused by the next ones.
+            let (mut tx, mut rx) = pair().await;
+            let task = compio_runtime::spawn(async move {
+                compio_runtime::time::sleep(Duration::from_millis(1)).await;
+                tx.write_all("hello").await.unwrap();
+            });
+            let (_, buffer) = rx.read_exact(Vec::with_capacity(5)).await.unwrap();
+            assert_eq!(buffer, b"hello");
+            task.await.unwrap_or_else(|e| resume_unwind(e));
+        }
+    });
+}
@@ -0,0 +1,71 @@
+#![cfg(unix)]
+
+use std::panic::resume_unwind;
+
+use compio_driver::{ProactorBuilder, sim::Sim};
+use compio_io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use compio_net::{TcpListener, TcpStream, UdpSocket};
+use compio_runtime::Runtime;
+
+fn runtime(seed: u64) -> Runtime {
+    let mut proactor = ProactorBuilder::new();
+    proactor.backend(move || {
+        Sim::builder()
+            .seed(seed)
+            .latency(std::time::Duration::from_millis(5))
+            .build()
+    });
+    Runtime::builder().with_proactor(proactor).build().unwrap()
+}
+
+#[test]
+fn tcp_echo() {
+    for seed in 0..8 {
+        runtime(seed).block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let task = compio_runtime::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (_, buffer) = stream.read_to_end(vec![]).await.unwrap();
+                stream.write_all(buffer).await.unwrap();
+                stream.shutdown().await.unwrap();
+            });
+
+            let mut cli = TcpStream::connect(&addr).await.unwrap();
+            cli.write_all("hello simulation").await.unwrap();
+            cli.shutdown().await.unwrap();
+            let (_, buffer) = cli.read_to_end(vec![]).await.unwrap();
+            assert_eq!(buffer, b"hello simulation");
+
+            task.await.unwrap_or_else(|e| resume_unwind(e));
+        });
+    }
+}
+
+#[test]
+fn tcp_refused() {
+    runtime(0).block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        listener.close().await.unwrap();
+
+        let err = TcpStream::connect(&addr).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+    });
+}
+
+#[test]
+fn udp() {
+    runtime(0).block_on(async {
+        let passive = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let passive_addr = passive.local_addr().unwrap();
+        let active = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let active_addr = active.local_addr().unwrap();
+
+        active.send_to("ping", passive_addr).await.unwrap();
+        let ((len, addr), buffer) = passive.recv_from(Vec::with_capacity(8)).await.unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(addr, active_addr);
+        assert_eq!(buffer, b"ping");
+    });
+}
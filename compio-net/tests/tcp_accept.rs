@@ -33,3 +33,39 @@ test_accept! {
     (str_port_tuple, ("127.0.0.1", 0)),
     (ip_port_tuple, ("127.0.0.1".parse::<std::net::IpAddr>().unwrap(), 0)),
 }
+
+#[compio_macros::test]
+async fn incoming() {
+    incoming_impl().await;
+}
+
+/// Accept once per submission, as on the kernels without multishot accept.
+#[cfg(unix)]
+#[test]
+fn incoming_fallback() {
+    let mut proactor = compio_driver::ProactorBuilder::new();
+    proactor.max_kernel_version(5, 15);
+    compio_runtime::Runtime::builder()
+        .with_proactor(proactor)
+        .build()
+        .unwrap()
+        .block_on(incoming_impl());
+}
+
+async fn incoming_impl() {
+    use futures_util::StreamExt;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut clients = vec![];
+    for _ in 0..4 {
+        clients.push(TcpStream::connect(&addr).await.unwrap());
+    }
+    let incoming = listener.incoming();
+    let mut incoming = std::pin::pin!(incoming);
+    for _ in 0..clients.len() {
+        let srv = incoming.next().await.unwrap().unwrap();
+        let peer = srv.peer_addr().unwrap();
+        assert!(clients.iter().any(|cli| cli.local_addr().unwrap() == peer));
+    }
+}
@@ -1,8 +1,12 @@
 use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     panic::resume_unwind,
+    time::Duration,
 };
 
+use compio_buf::{IntoInner, IoBuf};
+use compio_driver::op::Timeout;
+use compio_io::{AsyncRead, AsyncReadExt, AsyncWrite};
 use compio_net::{TcpListener, TcpStream, ToSocketAddrsAsync};
 
 async fn test_connect_ip_impl(
@@ -137,3 +141,53 @@ test_connect! {
 async fn connect_invalid_dst() {
     assert!(TcpStream::connect("127.0.0.0:0").await.is_err());
 }
+
+#[compio_macros::test]
+async fn send_zc() {
+    const LEN: usize = 1 << 20;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let task = compio_runtime::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        socket.read_to_end(Vec::with_capacity(LEN)).await.unwrap().1
+    });
+
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    let mut data = (0..LEN).map(|i| i as u8).collect::<Vec<_>>();
+    let mut sent = 0;
+    while sent < LEN {
+        let (len, buffer) = stream.send_zc(data.slice(sent..)).await.unwrap();
+        data = buffer.into_inner();
+        sent += len;
+    }
+    stream.shutdown().await.unwrap();
+    drop(stream);
+
+    let received = task.await.unwrap_or_else(|e| resume_unwind(e));
+    assert_eq!(received, data);
+}
+
+#[compio_macros::test]
+async fn close_cancels_pending() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let task = compio_runtime::spawn(async move { listener.accept().await.unwrap() });
+    let stream = TcpStream::connect(&addr).await.unwrap();
+    // Keep the peer open, so that the read never completes by itself.
+    let _peer = task.await.unwrap_or_else(|e| resume_unwind(e));
+
+    let mut reader = stream.clone();
+    let read = compio_runtime::spawn(async move { reader.read(Vec::with_capacity(16)).await.0 });
+    // Let the read start.
+    compio_runtime::submit(Timeout::new(Duration::from_millis(10)))
+        .await
+        .0
+        .unwrap();
+
+    stream.close().await.unwrap();
+    let res = read.await.unwrap_or_else(|e| resume_unwind(e));
+    assert!(res.is_err());
+}
@@ -64,3 +64,28 @@ async fn send_to() {
         active_addr
     );
 }
+
+#[compio_macros::test]
+async fn send_zc() {
+    const MSG: &str = "foo bar baz";
+
+    let passive = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let passive_addr = passive.local_addr().unwrap();
+
+    let active = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let active_addr = active.local_addr().unwrap();
+
+    let (len, buffer) = active.send_to_zc(MSG, &passive_addr).await.unwrap();
+    assert_eq!(len, MSG.len());
+    assert_eq!(buffer, MSG);
+
+    let ((_, addr), buffer) = passive.recv_from(Vec::with_capacity(20)).await.unwrap();
+    assert_eq!(addr, active_addr);
+    assert_eq!(MSG.as_bytes(), &buffer);
+
+    active.connect(passive_addr).await.unwrap();
+    active.send_zc(MSG).await.0.unwrap();
+
+    let (_, buffer) = passive.recv(Vec::with_capacity(20)).await.unwrap();
+    assert_eq!(MSG.as_bytes(), &buffer);
+}
@@ -0,0 +1,179 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+struct Node {
+    cancelled: bool,
+    children: Vec<Weak<RefCell<Node>>>,
+    waiters: Vec<(u64, Waker)>,
+    next_id: u64,
+}
+
+impl Node {
+    fn new(cancelled: bool) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            cancelled,
+            children: Vec::new(),
+            waiters: Vec::new(),
+            next_id: 0,
+        }))
+    }
+}
+
+/// A token to cancel a group of tasks and operations, e.g., all operations of
+/// a request. The clones share the same state.
+///
+/// A token could create child tokens, which are cancelled together with it.
+/// Cancelling a child token doesn't affect its parent.
+///
+/// The operations submitted with
+/// [`submit_with_cancel`](crate::submit_with_cancel) are interrupted when the
+/// token is cancelled.
+///
+/// ```
+/// use compio_runtime::CancellationToken;
+///
+/// # compio_runtime::Runtime::new().unwrap().block_on(async {
+/// let token = CancellationToken::new();
+/// let child = token.child_token();
+/// let task = compio_runtime::spawn(async move { child.cancelled().await });
+/// token.cancel();
+/// task.await.unwrap();
+/// # })
+/// ```
+#[derive(Clone)]
+pub struct CancellationToken {
+    node: Rc<RefCell<Node>>,
+}
+
+impl CancellationToken {
+    /// Create a token not cancelled.
+    pub fn new() -> Self {
+        Self {
+            node: Node::new(false),
+        }
+    }
+
+    /// Create a child token, which is cancelled when this token is cancelled.
+    /// It is already cancelled if this token is.
+    pub fn child_token(&self) -> Self {
+        let mut node = self.node.borrow_mut();
+        if node.cancelled {
+            return Self {
+                node: Node::new(true),
+            };
+        }
+        let child = Node::new(false);
+        node.children.retain(|child| child.strong_count() > 0);
+        node.children.push(Rc::downgrade(&child));
+        Self { node: child }
+    }
+
+    /// Cancel the token and all its children, and wake the tasks waiting on
+    /// them. It does nothing if the token has been cancelled.
+    pub fn cancel(&self) {
+        let mut wakers = Vec::new();
+        let mut nodes = vec![self.node.clone()];
+        while let Some(node) = nodes.pop() {
+            let mut state = node.borrow_mut();
+            if state.cancelled {
+                continue;
+            }
+            state.cancelled = true;
+            wakers.extend(state.waiters.drain(..).map(|(_, waker)| waker));
+            let children = mem::take(&mut state.children);
+            drop(state);
+            nodes.extend(children.iter().filter_map(Weak::upgrade));
+        }
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.node.borrow().cancelled
+    }
+
+    /// Wait until the token is cancelled.
+    pub fn cancelled(&self) -> WaitForCancellation<'_> {
+        WaitForCancellation {
+            token: self,
+            id: None,
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// The future returned by [`CancellationToken::cancelled`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitForCancellation<'a> {
+    token: &'a CancellationToken,
+    id: Option<u64>,
+}
+
+impl Future for WaitForCancellation<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let token = self.token;
+        let mut node = token.node.borrow_mut();
+        if node.cancelled {
+            self.id = None;
+            return Poll::Ready(());
+        }
+        match self.id {
+            Some(id) => {
+                if let Some((_, waker)) = node.waiters.iter_mut().find(|(i, _)| *i == id) {
+                    if !waker.will_wake(cx.waker()) {
+                        *waker = cx.waker().clone();
+                    }
+                }
+            }
+            None => {
+                let id = node.next_id;
+                node.next_id += 1;
+                node.waiters.push((id, cx.waker().clone()));
+                self.id = Some(id);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForCancellation<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            let mut node = self.token.node.borrow_mut();
+            let pos = node.waiters.iter().position(|(i, _)| *i == id);
+            let waiter = pos.map(|pos| node.waiters.swap_remove(pos));
+            drop(node);
+            drop(waiter);
+        }
+    }
+}
+
+impl fmt::Debug for WaitForCancellation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitForCancellation")
+            .field("token", self.token)
+            .finish()
+    }
+}
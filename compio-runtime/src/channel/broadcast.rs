@@ -0,0 +1,297 @@
+//! A multi-producer, multi-consumer channel, where each value is received by
+//! all receivers.
+//!
+//! The channel keeps the last `capacity` values. A receiver falling behind
+//! skips the values no longer kept, and is told how many are skipped by
+//! [`RecvError::Lagged`].
+//!
+//! ```
+//! use compio_runtime::channel::broadcast;
+//!
+//! # compio_runtime::Runtime::new().unwrap().block_on(async {
+//! let (tx, mut rx1) = broadcast::channel(16);
+//! let mut rx2 = tx.subscribe();
+//! tx.send(42).unwrap();
+//! assert_eq!(rx1.recv().await, Ok(42));
+//! assert_eq!(rx2.recv().await, Ok(42));
+//! # })
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    future::poll_fn,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use super::SendError;
+
+struct State<T> {
+    buffer: VecDeque<T>,
+    capacity: usize,
+    // The position of the first value in the buffer.
+    head: u64,
+    senders: usize,
+    receivers: usize,
+    waiters: Vec<(u64, Waker)>,
+    next_id: u64,
+}
+
+impl<T> State<T> {
+    fn tail(&self) -> u64 {
+        self.head + self.buffer.len() as u64
+    }
+
+    fn new_receiver(&mut self) -> u64 {
+        self.receivers += 1;
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn wake_all(&mut self) -> Vec<Waker> {
+        self.waiters.drain(..).map(|(_, waker)| waker).collect()
+    }
+}
+
+/// Create a broadcast channel keeping the last `capacity` values.
+///
+/// ## Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "the capacity should be positive");
+    let shared = Rc::new(RefCell::new(State {
+        buffer: VecDeque::with_capacity(capacity),
+        capacity,
+        head: 0,
+        senders: 1,
+        receivers: 0,
+        waiters: Vec::new(),
+        next_id: 0,
+    }));
+    let rx = Receiver::new(shared.clone(), 0);
+    (Sender { shared }, rx)
+}
+
+/// The sending half of [`channel`].
+pub struct Sender<T> {
+    shared: Rc<RefCell<State<T>>>,
+}
+
+impl<T: Clone> Sender<T> {
+    /// Send a value to all receivers, and return the number of them. The
+    /// value is returned back if there is no receiver.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let mut state = self.shared.borrow_mut();
+        if state.receivers == 0 {
+            return Err(SendError(value));
+        }
+        state.buffer.push_back(value);
+        let dropped = if state.buffer.len() > state.capacity {
+            state.head += 1;
+            state.buffer.pop_front()
+        } else {
+            None
+        };
+        let receivers = state.receivers;
+        let wakers = state.wake_all();
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+        drop(dropped);
+        Ok(receivers)
+    }
+
+    /// Create a receiver, which receives the values sent after this call.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let next = self.shared.borrow().tail();
+        Receiver::new(self.shared.clone(), next)
+    }
+}
+
+impl<T> Sender<T> {
+    /// The number of the receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.borrow().receivers
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        state.senders -= 1;
+        let wakers = if state.senders == 0 {
+            state.wake_all()
+        } else {
+            Vec::new()
+        };
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("Sender")
+            .field("capacity", &state.capacity)
+            .field("receivers", &state.receivers)
+            .finish()
+    }
+}
+
+/// The receiving half of [`channel`]. Cloning a receiver creates one at the
+/// same position.
+pub struct Receiver<T> {
+    shared: Rc<RefCell<State<T>>>,
+    id: u64,
+    // The position of the next value to receive.
+    next: u64,
+}
+
+impl<T> Receiver<T> {
+    fn new(shared: Rc<RefCell<State<T>>>, next: u64) -> Self {
+        let id = shared.borrow_mut().new_receiver();
+        Self { shared, id, next }
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receive the next value. It fails with [`RecvError::Closed`] after all
+    /// senders are dropped and all values are received.
+    ///
+    /// It is cancel-safe: no value is lost if the future is dropped.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the next value, see [`Receiver::recv`].
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Lagged(n)) => Poll::Ready(Err(RecvError::Lagged(n))),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+            Err(TryRecvError::Empty) => {
+                let mut state = self.shared.borrow_mut();
+                match state.waiters.iter_mut().find(|(id, _)| *id == self.id) {
+                    Some((_, waker)) => {
+                        if !waker.will_wake(cx.waker()) {
+                            *waker = cx.waker().clone();
+                        }
+                    }
+                    None => state.waiters.push((self.id, cx.waker().clone())),
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Try to receive the next value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let state = self.shared.borrow();
+        if self.next < state.head {
+            let lagged = state.head - self.next;
+            self.next = state.head;
+            return Err(TryRecvError::Lagged(lagged));
+        }
+        match state.buffer.get((self.next - state.head) as usize) {
+            Some(value) => {
+                self.next += 1;
+                Ok(value.clone())
+            }
+            None if state.senders == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// The number of the values not received yet.
+    pub fn len(&self) -> usize {
+        let state = self.shared.borrow();
+        (state.tail() - self.next.max(state.head)) as usize
+    }
+
+    /// Whether all values have been received.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.shared.clone(), self.next)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        state.receivers -= 1;
+        state.waiters.retain(|(id, _)| *id != self.id);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Error returned by [`Receiver::recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvError {
+    /// All senders are dropped, and all values are received.
+    Closed,
+    /// The receiver fell behind, and the number of the values are skipped.
+    /// The next call receives the oldest value kept.
+    Lagged(u64),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => "channel closed".fmt(f),
+            Self::Lagged(n) => write!(f, "channel lagged by {n}"),
+        }
+    }
+}
+
+impl Error for RecvError {}
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// No value is available now.
+    Empty,
+    /// All senders are dropped, and all values are received.
+    Closed,
+    /// The receiver fell behind, see [`RecvError::Lagged`].
+    Lagged(u64),
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => "channel empty".fmt(f),
+            Self::Closed => "channel closed".fmt(f),
+            Self::Lagged(n) => write!(f, "channel lagged by {n}"),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
@@ -0,0 +1,125 @@
+//! Channels to send values between the tasks on the same runtime.
+//!
+//! The channels are designed for the thread-per-core model: their states are
+//! shared by [`Rc`](std::rc::Rc) and [`RefCell`](std::cell::RefCell) instead
+//! of atomics, so neither halves could be sent to other threads. Use
+//! [`remote`](crate::remote) channels to send values to another runtime.
+//!
+//! * [`mpsc`]: bounded and unbounded multi-producer, single-consumer queues.
+//! * [`oneshot`]: a single value from one task to another.
+//! * [`broadcast`]: every value is received by all receivers.
+//! * [`watch`]: the receivers observe the latest value.
+//!
+//! ```
+//! use compio_runtime::channel::mpsc;
+//!
+//! # compio_runtime::Runtime::new().unwrap().block_on(async {
+//! let (tx, mut rx) = mpsc::channel(1);
+//! let task = compio_runtime::spawn(async move {
+//!     for i in 0..3 {
+//!         tx.send(i).await.unwrap();
+//!     }
+//! });
+//! assert_eq!(rx.recv().await, Some(0));
+//! assert_eq!(rx.recv().await, Some(1));
+//! assert_eq!(rx.recv().await, Some(2));
+//! assert_eq!(rx.recv().await, None);
+//! task.await.unwrap();
+//! # })
+//! ```
+
+use std::{error::Error, fmt};
+
+pub mod broadcast;
+pub mod mpsc;
+pub mod oneshot;
+pub mod watch;
+
+/// Error returned when sending to a closed channel. The value is returned
+/// back.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "SendError(..)".fmt(f)
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl<T> Error for SendError<T> {}
+
+/// Error returned by [`mpsc::Sender::try_send`]. The value is returned back.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+    /// The channel is closed.
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Consume the error, yielding the value that failed to be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => "Full(..)".fmt(f),
+            Self::Closed(_) => "Closed(..)".fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => "channel full".fmt(f),
+            Self::Closed(_) => "channel closed".fmt(f),
+        }
+    }
+}
+
+impl<T> Error for TrySendError<T> {}
+
+/// Error returned when the senders are dropped without sending a value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl Error for RecvError {}
+
+/// Error returned when receiving without waiting.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// No value is available now.
+    Empty,
+    /// The channel is closed, and no value will be available.
+    Closed,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => "channel empty".fmt(f),
+            Self::Closed => "channel closed".fmt(f),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
@@ -0,0 +1,473 @@
+//! Multi-producer, single-consumer queues.
+//!
+//! A bounded channel created by [`channel`] holds at most `capacity` values,
+//! and the senders wait for free slots in the order they start waiting. An
+//! unbounded channel created by [`unbounded`] never waits.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt,
+    future::{Future, poll_fn},
+    mem,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use super::{SendError, TryRecvError, TrySendError};
+
+struct Waiter {
+    id: u64,
+    waker: Waker,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    bounded: bool,
+    max_capacity: usize,
+    // The free slots of a bounded channel. It is 0 if any sender is waiting.
+    slots: usize,
+    waiters: VecDeque<Waiter>,
+    // The waiting senders which have been given a slot.
+    granted: Vec<u64>,
+    next_id: u64,
+    recv_waker: Option<Waker>,
+    senders: usize,
+    // Set when the receiver is closed or dropped.
+    closed: bool,
+}
+
+impl<T> State<T> {
+    fn new(bounded: bool, capacity: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            bounded,
+            max_capacity: capacity,
+            slots: capacity,
+            waiters: VecDeque::new(),
+            granted: Vec::new(),
+            next_id: 0,
+            recv_waker: None,
+            senders: 1,
+            closed: false,
+        }
+    }
+
+    fn push(&mut self, value: T) -> Option<Waker> {
+        self.queue.push_back(value);
+        self.recv_waker.take()
+    }
+
+    // Free a slot, and give it to the sender waiting first.
+    fn release(&mut self) -> Option<Waker> {
+        match self.waiters.pop_front() {
+            Some(waiter) if !self.closed => {
+                self.granted.push(waiter.id);
+                Some(waiter.waker)
+            }
+            _ => {
+                self.slots += 1;
+                None
+            }
+        }
+    }
+
+    fn close(&mut self) -> Vec<Waker> {
+        self.closed = true;
+        self.granted.clear();
+        self.waiters.drain(..).map(|waiter| waiter.waker).collect()
+    }
+
+    fn drop_sender(&mut self) -> Option<Waker> {
+        self.senders -= 1;
+        if self.senders == 0 {
+            self.recv_waker.take()
+        } else {
+            None
+        }
+    }
+}
+
+type Shared<T> = Rc<RefCell<State<T>>>;
+
+/// Create a bounded channel holding at most `capacity` values.
+///
+/// ## Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "the capacity should be positive");
+    let shared = Rc::new(RefCell::new(State::new(true, capacity)));
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Create an unbounded channel.
+pub fn unbounded<T>() -> (UnboundedSender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(State::new(false, 0)));
+    (
+        UnboundedSender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// The sending half of a bounded channel created by [`channel`].
+pub struct Sender<T> {
+    shared: Shared<T>,
+}
+
+impl<T> Sender<T> {
+    /// Send a value, and wait for a free slot if the channel is full. The
+    /// value is returned back if the receiver has been closed.
+    ///
+    /// If the future is dropped before completion, the value is dropped, and
+    /// the place in the waiting order is given up.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        match self.reserve().await {
+            Ok(permit) => {
+                permit.send(value);
+                Ok(())
+            }
+            Err(SendError(())) => Err(SendError(value)),
+        }
+    }
+
+    /// Try to send a value without waiting.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.shared.borrow_mut();
+        if state.closed {
+            return Err(TrySendError::Closed(value));
+        }
+        if state.slots == 0 {
+            return Err(TrySendError::Full(value));
+        }
+        state.slots -= 1;
+        let waker = state.push(value);
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Wait for a free slot, and reserve it for sending a value later.
+    pub fn reserve(&self) -> Reserve<'_, T> {
+        Reserve {
+            shared: &self.shared,
+            id: None,
+        }
+    }
+
+    /// Whether the receiver has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().closed
+    }
+
+    /// The number of the free slots.
+    pub fn capacity(&self) -> usize {
+        self.shared.borrow().slots
+    }
+
+    /// The capacity the channel is created with.
+    pub fn max_capacity(&self) -> usize {
+        self.shared.borrow().max_capacity
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = self.shared.borrow_mut().drop_sender();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("Sender")
+            .field("max_capacity", &state.max_capacity)
+            .field("len", &state.queue.len())
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+/// Future for [`Sender::reserve`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Reserve<'a, T> {
+    shared: &'a Shared<T>,
+    // Set when the sender is waiting.
+    id: Option<u64>,
+}
+
+impl<'a, T> Future for Reserve<'a, T> {
+    type Output = Result<Permit<'a, T>, SendError<()>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut state = this.shared.borrow_mut();
+        match this.id {
+            None => {
+                if state.closed {
+                    return Poll::Ready(Err(SendError(())));
+                }
+                if state.slots > 0 {
+                    state.slots -= 1;
+                    return Poll::Ready(Ok(Permit {
+                        shared: this.shared,
+                    }));
+                }
+                let id = state.next_id;
+                state.next_id += 1;
+                state.waiters.push_back(Waiter {
+                    id,
+                    waker: cx.waker().clone(),
+                });
+                this.id = Some(id);
+                Poll::Pending
+            }
+            Some(id) => {
+                if let Some(pos) = state.granted.iter().position(|granted| *granted == id) {
+                    state.granted.swap_remove(pos);
+                    this.id = None;
+                    return Poll::Ready(Ok(Permit {
+                        shared: this.shared,
+                    }));
+                }
+                if state.closed {
+                    this.id = None;
+                    return Poll::Ready(Err(SendError(())));
+                }
+                let waiter = state
+                    .waiters
+                    .iter_mut()
+                    .find(|waiter| waiter.id == id)
+                    .expect("the waiter should be queued");
+                if !waiter.waker.will_wake(cx.waker()) {
+                    waiter.waker = cx.waker().clone();
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Reserve<'_, T> {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let mut state = self.shared.borrow_mut();
+        let waker = if let Some(pos) = state.granted.iter().position(|granted| *granted == id) {
+            // Pass the slot on.
+            state.granted.swap_remove(pos);
+            state.release()
+        } else {
+            state.waiters.retain(|waiter| waiter.id != id);
+            None
+        };
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Reserve<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reserve").field("id", &self.id).finish()
+    }
+}
+
+/// A slot reserved by [`Sender::reserve`]. The slot is freed if the permit is
+/// dropped without sending.
+pub struct Permit<'a, T> {
+    shared: &'a Shared<T>,
+}
+
+impl<T> Permit<'_, T> {
+    /// Send a value with the reserved slot.
+    pub fn send(self, value: T) {
+        let waker = self.shared.borrow_mut().push(value);
+        mem::forget(self);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Permit<'_, T> {
+    fn drop(&mut self) {
+        let waker = self.shared.borrow_mut().release();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Permit<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Permit").finish_non_exhaustive()
+    }
+}
+
+/// The sending half of an unbounded channel created by [`unbounded`].
+pub struct UnboundedSender<T> {
+    shared: Shared<T>,
+}
+
+impl<T> UnboundedSender<T> {
+    /// Send a value. The value is returned back if the receiver has been
+    /// closed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut state = self.shared.borrow_mut();
+        if state.closed {
+            return Err(SendError(value));
+        }
+        let waker = state.push(value);
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Whether the receiver has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().closed
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        let waker = self.shared.borrow_mut().drop_sender();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for UnboundedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("UnboundedSender")
+            .field("len", &state.queue.len())
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+/// The receiving half of [`channel`] and [`unbounded`].
+pub struct Receiver<T> {
+    shared: Shared<T>,
+}
+
+impl<T> Receiver<T> {
+    /// Receive a value. It returns `None` after the channel is closed, or all
+    /// senders are dropped, and all values are received.
+    ///
+    /// It is cancel-safe: no value is lost if the future is dropped.
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive a value, see [`Receiver::recv`].
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => {
+                let mut state = self.shared.borrow_mut();
+                match &mut state.recv_waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    waker => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Try to receive a value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.borrow_mut();
+        match state.queue.pop_front() {
+            Some(value) => {
+                let waker = if state.bounded { state.release() } else { None };
+                drop(state);
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+                Ok(value)
+            }
+            None if state.closed || state.senders == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Close the channel without dropping the receiver. The senders fail to
+    /// send new values, and the values sent could still be received.
+    pub fn close(&mut self) {
+        let wakers = self.shared.borrow_mut().close();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// The number of the values in the channel.
+    pub fn len(&self) -> usize {
+        self.shared.borrow().queue.len()
+    }
+
+    /// Whether the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.shared.borrow().queue.is_empty()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        let wakers = state.close();
+        // The values may hold the senders, so they are dropped after the state is
+        // released.
+        let values = mem::take(&mut state.queue);
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+        drop(values);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("Receiver")
+            .field("len", &state.queue.len())
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
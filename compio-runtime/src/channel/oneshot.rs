@@ -0,0 +1,144 @@
+//! A channel to send a single value.
+//!
+//! ```
+//! use compio_runtime::channel::oneshot;
+//!
+//! # compio_runtime::Runtime::new().unwrap().block_on(async {
+//! let (tx, rx) = oneshot::channel();
+//! compio_runtime::spawn(async move { tx.send(42).unwrap() }).detach();
+//! assert_eq!(rx.await, Ok(42));
+//! # })
+//! ```
+
+use std::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use super::{RecvError, TryRecvError};
+
+struct State<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+}
+
+/// Create a oneshot channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(State {
+        value: None,
+        waker: None,
+        sender_dropped: false,
+        receiver_dropped: false,
+    }));
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// The sending half of [`channel`].
+pub struct Sender<T> {
+    shared: Rc<RefCell<State<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Send the value. The value is returned back if the receiver has been
+    /// dropped.
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut state = self.shared.borrow_mut();
+        if state.receiver_dropped {
+            return Err(value);
+        }
+        state.value = Some(value);
+        Ok(())
+    }
+
+    /// Whether the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().receiver_dropped
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        state.sender_dropped = true;
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// The receiving half of [`channel`]. It is a future resolving to the value,
+/// or [`RecvError`] if the sender is dropped without sending.
+pub struct Receiver<T> {
+    shared: Rc<RefCell<State<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Try to receive the value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.borrow_mut();
+        match state.value.take() {
+            Some(value) => Ok(value),
+            None if state.sender_dropped => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => {
+                let mut state = self.shared.borrow_mut();
+                match &mut state.waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    waker => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        state.receiver_dropped = true;
+        let value = state.value.take();
+        drop(state);
+        drop(value);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("Receiver")
+            .field("ready", &state.value.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,246 @@
+//! A single-producer, multi-consumer channel keeping only the latest value.
+//!
+//! The receivers observe the latest value, and are notified when it changes.
+//! The intermediate values may be missed.
+//!
+//! ```
+//! use compio_runtime::channel::watch;
+//!
+//! # compio_runtime::Runtime::new().unwrap().block_on(async {
+//! let (tx, mut rx) = watch::channel(0);
+//! compio_runtime::spawn(async move {
+//!     tx.send(1).unwrap();
+//! })
+//! .detach();
+//! rx.changed().await.unwrap();
+//! assert_eq!(*rx.borrow_and_update(), 1);
+//! # })
+//! ```
+
+use std::{
+    cell::{Ref, RefCell},
+    fmt,
+    future::poll_fn,
+    mem,
+    rc::Rc,
+    task::{Poll, Waker},
+};
+
+use super::{RecvError, SendError};
+
+struct State<T> {
+    value: T,
+    // Increased when the value is changed.
+    version: u64,
+    sender_dropped: bool,
+    receivers: usize,
+    waiters: Vec<(u64, Waker)>,
+    next_id: u64,
+}
+
+impl<T> State<T> {
+    fn new_receiver(&mut self) -> u64 {
+        self.receivers += 1;
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn wake_all(&mut self) -> Vec<Waker> {
+        self.waiters.drain(..).map(|(_, waker)| waker).collect()
+    }
+}
+
+/// Create a watch channel with the initial value.
+pub fn channel<T>(value: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(State {
+        value,
+        version: 0,
+        sender_dropped: false,
+        receivers: 0,
+        waiters: Vec::new(),
+        next_id: 0,
+    }));
+    let rx = Receiver::new(shared.clone(), 0);
+    (Sender { shared }, rx)
+}
+
+/// The sending half of [`channel`].
+pub struct Sender<T> {
+    shared: Rc<RefCell<State<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Replace the value and notify the receivers. The value is returned back
+    /// if there is no receiver.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.shared.borrow().receivers == 0 {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// Replace the value and notify the receivers even if there is no
+    /// receiver, returning the old value.
+    pub fn send_replace(&self, value: T) -> T {
+        let mut old = value;
+        self.send_modify(|value| mem::swap(value, &mut old));
+        old
+    }
+
+    /// Modify the value in place and notify the receivers.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if any [`Ref`] returned by `borrow` is held.
+    pub fn send_modify(&self, f: impl FnOnce(&mut T)) {
+        let mut state = self.shared.borrow_mut();
+        f(&mut state.value);
+        state.version += 1;
+        let wakers = state.wake_all();
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Borrow the latest value. The [`Ref`] should not be held when sending.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref::map(self.shared.borrow(), |state| &state.value)
+    }
+
+    /// Create a receiver, which treats the latest value as seen.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let version = self.shared.borrow().version;
+        Receiver::new(self.shared.clone(), version)
+    }
+
+    /// The number of the receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.borrow().receivers
+    }
+
+    /// Whether all receivers have been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        state.sender_dropped = true;
+        let wakers = state.wake_all();
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("Sender")
+            .field("value", &state.value)
+            .field("version", &state.version)
+            .finish()
+    }
+}
+
+/// The receiving half of [`channel`]. Cloning a receiver creates one which
+/// has seen the same version.
+pub struct Receiver<T> {
+    shared: Rc<RefCell<State<T>>>,
+    id: u64,
+    // The version seen by the receiver.
+    version: u64,
+}
+
+impl<T> Receiver<T> {
+    fn new(shared: Rc<RefCell<State<T>>>, version: u64) -> Self {
+        let id = shared.borrow_mut().new_receiver();
+        Self {
+            shared,
+            id,
+            version,
+        }
+    }
+
+    /// Borrow the latest value without marking it as seen. The [`Ref`] should
+    /// not be held when sending.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref::map(self.shared.borrow(), |state| &state.value)
+    }
+
+    /// Borrow the latest value and mark it as seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let state = self.shared.borrow();
+        self.version = state.version;
+        Ref::map(state, |state| &state.value)
+    }
+
+    /// Whether the value has been changed since last seen. It fails if the
+    /// sender has been dropped.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        let state = self.shared.borrow();
+        if state.sender_dropped {
+            Err(RecvError)
+        } else {
+            Ok(state.version != self.version)
+        }
+    }
+
+    /// Wait until the value is changed since last seen, and mark it as seen.
+    /// It fails if the sender is dropped without changing the value.
+    ///
+    /// It is cancel-safe: no change is missed if the future is dropped.
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        poll_fn(|cx| {
+            let mut state = self.shared.borrow_mut();
+            if state.version != self.version {
+                self.version = state.version;
+                return Poll::Ready(Ok(()));
+            }
+            if state.sender_dropped {
+                return Poll::Ready(Err(RecvError));
+            }
+            match state.waiters.iter_mut().find(|(id, _)| *id == self.id) {
+                Some((_, waker)) => {
+                    if !waker.will_wake(cx.waker()) {
+                        *waker = cx.waker().clone();
+                    }
+                }
+                None => state.waiters.push((self.id, cx.waker().clone())),
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Mark the latest value as seen.
+    pub fn mark_unchanged(&mut self) {
+        self.version = self.shared.borrow().version;
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.shared.clone(), self.version)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.borrow_mut();
+        state.receivers -= 1;
+        state.waiters.retain(|(id, _)| *id != self.id);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.borrow();
+        f.debug_struct("Receiver")
+            .field("value", &state.value)
+            .field("version", &self.version)
+            .finish()
+    }
+}
@@ -0,0 +1,345 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::{Future, poll_fn},
+    mem,
+    panic::{AssertUnwindSafe, resume_unwind},
+    pin::pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use futures_util::{
+    FutureExt, StreamExt,
+    future::{LocalBoxFuture, join_all},
+    stream::FuturesUnordered,
+};
+
+use crate::JoinHandle;
+
+/// The result of a task, which is `Err` with the panic payload if the task
+/// panicked. The same as the output of [`JoinHandle`].
+pub type JoinResult<T> = Result<T, Box<dyn Any + Send>>;
+
+/// A set of tasks spawned on the current runtime, which could be awaited in
+/// the order they complete.
+///
+/// All tasks are cancelled when the set is dropped.
+///
+/// ```
+/// use compio_runtime::JoinSet;
+///
+/// # compio_runtime::Runtime::new().unwrap().block_on(async {
+/// let mut set = JoinSet::new();
+/// for i in 0..3 {
+///     set.spawn(async move { i });
+/// }
+/// let mut sum = 0;
+/// while let Some(res) = set.join_next().await {
+///     sum += res.unwrap();
+/// }
+/// assert_eq!(sum, 3);
+/// # })
+/// ```
+pub struct JoinSet<T> {
+    tasks: FuturesUnordered<JoinHandle<T>>,
+}
+
+impl<T> JoinSet<T> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self {
+            tasks: FuturesUnordered::new(),
+        }
+    }
+
+    /// The number of the tasks not joined.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Whether there is no task to join.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+impl<T: 'static> JoinSet<T> {
+    /// Spawn a task on the current runtime, and add it to the set.
+    ///
+    /// ## Panics
+    ///
+    /// This method doesn't create runtime. It tries to obtain the current
+    /// runtime by [`Runtime::with_current`](crate::Runtime::with_current).
+    pub fn spawn(&mut self, future: impl Future<Output = T> + 'static) {
+        self.tasks.push(crate::spawn(future));
+    }
+
+    /// Wait for the next task to complete, and remove it from the set. It
+    /// returns `None` if the set is empty.
+    ///
+    /// It is cancel-safe: no result is lost if the future is dropped.
+    pub async fn join_next(&mut self) -> Option<JoinResult<T>> {
+        self.tasks.next().await
+    }
+
+    /// Poll to join the next task, see [`JoinSet::join_next`].
+    pub fn poll_join_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<JoinResult<T>>> {
+        self.tasks.poll_next_unpin(cx)
+    }
+
+    /// Cancel all tasks and remove them from the set. The tasks are dropped
+    /// by the runtime later.
+    pub fn abort_all(&mut self) {
+        self.tasks.clear();
+    }
+
+    /// Cancel all tasks, remove them from the set, and wait until they are
+    /// dropped.
+    pub async fn shutdown(&mut self) {
+        let tasks = mem::take(&mut self.tasks);
+        join_all(tasks.into_iter().map(|task| task.cancel())).await;
+    }
+
+    /// Remove all tasks from the set without cancelling them.
+    pub fn detach_all(&mut self) {
+        mem::take(&mut self.tasks)
+            .into_iter()
+            .for_each(|task| task.detach());
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for JoinSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinSet").field("len", &self.len()).finish()
+    }
+}
+
+struct ScopeState<'env, T> {
+    // Spawned but not yet polled by the scope.
+    spawned: RefCell<Vec<LocalBoxFuture<'env, JoinResult<T>>>>,
+    tasks: RefCell<FuturesUnordered<LocalBoxFuture<'env, JoinResult<T>>>>,
+    ready: RefCell<VecDeque<JoinResult<T>>>,
+    // The number of the tasks not completed.
+    running: Cell<usize>,
+    // Set if the tasks are aborted when being polled.
+    aborted: Cell<bool>,
+    scope_waker: RefCell<Option<Waker>>,
+    join_waker: RefCell<Option<Waker>>,
+}
+
+impl<T> ScopeState<'_, T> {
+    fn wake_joiner(&self) {
+        if let Some(waker) = self.join_waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+
+    fn clear(&self) {
+        let spawned = mem::take(&mut *self.spawned.borrow_mut());
+        let tasks = mem::take(&mut *self.tasks.borrow_mut());
+        self.running.set(0);
+        drop(spawned);
+        drop(tasks);
+    }
+
+    // Poll the tasks until all are pending, and return whether any task is
+    // still running.
+    fn poll_tasks(&self, cx: &mut Context<'_>) -> bool {
+        loop {
+            let spawned = mem::take(&mut *self.spawned.borrow_mut());
+            let mut tasks = self.tasks.borrow_mut();
+            tasks.extend(spawned);
+            let res = tasks.poll_next_unpin(cx);
+            drop(tasks);
+            let completed = match res {
+                Poll::Ready(Some(res)) => {
+                    self.running.set(self.running.get() - 1);
+                    self.ready.borrow_mut().push_back(res);
+                    self.wake_joiner();
+                    true
+                }
+                _ => false,
+            };
+            if self.aborted.replace(false) {
+                self.clear();
+                self.wake_joiner();
+                return false;
+            }
+            if !completed && self.spawned.borrow().is_empty() {
+                break;
+            }
+        }
+        if self.running.get() == 0 {
+            self.wake_joiner();
+        }
+        self.running.get() > 0
+    }
+}
+
+/// A handle to spawn tasks in [`scope`]. It could be cloned and moved into
+/// the tasks.
+pub struct Scope<'env, T> {
+    state: Rc<ScopeState<'env, T>>,
+}
+
+impl<'env, T> Scope<'env, T> {
+    /// Spawn a task in the scope. The task could borrow the values outliving
+    /// the scope.
+    pub fn spawn(&self, future: impl Future<Output = T> + 'env) {
+        let task = AssertUnwindSafe(future).catch_unwind().boxed_local();
+        self.state.spawned.borrow_mut().push(task);
+        self.state.running.set(self.state.running.get() + 1);
+        if let Some(waker) = &*self.state.scope_waker.borrow() {
+            waker.wake_by_ref();
+        }
+    }
+
+    /// Wait for the next task to complete. It returns `None` if no task is
+    /// running.
+    ///
+    /// It is cancel-safe: no result is lost if the future is dropped.
+    pub async fn join_next(&self) -> Option<JoinResult<T>> {
+        poll_fn(|cx| {
+            if let Some(res) = self.state.ready.borrow_mut().pop_front() {
+                return Poll::Ready(Some(res));
+            }
+            if self.state.running.get() == 0 {
+                return Poll::Ready(None);
+            }
+            *self.state.join_waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Cancel all running tasks. The results of the completed tasks are kept.
+    pub fn abort_all(&self) {
+        match self.state.tasks.try_borrow_mut() {
+            Ok(tasks) => {
+                drop(tasks);
+                self.state.clear();
+                self.state.wake_joiner();
+            }
+            // Called by a task, and the tasks are dropped after being polled.
+            Err(_) => self.state.aborted.set(true),
+        }
+    }
+
+    /// The number of the running tasks.
+    pub fn len(&self) -> usize {
+        self.state.running.get()
+    }
+
+    /// Whether no task is running.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for Scope<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Scope<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope").field("len", &self.len()).finish()
+    }
+}
+
+// Drops the tasks when the scope ends or is cancelled. The tasks may hold the
+// scope, so they should be dropped explicitly.
+struct ScopeGuard<'a, 'env, T>(&'a ScopeState<'env, T>);
+
+impl<T> Drop for ScopeGuard<'_, '_, T> {
+    fn drop(&mut self) {
+        self.0.clear();
+        let ready = mem::take(&mut *self.0.ready.borrow_mut());
+        drop(ready);
+    }
+}
+
+/// Run the future with a [`Scope`] to spawn tasks borrowing the values on the
+/// stack of the caller. It returns after the future and all tasks complete.
+///
+/// The tasks are polled by the scope itself rather than scheduled by the
+/// runtime, so they run concurrently with the future, and are dropped if the
+/// scope is dropped. If a task panics and its result is not taken by
+/// [`Scope::join_next`], the panic is resumed after all tasks complete.
+///
+/// ```
+/// # compio_runtime::Runtime::new().unwrap().block_on(async {
+/// let values = vec![1, 2, 3];
+/// let values = &values;
+/// let sum = compio_runtime::scope(|s| async move {
+///     for value in values {
+///         s.spawn(async move { *value * 2 });
+///     }
+///     let mut sum = 0;
+///     while let Some(res) = s.join_next().await {
+///         sum += res.unwrap();
+///     }
+///     sum
+/// })
+/// .await;
+/// assert_eq!(sum, 12);
+/// # })
+/// ```
+pub async fn scope<'env, T, R, F, Fut>(f: F) -> R
+where
+    F: FnOnce(Scope<'env, T>) -> Fut,
+    Fut: Future<Output = R>,
+{
+    let state = Rc::new(ScopeState {
+        spawned: RefCell::new(Vec::new()),
+        tasks: RefCell::new(FuturesUnordered::new()),
+        ready: RefCell::new(VecDeque::new()),
+        running: Cell::new(0),
+        aborted: Cell::new(false),
+        scope_waker: RefCell::new(None),
+        join_waker: RefCell::new(None),
+    });
+    let guard = ScopeGuard(&state);
+    let mut future = pin!(f(Scope {
+        state: state.clone(),
+    }));
+    let mut result = None;
+    poll_fn(|cx| {
+        match &mut *state.scope_waker.borrow_mut() {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            waker => *waker = Some(cx.waker().clone()),
+        }
+        if result.is_none() {
+            if let Poll::Ready(res) = future.as_mut().poll(cx) {
+                result = Some(res);
+            }
+        }
+        let running = state.poll_tasks(cx);
+        if result.is_some() && !running {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+    let ready = mem::take(&mut *state.ready.borrow_mut());
+    drop(guard);
+    for res in ready {
+        if let Err(e) = res {
+            resume_unwind(e);
+        }
+    }
+    result.expect("the future should complete")
+}
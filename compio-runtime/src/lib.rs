@@ -12,17 +12,27 @@
 #![warn(missing_docs)]
 
 mod attacher;
+mod cancel;
+mod join_set;
 mod runtime;
 
+pub mod channel;
 #[cfg(feature = "event")]
 pub mod event;
+pub mod remote;
+pub mod sync;
 #[cfg(feature = "time")]
 pub mod time;
 
 pub use async_task::Task;
 pub use attacher::*;
+pub use cancel::*;
 use compio_buf::BufResult;
+pub use join_set::*;
+#[cfg(feature = "metrics")]
+pub use runtime::RuntimeMetrics;
 pub use runtime::{
-    BorrowedBuffer, BufferPool, JoinHandle, Runtime, RuntimeBuilder, spawn, spawn_blocking, submit,
-    submit_with_flags,
+    BorrowedBuffer, BufferPool, JoinHandle, Link, MultishotItem, Runtime, RuntimeBuilder,
+    RuntimeHandle, SubmitMultishot, spawn, spawn_blocking, spawn_blocking_with, submit, submit_all,
+    submit_link, submit_multishot, submit_with_cancel, submit_with_flags,
 };
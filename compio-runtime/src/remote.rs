@@ -0,0 +1,292 @@
+//! Channels to send values to the tasks on another runtime.
+//!
+//! The receiver belongs to the runtime creating the channel, and the senders
+//! could be sent to other threads. When a sender runs on an io-uring runtime
+//! and the receiving runtime is also io-uring, the receiver is woken by
+//! posting a message to the completion queue of the receiving runtime with
+//! [`MsgRing`], which is submitted with other operations of the sending
+//! runtime. Otherwise, the id of the channel is queued to the receiving
+//! runtime, which is woken through its [`NotifyHandle`]. Either way the
+//! receiver is woken on its own thread, instead of by a waker shared across
+//! threads.
+//!
+//! ```
+//! use compio_runtime::remote;
+//!
+//! # compio_runtime::Runtime::new().unwrap().block_on(async {
+//! let (tx, mut rx) = remote::channel();
+//! let handle = std::thread::spawn(move || {
+//!     compio_runtime::Runtime::new()
+//!         .unwrap()
+//!         .block_on(async move {
+//!             tx.send(42).unwrap();
+//!         })
+//! });
+//! assert_eq!(rx.recv().await, Some(42));
+//! assert_eq!(rx.recv().await, None);
+//! handle.join().unwrap();
+//! # })
+//! ```
+//!
+//! [`MsgRing`]: compio_driver::op::MsgRing
+//! [`NotifyHandle`]: compio_driver::NotifyHandle
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    future::{Future, poll_fn},
+    pin::Pin,
+    rc::Weak,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use compio_driver::{NotifyHandle, RawFd};
+use crossbeam_queue::SegQueue;
+use futures_util::task::AtomicWaker;
+
+use crate::{Runtime, channel::RecvError};
+
+/// The wakers of the remote channels of a runtime, by their ids.
+pub(crate) type RemoteWakers = RefCell<HashMap<u32, Arc<AtomicWaker>>>;
+
+/// The ids of the remote channels to wake, queued by other threads.
+pub(crate) struct RemoteWakeups {
+    ids: SegQueue<u32>,
+    notify: NotifyHandle,
+    // Cleared when the runtime is dropped, after which its ring fd may be
+    // reused by another ring.
+    alive: AtomicBool,
+}
+
+impl RemoteWakeups {
+    pub fn new(notify: NotifyHandle) -> Self {
+        Self {
+            ids: SegQueue::new(),
+            notify,
+            alive: AtomicBool::new(true),
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+
+    pub fn close(&self) {
+        self.alive.store(false, Ordering::Release);
+    }
+
+    pub fn wake(&self, id: u32) {
+        self.ids.push(id);
+        self.notify.notify().ok();
+    }
+
+    pub fn pop(&self) -> Option<u32> {
+        self.ids.pop()
+    }
+}
+
+struct Shared<T> {
+    queue: SegQueue<T>,
+    wakeups: Arc<RemoteWakeups>,
+    waiting: AtomicBool,
+    senders: AtomicUsize,
+    closed: AtomicBool,
+    ring: Option<RawFd>,
+    id: u32,
+}
+
+impl<T> Shared<T> {
+    fn wake(&self) {
+        if !self.waiting.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if let Some(ring) = self.ring.filter(|_| self.wakeups.is_alive()) {
+            if Runtime::try_with_current(|r| r.post_message(ring, self.id, &self.wakeups))
+                .unwrap_or(false)
+            {
+                return;
+            }
+        }
+        self.wakeups.wake(self.id);
+    }
+}
+
+/// Create a channel, whose receiver belongs to the current runtime.
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`].
+pub fn channel<T: Send>() -> (Sender<T>, Receiver<T>) {
+    let waker = Arc::new(AtomicWaker::new());
+    let (ring, id, wakeups, wakers) = Runtime::with_current(|r| {
+        (
+            r.message_ring(),
+            r.register_remote(waker.clone()),
+            r.remote_wakeups(),
+            r.remote_wakers(),
+        )
+    });
+    let shared = Arc::new(Shared {
+        queue: SegQueue::new(),
+        wakeups,
+        waiting: AtomicBool::new(false),
+        senders: AtomicUsize::new(1),
+        closed: AtomicBool::new(false),
+        ring,
+        id,
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver {
+            shared,
+            waker,
+            wakers,
+        },
+    )
+}
+
+/// The sending half of [`channel`]. It could be cloned and sent to other
+/// threads.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send a value to the receiver. The value is returned back if the
+    /// receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), T> {
+        if self.shared.closed.load(Ordering::Acquire) {
+            return Err(value);
+        }
+        self.shared.queue.push(value);
+        self.shared.wake();
+        Ok(())
+    }
+
+    /// Whether the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.wake();
+        }
+    }
+}
+
+/// The receiving half of [`channel`]. It belongs to the runtime creating it.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    // Registered to the runtime, and woken by it.
+    waker: Arc<AtomicWaker>,
+    // The registry of the owning runtime, to unregister from it even if the
+    // receiver is dropped elsewhere. It also makes the receiver !Send.
+    wakers: Weak<RemoteWakers>,
+}
+
+impl<T> Receiver<T> {
+    /// Receive a value. It returns `None` after all senders are dropped and
+    /// all values are received.
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Try to receive a value without waiting.
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.shared.queue.pop()
+    }
+
+    /// Poll to receive a value, see [`Receiver::recv`].
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let shared = &self.shared;
+        if let Some(value) = shared.queue.pop() {
+            return Poll::Ready(Some(value));
+        }
+        self.waker.register(cx.waker());
+        shared.waiting.store(true, Ordering::SeqCst);
+        // Check again after registering, or a wakeup may be lost.
+        if let Some(value) = shared.queue.pop() {
+            shared.waiting.store(false, Ordering::SeqCst);
+            Poll::Ready(Some(value))
+        } else if shared.senders.load(Ordering::SeqCst) == 0 {
+            Poll::Ready(shared.queue.pop())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        if let Some(wakers) = self.wakers.upgrade() {
+            wakers.borrow_mut().remove(&self.shared.id);
+        }
+    }
+}
+
+/// Create a channel to send a single value, whose receiver belongs to the
+/// current runtime.
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`].
+pub fn oneshot<T: Send>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let (tx, rx) = channel();
+    (OneshotSender(tx), OneshotReceiver(rx))
+}
+
+/// The sending half of [`oneshot`]. It could be sent to other threads.
+pub struct OneshotSender<T>(Sender<T>);
+
+impl<T> OneshotSender<T> {
+    /// Send the value. The value is returned back if the receiver has been
+    /// dropped.
+    pub fn send(self, value: T) -> Result<(), T> {
+        self.0.send(value)
+    }
+
+    /// Whether the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+}
+
+/// The receiving half of [`oneshot`]. It is a future resolving to the value,
+/// or [`RecvError`] if the sender is dropped without sending.
+pub struct OneshotReceiver<T>(Receiver<T>);
+
+impl<T> OneshotReceiver<T> {
+    /// Try to receive the value without waiting.
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.0.try_recv()
+    }
+}
+
+impl<T> Future for OneshotReceiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_recv(cx).map(|value| value.ok_or(RecvError))
+    }
+}
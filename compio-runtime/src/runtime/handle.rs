@@ -0,0 +1,141 @@
+use std::{
+    fmt,
+    future::Future,
+    panic::{AssertUnwindSafe, resume_unwind},
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use async_task::Runnable;
+use compio_driver::NotifyHandle;
+use futures_util::FutureExt;
+
+use super::{JoinHandle, RunnableQueue};
+
+/// A handle to spawn tasks on a [`Runtime`](crate::Runtime) from other
+/// threads. It could be cloned and sent to other threads, and is obtained by
+/// [`Runtime::handle`](crate::Runtime::handle).
+///
+/// The tasks are queued to the runtime, which is woken through its
+/// [`NotifyHandle`]. If the runtime has been dropped, the tasks are cancelled
+/// without running, and awaiting their [`JoinHandle`]s panics.
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use compio_runtime::Runtime;
+///
+/// let runtime = Runtime::new().unwrap();
+/// let handle = runtime.handle();
+/// let task = std::thread::spawn(move || {
+///     handle.spawn_local_fn(|| async {
+///         let value = Rc::new(42);
+///         *value
+///     })
+/// })
+/// .join()
+/// .unwrap();
+/// assert_eq!(runtime.block_on(task).unwrap(), 42);
+/// ```
+#[derive(Clone)]
+pub struct RuntimeHandle {
+    runnables: Arc<RunnableQueue>,
+    notify: Arc<NotifyHandle>,
+}
+
+impl RuntimeHandle {
+    pub(super) fn new(runnables: Arc<RunnableQueue>, notify: NotifyHandle) -> Self {
+        Self {
+            runnables,
+            notify: Arc::new(notify),
+        }
+    }
+
+    /// Spawn a task on the runtime.
+    pub fn spawn<F: Future + Send + 'static>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F::Output: Send,
+    {
+        let future = AssertUnwindSafe(future).catch_unwind();
+        let (runnable, task) = async_task::spawn(future, self.schedule());
+        self.push(runnable);
+        task
+    }
+
+    /// Spawn a task on the runtime, whose future is created by `f` on the
+    /// thread of the runtime. The future doesn't need to be [`Send`], so it
+    /// could use the thread-local resources, e.g., the files or sockets.
+    pub fn spawn_local_fn<F, Fut>(&self, f: F) -> JoinHandle<Fut::Output>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let future = AssertUnwindSafe(async move { f().await }).catch_unwind();
+        // SAFETY: the future is only polled on the thread of the runtime, or
+        // dropped there after being polled. Before being polled, it holds only
+        // `f`, which is `Send`.
+        let (runnable, task) = unsafe { async_task::spawn_unchecked(future, self.schedule()) };
+        self.push(runnable);
+        task
+    }
+
+    /// Spawn a task on the runtime, and block the current thread until it
+    /// completes.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if called on the thread of the runtime, or if the runtime is
+    /// dropped before the task completes. The panic of the task is resumed.
+    pub fn block_on_remote<F: Future + Send + 'static>(&self, future: F) -> F::Output
+    where
+        F::Output: Send,
+    {
+        assert!(
+            !self.runnables.local_runnables.valid(),
+            "cannot block on the thread of the runtime"
+        );
+        let mut task = pin!(self.spawn(future));
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match task.as_mut().poll(&mut cx) {
+                Poll::Ready(res) => return res.unwrap_or_else(|e| resume_unwind(e)),
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    fn schedule(&self) -> impl Fn(Runnable) + Send + Sync + 'static {
+        let runnables = self.runnables.clone();
+        let notify = self.notify.clone();
+        move |runnable| runnables.schedule(runnable, &notify)
+    }
+
+    fn push(&self, runnable: Runnable) {
+        if let Err(runnable) = self.runnables.push_remote(runnable, &self.notify) {
+            // The runtime has been dropped, and the task is cancelled.
+            drop(runnable);
+        }
+    }
+}
+
+impl fmt::Debug for RuntimeHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuntimeHandle").finish_non_exhaustive()
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
@@ -0,0 +1,38 @@
+use std::{future::Future, time::Duration};
+
+use compio_driver::OpCode;
+use futures_util::FutureExt;
+
+use crate::{BufResult, runtime::op::OpFuture};
+
+/// A chain of linked operations, see [`submit_link`].
+///
+/// [`submit_link`]: crate::submit_link
+pub struct Link<'a> {
+    inner: compio_driver::Link<'a>,
+}
+
+impl<'a> Link<'a> {
+    pub(crate) fn new(inner: compio_driver::Link<'a>) -> Self {
+        Self { inner }
+    }
+
+    /// Append an operation to the chain, and return a future for it.
+    pub fn push<T: OpCode + 'static>(
+        &mut self,
+        op: T,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        OpFuture::new(self.inner.push(op)).map(|(res, _)| res)
+    }
+
+    /// Append an operation with a timeout to the chain, and return a future
+    /// for it. If the operation doesn't complete in `timeout`, it fails with
+    /// [`std::io::ErrorKind::TimedOut`].
+    pub fn push_with_timeout<T: OpCode + 'static>(
+        &mut self,
+        op: T,
+        timeout: Duration,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        OpFuture::new(self.inner.push_with_timeout(op, timeout)).map(|(res, _)| res)
+    }
+}
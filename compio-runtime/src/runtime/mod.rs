@@ -1,25 +1,35 @@
+#[cfg(feature = "time")]
+use std::task::Poll;
 use std::{
     any::Any,
     cell::{Cell, RefCell},
-    collections::VecDeque,
-    future::{Future, ready},
+    collections::{HashMap, VecDeque},
+    future::{Future, poll_fn, ready},
     io,
     marker::PhantomData,
+    mem::ManuallyDrop,
     panic::AssertUnwindSafe,
-    rc::Rc,
-    sync::Arc,
-    task::{Context, Poll},
+    pin::{Pin, pin},
+    rc::{Rc, Weak},
+    sync::{Arc, Mutex, PoisonError},
+    task::Context,
     time::Duration,
 };
 
 use async_task::{Runnable, Task};
 use compio_buf::IntoInner;
+#[cfg(feature = "metrics")]
+use compio_driver::DriverMetrics;
+#[cfg(feature = "faults")]
+use compio_driver::FaultRules;
 use compio_driver::{
-    AsRawFd, Key, NotifyHandle, OpCode, Proactor, ProactorBuilder, PushEntry, RawFd, op::Asyncify,
+    AsRawFd, DispatchOptions, DriverType, FixedFd, Key, NotifyHandle, OpCode, OwnedFd, Proactor,
+    ProactorBuilder, PushEntry, RawFd, RegisteredBuffers, RingMessage, SetupReport,
+    op::{Asyncify, MsgRing, Multishot},
 };
 use compio_log::{debug, instrument};
 use crossbeam_queue::SegQueue;
-use futures_util::{FutureExt, future::Either};
+use futures_util::{FutureExt, future::Either, task::AtomicWaker};
 
 pub(crate) mod op;
 #[cfg(feature = "time")]
@@ -28,32 +38,68 @@ pub(crate) mod time;
 mod buffer_pool;
 pub use buffer_pool::*;
 
+mod handle;
+pub use handle::*;
+
+mod link;
+pub use link::*;
+
+mod multishot;
+pub use multishot::*;
+
 mod send_wrapper;
 use send_wrapper::SendWrapper;
 
 #[cfg(feature = "time")]
 use crate::runtime::time::{TimerFuture, TimerRuntime};
-use crate::{BufResult, runtime::op::OpFuture};
+use crate::{
+    BufResult, CancellationToken,
+    remote::{RemoteWakers, RemoteWakeups},
+    runtime::op::OpFuture,
+};
 
 scoped_tls::scoped_thread_local!(static CURRENT_RUNTIME: Runtime);
 
+/// The bit of the message values reserved by the remote channels.
+const REMOTE_BIT: u32 = 1 << 31;
+
 /// Type alias for `Task<Result<T, Box<dyn Any + Send>>>`, which resolves to an
 /// `Err` when the spawned future panicked.
 pub type JoinHandle<T> = Task<Result<T, Box<dyn Any + Send>>>;
 
 struct RunnableQueue {
-    local_runnables: SendWrapper<RefCell<VecDeque<Runnable>>>,
+    local_runnables: ManuallyDrop<SendWrapper<RefCell<VecDeque<Runnable>>>>,
     sync_runnables: SegQueue<Runnable>,
+    // Set when the runtime is dropped, so that no task is spawned by the handles.
+    closed: Mutex<bool>,
 }
 
 impl RunnableQueue {
     pub fn new() -> Self {
         Self {
-            local_runnables: SendWrapper::new(RefCell::new(VecDeque::new())),
+            local_runnables: ManuallyDrop::new(SendWrapper::new(RefCell::new(VecDeque::new()))),
             sync_runnables: SegQueue::new(),
+            closed: Mutex::new(false),
         }
     }
 
+    /// Queue a new task spawned by a [`RuntimeHandle`]. The task is returned
+    /// back if the runtime has been dropped.
+    pub fn push_remote(&self, runnable: Runnable, handle: &NotifyHandle) -> Result<(), Runnable> {
+        let closed = self.closed.lock().unwrap_or_else(PoisonError::into_inner);
+        if *closed {
+            return Err(runnable);
+        }
+        self.sync_runnables.push(runnable);
+        drop(closed);
+        handle.notify().ok();
+        Ok(())
+    }
+
+    pub fn close(&self) {
+        *self.closed.lock().unwrap_or_else(PoisonError::into_inner) = true;
+    }
+
     pub fn schedule(&self, runnable: Runnable, handle: &NotifyHandle) {
         if let Some(runnables) = self.local_runnables.get() {
             runnables.borrow_mut().push_back(runnable);
@@ -65,26 +111,97 @@ impl RunnableQueue {
         }
     }
 
+    /// Run the tasks, and return whether there are still tasks in the queue,
+    /// and the number of the tasks polled.
+    ///
     /// SAFETY: call in the main thread
-    pub unsafe fn run(&self, event_interval: usize) -> bool {
+    pub unsafe fn run(&self, event_interval: usize) -> (bool, usize) {
         let local_runnables = self.local_runnables.get_unchecked();
+        let mut polled = 0;
         for _i in 0..event_interval {
             let next_task = local_runnables.borrow_mut().pop_front();
             let has_local_task = next_task.is_some();
             if let Some(task) = next_task {
                 task.run();
+                polled += 1;
             }
             // Cheaper than pop.
             let has_sync_task = !self.sync_runnables.is_empty();
             if has_sync_task {
                 if let Some(task) = self.sync_runnables.pop() {
                     task.run();
+                    polled += 1;
                 }
             } else if !has_local_task {
                 break;
             }
         }
-        !(local_runnables.borrow_mut().is_empty() && self.sync_runnables.is_empty())
+        let remaining =
+            !(local_runnables.borrow_mut().is_empty() && self.sync_runnables.is_empty());
+        (remaining, polled)
+    }
+
+    /// SAFETY: call in the main thread
+    #[cfg(feature = "metrics")]
+    pub unsafe fn len(&self) -> (usize, usize) {
+        (
+            self.local_runnables.get_unchecked().borrow().len(),
+            self.sync_runnables.len(),
+        )
+    }
+}
+
+impl Drop for RunnableQueue {
+    fn drop(&mut self) {
+        // A waker woken on another thread may hold the last reference after the
+        // runtime is dropped. The local queue has been cleared by the runtime then,
+        // so it is fine to leak it.
+        if self.local_runnables.valid() {
+            // SAFETY: on the main thread, and the field is not used after.
+            unsafe { ManuallyDrop::drop(&mut self.local_runnables) }
+        }
+    }
+}
+
+/// A snapshot of the counters of a [`Runtime`], see [`Runtime::metrics`].
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct RuntimeMetrics {
+    /// The counters of the driver, see [`Proactor::metrics`].
+    pub driver: DriverMetrics,
+    /// The tasks scheduled on the runtime thread and waiting to run.
+    pub local_queue_len: usize,
+    /// The tasks scheduled from other threads and waiting to run.
+    pub sync_queue_len: usize,
+    /// The times the runnable queue was run, i.e., the ticks.
+    pub ticks: u64,
+    /// The tasks polled in total.
+    pub tasks_polled: u64,
+    /// The tasks polled in the last tick.
+    pub last_tick_polled: usize,
+    /// The most tasks polled in one tick.
+    pub max_tick_polled: usize,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default)]
+struct RuntimeCounters {
+    ticks: Cell<u64>,
+    tasks_polled: Cell<u64>,
+    last_tick_polled: Cell<usize>,
+    max_tick_polled: Cell<usize>,
+}
+
+#[cfg(feature = "metrics")]
+impl RuntimeCounters {
+    fn tick(&self, polled: usize) {
+        self.ticks.set(self.ticks.get() + 1);
+        self.tasks_polled
+            .set(self.tasks_polled.get() + polled as u64);
+        self.last_tick_polled.set(polled);
+        self.max_tick_polled
+            .set(self.max_tick_polled.get().max(polled));
     }
 }
 
@@ -92,11 +209,16 @@ thread_local! {
     static RUNTIME_ID: Cell<u64> = const { Cell::new(0) };
 }
 
+/// A message posted to another runtime, the wakeups of the receiving runtime,
+/// and the message data.
+type Post = (Key<MsgRing>, Arc<RemoteWakeups>, u32);
+
 /// The async runtime of compio. It is a thread local runtime, and cannot be
 /// sent to other threads.
 pub struct Runtime {
     driver: RefCell<Proactor>,
     runnables: Arc<RunnableQueue>,
+    // The fallback of the timers which the driver fails to create.
     #[cfg(feature = "time")]
     timer_runtime: RefCell<TimerRuntime>,
     event_interval: usize,
@@ -108,6 +230,16 @@ pub struct Runtime {
     // - buffer pool will return a wrong buffer which the buffer's data is uninit, that will cause
     //   UB
     id: u64,
+    // The messages posted by other drivers, which are not consumed by the remote channels.
+    messages: RefCell<VecDeque<RingMessage>>,
+    remote_wakers: Rc<RemoteWakers>,
+    remote_wakeups: Arc<RemoteWakeups>,
+    next_remote_id: Cell<u32>,
+    // The messages posted to other runtimes and not completed, with the wakeups
+    // to fall back to if the posting fails.
+    posts: RefCell<Vec<Post>>,
+    #[cfg(feature = "metrics")]
+    counters: RuntimeCounters,
     // Other fields don't make it !Send, but actually `local_runnables` implies it should be !Send,
     // otherwise it won't be valid if the runtime is sent to other threads.
     _p: PhantomData<Rc<VecDeque<Runnable>>>,
@@ -127,13 +259,22 @@ impl Runtime {
     fn with_builder(builder: &RuntimeBuilder) -> io::Result<Self> {
         let id = RUNTIME_ID.get();
         RUNTIME_ID.set(id + 1);
+        let driver = builder.proactor_builder.build()?;
+        let remote_wakeups = Arc::new(RemoteWakeups::new(driver.handle()));
         Ok(Self {
-            driver: RefCell::new(builder.proactor_builder.build()?),
+            driver: RefCell::new(driver),
             runnables: Arc::new(RunnableQueue::new()),
             #[cfg(feature = "time")]
             timer_runtime: RefCell::new(TimerRuntime::new()),
             event_interval: builder.event_interval,
             id,
+            messages: RefCell::new(VecDeque::new()),
+            remote_wakers: Rc::new(RefCell::new(HashMap::new())),
+            remote_wakeups,
+            next_remote_id: Cell::new(0),
+            posts: RefCell::new(Vec::new()),
+            #[cfg(feature = "metrics")]
+            counters: RuntimeCounters::default(),
             _p: PhantomData,
         })
     }
@@ -169,7 +310,13 @@ impl Runtime {
     /// Set this runtime as current runtime, and perform a function in the
     /// current scope.
     pub fn enter<T, F: FnOnce() -> T>(&self, f: F) -> T {
-        CURRENT_RUNTIME.set(self, f)
+        self.driver_type().scope(|| CURRENT_RUNTIME.set(self, f))
+    }
+
+    /// The type of the driver of the runtime, see [`ProactorBuilder::driver`].
+    /// It is also the [`DriverType::current`] when the runtime is entered.
+    pub fn driver_type(&self) -> DriverType {
+        self.driver.borrow().driver_type()
     }
 
     /// Spawns a new asynchronous task, returning a [`Task`] for it.
@@ -195,12 +342,15 @@ impl Runtime {
     /// The return value indicates whether there are still tasks in the queue.
     pub fn run(&self) -> bool {
         // SAFETY: self is !Send + !Sync.
-        unsafe { self.runnables.run(self.event_interval) }
+        let (remaining, _polled) = unsafe { self.runnables.run(self.event_interval) };
+        #[cfg(feature = "metrics")]
+        self.counters.tick(_polled);
+        remaining
     }
 
     /// Block on the future till it completes.
     pub fn block_on<F: Future>(&self, future: F) -> F::Output {
-        CURRENT_RUNTIME.set(self, || {
+        self.enter(|| {
             let mut result = None;
             unsafe { self.spawn_unchecked(async { result = Some(future.await) }) }.detach();
             loop {
@@ -225,17 +375,39 @@ impl Runtime {
         unsafe { self.spawn_unchecked(AssertUnwindSafe(future).catch_unwind()) }
     }
 
+    /// Create a handle to spawn tasks on this runtime from other threads.
+    pub fn handle(&self) -> RuntimeHandle {
+        RuntimeHandle::new(self.runnables.clone(), self.driver.borrow().handle())
+    }
+
     /// Spawns a blocking task in a new thread, and wait for it.
     ///
-    /// The task will not be cancelled even if the future is dropped.
+    /// The task will not be cancelled even if the future is dropped. See
+    /// [`Runtime::spawn_blocking_with`] and [`DispatchOptions::cancel_queued`]
+    /// to drop it if it is still waiting for a thread.
     pub fn spawn_blocking<T: Send + 'static>(
         &self,
         f: impl (FnOnce() -> T) + Send + 'static,
+    ) -> JoinHandle<T> {
+        self.spawn_blocking_with(DispatchOptions::default(), f)
+    }
+
+    /// Spawns a blocking task with the options, e.g., to a dedicated
+    /// [`AsyncifyPool`] or with a higher [`Priority`]. See
+    /// [`Runtime::spawn_blocking`].
+    ///
+    /// [`AsyncifyPool`]: compio_driver::AsyncifyPool
+    /// [`Priority`]: compio_driver::Priority
+    pub fn spawn_blocking_with<T: Send + 'static>(
+        &self,
+        options: DispatchOptions,
+        f: impl (FnOnce() -> T) + Send + 'static,
     ) -> JoinHandle<T> {
         let op = Asyncify::new(move || {
             let res = std::panic::catch_unwind(AssertUnwindSafe(f));
             BufResult(Ok(0), res)
-        });
+        })
+        .with_options(options);
         // It is safe and sound to use `submit` here because the task is spawned
         // immediately.
         #[allow(deprecated)]
@@ -252,6 +424,62 @@ impl Runtime {
         self.driver.borrow_mut().attach(fd)
     }
 
+    /// Cancel all operations submitted against the fd. See
+    /// [`Proactor::cancel_fd`].
+    ///
+    /// You only need this when authoring your own high-level APIs. High-level
+    /// resources in this crate cancel them when closed.
+    pub fn cancel_fd(&self, fd: RawFd) {
+        self.driver.borrow_mut().cancel_fd(fd)
+    }
+
+    /// Get the [`RegisteredBuffers`] of the driver, if it is enabled by
+    /// [`ProactorBuilder::registered_buffers`].
+    pub fn registered_buffers(&self) -> Option<RegisteredBuffers> {
+        self.driver.borrow().registered_buffers().cloned()
+    }
+
+    /// The setup options of the driver that actually took effect. See
+    /// [`Proactor::setup_report`].
+    pub fn setup_report(&self) -> SetupReport {
+        self.driver.borrow().setup_report()
+    }
+
+    /// Take a snapshot of the counters of the runtime and its driver. See
+    /// [`Proactor::metrics`].
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> RuntimeMetrics {
+        // SAFETY: self is !Send + !Sync.
+        let (local_queue_len, sync_queue_len) = unsafe { self.runnables.len() };
+        let counters = &self.counters;
+        RuntimeMetrics {
+            driver: self.driver.borrow().metrics(),
+            local_queue_len,
+            sync_queue_len,
+            ticks: counters.ticks.get(),
+            tasks_polled: counters.tasks_polled.get(),
+            last_tick_polled: counters.last_tick_polled.get(),
+            max_tick_polled: counters.max_tick_polled.get(),
+        }
+    }
+
+    /// Register a file descriptor into the fixed file table of the driver.
+    /// See [`Proactor::register_fd`].
+    pub fn register_fd(&self, fd: OwnedFd) -> io::Result<FixedFd> {
+        self.driver.borrow_mut().register_fd(fd)
+    }
+
+    /// Create [`FixedFd`] from the result of a direct operation. See
+    /// [`Proactor::direct_fd`].
+    ///
+    /// # Safety
+    ///
+    /// See [`Proactor::direct_fd`].
+    #[cfg(unix)]
+    pub unsafe fn direct_fd(&self, res: usize) -> FixedFd {
+        self.driver.borrow_mut().direct_fd(res)
+    }
+
     fn submit_raw<T: OpCode + 'static>(&self, op: T) -> PushEntry<Key<T>, BufResult<usize, T>> {
         self.driver.borrow_mut().push(op)
     }
@@ -298,6 +526,10 @@ impl Runtime {
         self.driver.borrow_mut().cancel(op);
     }
 
+    pub(crate) fn interrupt_op<T: OpCode>(&self, op: &mut Key<T>) {
+        self.driver.borrow_mut().interrupt(op);
+    }
+
     #[cfg(feature = "time")]
     pub(crate) fn cancel_timer(&self, key: usize) {
         self.timer_runtime.borrow_mut().cancel(key);
@@ -334,10 +566,12 @@ impl Runtime {
     ///
     /// Get the timeout value to be passed to [`Proactor::poll`].
     pub fn current_timeout(&self) -> Option<Duration> {
-        #[cfg(not(feature = "time"))]
-        let timeout = None;
+        let timeout = self.driver.borrow().next_timeout();
         #[cfg(feature = "time")]
-        let timeout = self.timer_runtime.borrow().min_timeout();
+        let timeout = match (timeout, self.timer_runtime.borrow().min_timeout()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
         timeout
     }
 
@@ -368,8 +602,125 @@ impl Runtime {
                 _ => panic!("{e:?}"),
             },
         }
+        drop(driver);
         #[cfg(feature = "time")]
         self.timer_runtime.borrow_mut().wake();
+
+        loop {
+            let Some(message) = self.driver.borrow_mut().pop_message() else {
+                break;
+            };
+            let waker = match &message {
+                RingMessage::Data(id) => self.remote_wakers.borrow().get(id).cloned(),
+                RingMessage::Fd(_) => None,
+            };
+            match waker {
+                Some(waker) => waker.wake(),
+                None => self.messages.borrow_mut().push_back(message),
+            }
+        }
+        self.poll_posts();
+        while let Some(id) = self.remote_wakeups.pop() {
+            let waker = self.remote_wakers.borrow().get(&id).cloned();
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Pop a message posted to this runtime by [`MsgRing`] or
+    /// [`MsgRingFd`](compio_driver::op::MsgRingFd), which is received when
+    /// polling. The values with the highest bit set are reserved by
+    /// [`remote`](crate::remote) channels, and consumed by them.
+    pub fn pop_message(&self) -> Option<RingMessage> {
+        self.messages.borrow_mut().pop_front()
+    }
+
+    /// The fd to post messages to this runtime, if its driver supports
+    /// [`MsgRing`].
+    pub(crate) fn message_ring(&self) -> Option<RawFd> {
+        let driver = self.driver.borrow();
+        driver.supports(MsgRing::OPCODE).then(|| driver.as_raw_fd())
+    }
+
+    /// Post a message to the runtime with the ring fd. Returns `false` if it
+    /// cannot be posted from this runtime. If the posting fails later, the
+    /// message is queued to `wakeups` instead.
+    pub(crate) fn post_message(
+        &self,
+        ring: RawFd,
+        data: u32,
+        wakeups: &Arc<RemoteWakeups>,
+    ) -> bool {
+        if ring == self.as_raw_fd() || self.message_ring().is_none() {
+            return false;
+        }
+        match self.submit_raw(MsgRing::new(ring, data)) {
+            PushEntry::Pending(key) => {
+                self.posts.borrow_mut().push((key, wakeups.clone(), data));
+                true
+            }
+            PushEntry::Ready(BufResult(res, _)) => res.is_ok(),
+        }
+    }
+
+    /// Check the completed posts, and fall back to the wakeups for the failed
+    /// ones.
+    fn poll_posts(&self) {
+        let mut posts = self.posts.borrow_mut();
+        if posts.is_empty() {
+            return;
+        }
+        let mut driver = self.driver.borrow_mut();
+        *posts = std::mem::take(&mut *posts)
+            .into_iter()
+            .filter_map(|(key, wakeups, data)| match driver.pop(key) {
+                PushEntry::Pending(key) => Some((key, wakeups, data)),
+                PushEntry::Ready((BufResult(res, _), _)) => {
+                    if res.is_err() {
+                        wakeups.wake(data);
+                    }
+                    None
+                }
+            })
+            .collect();
+    }
+
+    /// Submit the pending posts before the driver is dropped, and fall back to
+    /// the wakeups for the ones not completed.
+    fn flush_posts(&self) {
+        if self.posts.borrow().is_empty() {
+            return;
+        }
+        self.driver.borrow_mut().poll(Some(Duration::ZERO)).ok();
+        self.poll_posts();
+        let mut driver = self.driver.borrow_mut();
+        for (key, wakeups, data) in self.posts.take() {
+            driver.cancel(key);
+            wakeups.wake(data);
+        }
+    }
+
+    pub(crate) fn register_remote(&self, waker: Arc<AtomicWaker>) -> u32 {
+        let mut wakers = self.remote_wakers.borrow_mut();
+        loop {
+            let id = self.next_remote_id.get();
+            self.next_remote_id.set(id.wrapping_add(1) & !REMOTE_BIT);
+            if let std::collections::hash_map::Entry::Vacant(e) = wakers.entry(id | REMOTE_BIT) {
+                e.insert(waker);
+                break id | REMOTE_BIT;
+            }
+        }
+    }
+
+    pub(crate) fn remote_wakeups(&self) -> Arc<RemoteWakeups> {
+        self.remote_wakeups.clone()
+    }
+
+    /// The registry of the remote channels, which may be dropped after this
+    /// runtime.
+    pub(crate) fn remote_wakers(&self) -> Weak<RemoteWakers> {
+        Rc::downgrade(&self.remote_wakers)
     }
 
     pub(crate) fn create_buffer_pool(
@@ -396,6 +747,9 @@ impl Runtime {
 
 impl Drop for Runtime {
     fn drop(&mut self) {
+        self.remote_wakeups.close();
+        self.flush_posts();
+        self.runnables.close();
         self.enter(|| {
             while self.runnables.sync_runnables.pop().is_some() {}
             let local_runnables = unsafe { self.runnables.local_runnables.get_unchecked() };
@@ -466,6 +820,15 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Inject faults into the operations submitted to the runtime, see
+    /// [`ProactorBuilder::faults`]. The rules are replaced by
+    /// [`RuntimeBuilder::with_proactor`], so set them after it.
+    #[cfg(feature = "faults")]
+    pub fn faults(&mut self, rules: FaultRules) -> &mut Self {
+        self.proactor_builder.faults(rules);
+        self
+    }
+
     /// Build [`Runtime`].
     pub fn build(&self) -> io::Result<Runtime> {
         Runtime::with_builder(self)
@@ -501,7 +864,8 @@ pub fn spawn<F: Future + 'static>(future: F) -> JoinHandle<F::Output> {
 
 /// Spawns a blocking task in a new thread, and wait for it.
 ///
-/// The task will not be cancelled even if the future is dropped.
+/// The task will not be cancelled even if the future is dropped. See
+/// [`Runtime::spawn_blocking`].
 ///
 /// ## Panics
 ///
@@ -513,6 +877,20 @@ pub fn spawn_blocking<T: Send + 'static>(
     Runtime::with_current(|r| r.spawn_blocking(f))
 }
 
+/// Spawns a blocking task with the options, and wait for it. See
+/// [`Runtime::spawn_blocking_with`].
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`].
+pub fn spawn_blocking_with<T: Send + 'static>(
+    options: DispatchOptions,
+    f: impl (FnOnce() -> T) + Send + 'static,
+) -> JoinHandle<T> {
+    Runtime::with_current(|r| r.spawn_blocking_with(options, f))
+}
+
 /// Submit an operation to the current runtime, and return a future for it.
 ///
 /// ## Panics
@@ -542,10 +920,152 @@ pub async fn submit_with_flags<T: OpCode + 'static>(op: T) -> (BufResult<usize,
     }
 }
 
+/// Submit an operation to the current runtime, which is interrupted when the
+/// token is cancelled.
+///
+/// If the token is cancelled before the operation completes, the operation is
+/// cancelled in the driver, e.g., with `AsyncCancel` on io-uring, and the
+/// future still waits for it to complete, so that the buffer is returned. The
+/// result is then an error of [`ErrorKind::Interrupted`](io::ErrorKind) if it
+/// is cancelled by the driver, or the result of the operation if it completes
+/// before being cancelled. If the token is cancelled before submission, the
+/// operation is not submitted at all.
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`].
+pub async fn submit_with_cancel<T: OpCode + 'static>(
+    op: T,
+    token: &CancellationToken,
+) -> BufResult<usize, T> {
+    if token.is_cancelled() {
+        return BufResult(Err(io::ErrorKind::Interrupted.into()), op);
+    }
+    let mut op = match Runtime::with_current(|r| r.submit_raw(op)) {
+        PushEntry::Pending(key) => OpFuture::new(key),
+        PushEntry::Ready(res) => return res,
+    };
+    let mut cancelled = pin!(token.cancelled());
+    let mut interrupted = false;
+    let BufResult(res, op) = poll_fn(|cx| {
+        if !interrupted && cancelled.as_mut().poll(cx).is_ready() {
+            op.interrupt();
+            interrupted = true;
+        }
+        Pin::new(&mut op).poll(cx).map(|(res, _)| res)
+    })
+    .await;
+    match res {
+        Err(e) if interrupted && compio_driver::is_cancelled(&e) => {
+            BufResult(Err(io::ErrorKind::Interrupted.into()), op)
+        }
+        res => BufResult(res, op),
+    }
+}
+
+/// Submit several operations to the current runtime at once, and return
+/// futures for them in the same order. With io-uring, the operations are
+/// submitted with one syscall.
+///
+/// The returned futures could be awaited in any order, and dropping one of them
+/// cancels the operation.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use compio_driver::op::Timeout;
+///
+/// # compio_runtime::Runtime::new().unwrap().block_on(async {
+/// let ops = (1..=3).map(|i| Timeout::new(Duration::from_millis(i)));
+/// let results = futures_util::future::join_all(compio_runtime::submit_all(ops)).await;
+/// for res in results {
+///     res.0.unwrap();
+/// }
+/// # })
+/// ```
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`].
+pub fn submit_all<T: OpCode + 'static>(
+    ops: impl IntoIterator<Item = T>,
+) -> Vec<impl Future<Output = BufResult<usize, T>>> {
+    // Collect the ops first, in case that the iterator uses the runtime.
+    let ops = ops.into_iter().collect::<Vec<_>>();
+    Runtime::with_current(|r| r.driver.borrow_mut().push_batch(ops))
+        .into_iter()
+        .map(|entry| match entry {
+            PushEntry::Pending(key) => Either::Left(OpFuture::new(key).map(|(res, _)| res)),
+            PushEntry::Ready(res) => Either::Right(ready(res)),
+        })
+        .collect()
+}
+
+/// Submit a multishot operation to the current runtime, and return a stream
+/// of its results. The intermediate results are yielded as
+/// [`MultishotItem::More`], and the stream ends after
+/// [`MultishotItem::Done`]. Dropping the stream cancels the operation.
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`].
+pub fn submit_multishot<T: OpCode + Multishot + 'static>(op: T) -> SubmitMultishot<T> {
+    SubmitMultishot::new(Runtime::with_current(|r| r.submit_raw(op)))
+}
+
+/// Submit a chain of linked operations to the current runtime. The operations
+/// are pushed to the [`Link`] in `f`, and they are started one after another.
+/// If one of them fails, the following ones fail without being started.
+///
+/// The chain is submitted after `f` returns. The returned futures could be
+/// awaited in any order, and dropping one of them cancels the operation.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use compio_driver::op::Timeout;
+///
+/// # compio_runtime::Runtime::new().unwrap().block_on(async {
+/// let (first, second) = compio_runtime::submit_link(|link| {
+///     (
+///         link.push(Timeout::new(Duration::from_millis(1))),
+///         link.push(Timeout::new(Duration::from_millis(1))),
+///     )
+/// });
+/// let (first, second) = futures_util::join!(first, second);
+/// first.0.unwrap();
+/// second.0.unwrap();
+/// # })
+/// ```
+///
+/// ## Panics
+///
+/// This method doesn't create runtime. It tries to obtain the current runtime
+/// by [`Runtime::with_current`]. It also panics if `f` accesses the current
+/// runtime, e.g., spawns a task.
+pub fn submit_link<R>(f: impl FnOnce(&mut Link) -> R) -> R {
+    Runtime::with_current(|r| {
+        let mut driver = r.driver.borrow_mut();
+        let mut link = Link::new(driver.link());
+        f(&mut link)
+    })
+}
+
 #[cfg(feature = "time")]
 pub(crate) async fn create_timer(instant: std::time::Instant) {
-    let key = Runtime::with_current(|r| r.timer_runtime.borrow_mut().insert(instant));
-    if let Some(key) = key {
-        TimerFuture::new(key).await
+    let delay = instant.saturating_duration_since(std::time::Instant::now());
+    if delay.is_zero() {
+        return;
+    }
+    let BufResult(res, _) = submit(compio_driver::op::Timeout::new(delay)).await;
+    if let Err(_e) = res {
+        compio_log::warn!("cannot create the timer in the driver: {_e}");
+        let key = Runtime::with_current(|r| r.timer_runtime.borrow_mut().insert(instant));
+        if let Some(key) = key {
+            TimerFuture::new(key).await
+        }
     }
 }
@@ -0,0 +1,86 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use compio_driver::{Key, OpCode, PushEntry, op::Multishot};
+use futures_util::Stream;
+
+use crate::{BufResult, Runtime};
+
+/// An item of [`SubmitMultishot`].
+pub enum MultishotItem<T: Multishot> {
+    /// An intermediate result.
+    More(T::Item),
+    /// The final result of the operation with flags. The stream ends after it.
+    Done(BufResult<usize, T>, u32),
+}
+
+enum State<T> {
+    Submitted(Key<T>),
+    Completed(BufResult<usize, T>, u32),
+    Finished,
+}
+
+/// A stream of the results of a multishot operation, see [`submit_multishot`].
+///
+/// [`submit_multishot`]: crate::submit_multishot
+pub struct SubmitMultishot<T: Multishot + OpCode> {
+    state: State<T>,
+}
+
+// The op is never pinned by the stream, and the driver holds it on the heap.
+impl<T: Multishot + OpCode> Unpin for SubmitMultishot<T> {}
+
+impl<T: Multishot + OpCode> SubmitMultishot<T> {
+    pub(crate) fn new(entry: PushEntry<Key<T>, BufResult<usize, T>>) -> Self {
+        let state = match entry {
+            PushEntry::Pending(key) => State::Submitted(key),
+            PushEntry::Ready(res) => State::Completed(res, 0),
+        };
+        Self { state }
+    }
+}
+
+impl<T: Multishot + OpCode> Stream for SubmitMultishot<T> {
+    type Item = MultishotItem<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let State::Submitted(key) = &mut this.state {
+            if let Some(item) = Runtime::with_current(|r| r.driver.borrow_mut().pop_multishot(key))
+            {
+                return Poll::Ready(Some(MultishotItem::More(item)));
+            }
+            let State::Submitted(key) = std::mem::replace(&mut this.state, State::Finished) else {
+                unreachable!()
+            };
+            match Runtime::with_current(|r| r.poll_task(cx, key)) {
+                PushEntry::Pending(key) => {
+                    this.state = State::Submitted(key);
+                    return Poll::Pending;
+                }
+                PushEntry::Ready((res, flags)) => this.state = State::Completed(res, flags),
+            }
+        }
+        match std::mem::replace(&mut this.state, State::Finished) {
+            // Take the intermediate results left before the final one.
+            State::Completed(mut res, flags) => match res.1.pop_multishot() {
+                Some(item) => {
+                    this.state = State::Completed(res, flags);
+                    Poll::Ready(Some(MultishotItem::More(item)))
+                }
+                None => Poll::Ready(Some(MultishotItem::Done(res, flags))),
+            },
+            _ => Poll::Ready(None),
+        }
+    }
+}
+
+impl<T: Multishot + OpCode> Drop for SubmitMultishot<T> {
+    fn drop(&mut self) {
+        if let State::Submitted(key) = std::mem::replace(&mut self.state, State::Finished) {
+            Runtime::with_current(|r| r.cancel_op(key));
+        }
+    }
+}
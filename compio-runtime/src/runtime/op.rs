@@ -18,6 +18,13 @@ impl<T: OpCode> OpFuture<T> {
     pub fn new(key: Key<T>) -> Self {
         Self { key: Some(key) }
     }
+
+    // Cancel the op, and keep waiting for it to get the buffer back.
+    pub fn interrupt(&mut self) {
+        if let Some(key) = &mut self.key {
+            Runtime::with_current(|r| r.interrupt_op(key));
+        }
+    }
 }
 
 impl<T: OpCode> Future for OpFuture<T> {
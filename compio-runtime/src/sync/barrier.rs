@@ -0,0 +1,122 @@
+use std::{
+    fmt,
+    future::poll_fn,
+    task::{Poll, Waker},
+};
+
+use super::Lock;
+
+struct State {
+    arrived: usize,
+    // Increased when all tasks arrive.
+    generation: u64,
+    waiters: Vec<(u64, Waker)>,
+    next_id: u64,
+}
+
+/// A barrier to let a number of tasks wait for each other.
+pub struct Barrier {
+    n: usize,
+    state: Lock<State>,
+}
+
+impl Barrier {
+    /// Create a barrier for `n` tasks. A barrier for 0 tasks behaves like one
+    /// for 1 task.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(1),
+            state: Lock::new(State {
+                arrived: 0,
+                generation: 0,
+                waiters: Vec::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Wait until all `n` tasks arrive. The barrier could be reused after all
+    /// tasks are released.
+    ///
+    /// If the future is dropped before all tasks arrive, the task is no longer
+    /// counted as arrived.
+    pub async fn wait(&self) -> BarrierWaitResult {
+        let (generation, id) = {
+            let mut state = self.state.lock();
+            state.arrived += 1;
+            if state.arrived == self.n {
+                state.arrived = 0;
+                state.generation += 1;
+                let waiters = std::mem::take(&mut state.waiters);
+                drop(state);
+                waiters.into_iter().for_each(|(_, waker)| waker.wake());
+                return BarrierWaitResult(true);
+            }
+            let id = state.next_id;
+            state.next_id += 1;
+            (state.generation, id)
+        };
+        let guard = Arrival {
+            barrier: self,
+            generation,
+            id,
+        };
+        poll_fn(|cx| {
+            let mut state = self.state.lock();
+            if state.generation != generation {
+                return Poll::Ready(());
+            }
+            match state.waiters.iter_mut().find(|(i, _)| *i == id) {
+                Some((_, waker)) => {
+                    if !waker.will_wake(cx.waker()) {
+                        *waker = cx.waker().clone();
+                    }
+                }
+                None => state.waiters.push((id, cx.waker().clone())),
+            }
+            Poll::Pending
+        })
+        .await;
+        std::mem::forget(guard);
+        BarrierWaitResult(false)
+    }
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Barrier")
+            .field("n", &self.n)
+            .field("arrived", &state.arrived)
+            .finish()
+    }
+}
+
+// Withdraws the arrival if the waiting future is dropped.
+struct Arrival<'a> {
+    barrier: &'a Barrier,
+    generation: u64,
+    id: u64,
+}
+
+impl Drop for Arrival<'_> {
+    fn drop(&mut self) {
+        let mut state = self.barrier.state.lock();
+        if state.generation == self.generation {
+            state.arrived -= 1;
+            state.waiters.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// The result of [`Barrier::wait`].
+#[derive(Debug, Clone, Copy)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    /// Whether the task is the last one arriving. Only one task is the leader
+    /// for each time the barrier is passed.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
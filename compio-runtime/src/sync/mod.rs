@@ -0,0 +1,78 @@
+//! Asynchronous synchronization primitives for the tasks on one runtime.
+//!
+//! The primitives are neither `Send` nor `Sync`, and their states are kept in
+//! [`RefCell`]s without atomic operations, because the tasks spawned by
+//! [`Runtime::spawn`] never leave their thread. The primitives of the same
+//! names in [`send`] could be shared by the tasks on different threads, e.g.,
+//! the ones of a [`Dispatcher`].
+//!
+//! All of them are fair: the waiting tasks are served in order. Dropping a
+//! waiting future, e.g., [`Mutex::lock`], is always safe, and a wakeup or a
+//! permit it has been given is passed on to the next one.
+//!
+//! ```
+//! use std::rc::Rc;
+//!
+//! use compio_runtime::sync::Mutex;
+//!
+//! # compio_runtime::Runtime::new().unwrap().block_on(async {
+//! let counter = Rc::new(Mutex::new(0));
+//! let tasks = (0..4)
+//!     .map(|_| {
+//!         let counter = counter.clone();
+//!         compio_runtime::spawn(async move {
+//!             *counter.lock().await += 1;
+//!         })
+//!     })
+//!     .collect::<Vec<_>>();
+//! for task in tasks {
+//!     task.await.unwrap();
+//! }
+//! assert_eq!(*counter.lock().await, 4);
+//! # })
+//! ```
+//!
+//! [`Runtime::spawn`]: crate::Runtime::spawn
+//! [`Dispatcher`]: https://docs.rs/compio-dispatcher
+
+use std::{
+    cell::{RefCell, RefMut},
+    marker::PhantomData,
+};
+
+mod barrier;
+mod mutex;
+mod notify;
+mod rwlock;
+mod semaphore;
+pub mod send;
+
+pub use barrier::*;
+pub use mutex::*;
+pub use notify::*;
+pub use rwlock::*;
+pub use semaphore::*;
+
+/// The lock of the state of a primitive.
+struct Lock<T: ?Sized> {
+    // The primitives are only used on one thread.
+    _p: PhantomData<*const ()>,
+    inner: RefCell<T>,
+}
+
+impl<T> Lock<T> {
+    fn new(value: T) -> Self {
+        Self {
+            _p: PhantomData,
+            inner: RefCell::new(value),
+        }
+    }
+}
+
+impl<T: ?Sized> Lock<T> {
+    // The wakers are woken after the lock is released, so that it is never
+    // locked twice.
+    fn lock(&self) -> RefMut<'_, T> {
+        self.inner.borrow_mut()
+    }
+}
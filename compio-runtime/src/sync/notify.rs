@@ -0,0 +1,203 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use super::Lock;
+
+#[derive(PartialEq, Eq)]
+enum Status {
+    Waiting,
+    // Notified by `notify_one`, which is passed on if the waiter is dropped.
+    NotifiedOne,
+    NotifiedAll,
+}
+
+struct Waiter {
+    id: u64,
+    status: Status,
+    waker: Waker,
+}
+
+struct State {
+    // Stored by `notify_one` when there is no waiter.
+    permit: bool,
+    // Increased by `notify_waiters`.
+    generation: u64,
+    waiters: VecDeque<Waiter>,
+    next_id: u64,
+}
+
+impl State {
+    fn notify_one(&mut self) -> Option<Waker> {
+        match self
+            .waiters
+            .iter_mut()
+            .find(|waiter| waiter.status == Status::Waiting)
+        {
+            Some(waiter) => {
+                waiter.status = Status::NotifiedOne;
+                Some(waiter.waker.clone())
+            }
+            None => {
+                self.permit = true;
+                None
+            }
+        }
+    }
+}
+
+/// Notify a task, or all the waiting tasks, to wake up.
+///
+/// [`Notify::notify_one`] wakes the task waiting first, or stores a permit
+/// for the next call of [`Notify::notified`] if there is none. The permit is
+/// not accumulated. [`Notify::notify_waiters`] wakes all the tasks waiting,
+/// and the futures created before it, without storing a permit.
+pub struct Notify {
+    state: Lock<State>,
+}
+
+impl Notify {
+    /// Create [`Notify`] without a permit.
+    pub fn new() -> Self {
+        Self {
+            state: Lock::new(State {
+                permit: false,
+                generation: 0,
+                waiters: VecDeque::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Wait for a notification.
+    pub fn notified(&self) -> Notified<'_> {
+        let generation = self.state.lock().generation;
+        Notified {
+            notify: self,
+            generation,
+            id: None,
+        }
+    }
+
+    /// Wake the task waiting first, or store a permit.
+    pub fn notify_one(&self) {
+        let waker = self.state.lock().notify_one();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Wake all the tasks waiting.
+    pub fn notify_waiters(&self) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        let wakers = state
+            .waiters
+            .iter_mut()
+            .filter(|waiter| waiter.status == Status::Waiting)
+            .map(|waiter| {
+                waiter.status = Status::NotifiedAll;
+                waiter.waker.clone()
+            })
+            .collect::<Vec<_>>();
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Notify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Notify")
+            .field("permit", &state.permit)
+            .field("waiters", &state.waiters.len())
+            .finish()
+    }
+}
+
+/// Future for [`Notify::notified`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    generation: u64,
+    id: Option<u64>,
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        let mut state = this.notify.state.lock();
+        match this.id {
+            None => {
+                if state.generation != this.generation {
+                    return Poll::Ready(());
+                }
+                if state.permit {
+                    state.permit = false;
+                    return Poll::Ready(());
+                }
+                let id = state.next_id;
+                state.next_id += 1;
+                state.waiters.push_back(Waiter {
+                    id,
+                    status: Status::Waiting,
+                    waker: cx.waker().clone(),
+                });
+                this.id = Some(id);
+                Poll::Pending
+            }
+            Some(id) => {
+                let pos = state
+                    .waiters
+                    .iter()
+                    .position(|waiter| waiter.id == id)
+                    .expect("the waiter should be queued");
+                let waiter = &mut state.waiters[pos];
+                if waiter.status == Status::Waiting {
+                    if !waiter.waker.will_wake(cx.waker()) {
+                        waiter.waker = cx.waker().clone();
+                    }
+                    Poll::Pending
+                } else {
+                    state.waiters.remove(pos);
+                    this.id = None;
+                    Poll::Ready(())
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let mut state = self.notify.state.lock();
+        let Some(pos) = state.waiters.iter().position(|waiter| waiter.id == id) else {
+            return;
+        };
+        let waiter = state.waiters.remove(pos).expect("the waiter should exist");
+        let waker = if waiter.status == Status::NotifiedOne {
+            state.notify_one()
+        } else {
+            None
+        };
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
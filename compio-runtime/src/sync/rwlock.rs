@@ -0,0 +1,161 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use super::{Semaphore, SemaphorePermit};
+
+// A reader holds one permit, and a writer holds all of them.
+const MAX_READS: usize = u32::MAX as usize >> 3;
+
+/// An asynchronous reader-writer lock. The tasks are given the lock in the
+/// order they start waiting, so a waiting writer blocks the readers after it.
+pub struct RwLock<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> RwLock<T> {
+    /// Create a reader-writer lock holding the value.
+    pub fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(MAX_READS),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consume the lock, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Lock with shared read access, and wait until it is available.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        let permit = self.semaphore.acquire().await;
+        // Safety: no writer holds the lock.
+        unsafe { RwLockReadGuard::new(permit, &self.value) }
+    }
+
+    /// Try to lock with shared read access without waiting.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        let permit = self.semaphore.try_acquire()?;
+        // Safety: no writer holds the lock.
+        Some(unsafe { RwLockReadGuard::new(permit, &self.value) })
+    }
+
+    /// Lock with exclusive write access, and wait until it is available.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let permit = self.semaphore.acquire_many(MAX_READS).await;
+        // Safety: the permits are exclusive.
+        unsafe { RwLockWriteGuard::new(permit, &self.value) }
+    }
+
+    /// Try to lock with exclusive write access without waiting.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        let permit = self.semaphore.try_acquire_many(MAX_READS)?;
+        // Safety: the permits are exclusive.
+        Some(unsafe { RwLockWriteGuard::new(permit, &self.value) })
+    }
+
+    /// Get the mutable reference of the value. No locking is needed because
+    /// the lock is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("RwLock");
+        match self.try_read() {
+            Some(guard) => d.field("value", &&*guard),
+            None => d.field("value", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+/// The guard of a [`RwLock`] locked with shared read access.
+#[must_use = "if unused the RwLock will immediately unlock"]
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    _permit: SemaphorePermit<'a>,
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized> RwLockReadGuard<'a, T> {
+    /// # Safety
+    /// No writer should hold the lock.
+    unsafe fn new(permit: SemaphorePermit<'a>, value: &'a UnsafeCell<T>) -> Self {
+        Self {
+            _permit: permit,
+            value: &*value.get(),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// The guard of a [`RwLock`] locked with exclusive write access.
+#[must_use = "if unused the RwLock will immediately unlock"]
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    _permit: SemaphorePermit<'a>,
+    value: &'a mut T,
+}
+
+impl<'a, T: ?Sized> RwLockWriteGuard<'a, T> {
+    /// # Safety
+    /// The permits should be exclusive.
+    unsafe fn new(permit: SemaphorePermit<'a>, value: &'a UnsafeCell<T>) -> Self {
+        Self {
+            _permit: permit,
+            value: &mut *value.get(),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
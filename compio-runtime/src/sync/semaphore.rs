@@ -0,0 +1,258 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use super::Lock;
+
+struct Waiter {
+    id: u64,
+    permits: usize,
+    waker: Waker,
+}
+
+struct State {
+    permits: usize,
+    waiters: VecDeque<Waiter>,
+    // The waiters which have been assigned the permits, but haven't been
+    // polled yet.
+    granted: Vec<u64>,
+    next_id: u64,
+}
+
+impl State {
+    /// Assign the permits to the waiters in order. Returns the wakers to be
+    /// woken after the lock is released.
+    fn grant(&mut self) -> Vec<Waker> {
+        let mut wakers = vec![];
+        while let Some(waiter) = self.waiters.front() {
+            if waiter.permits > self.permits {
+                break;
+            }
+            let waiter = self.waiters.pop_front().expect("the waiter should exist");
+            self.permits -= waiter.permits;
+            self.granted.push(waiter.id);
+            wakers.push(waiter.waker);
+        }
+        wakers
+    }
+}
+
+fn wake_all(wakers: Vec<Waker>) {
+    wakers.into_iter().for_each(Waker::wake);
+}
+
+/// A semaphore maintaining a set of permits. The tasks acquiring permits are
+/// served in order: a task waiting for many permits blocks the following ones,
+/// even if there are enough permits for them.
+pub struct Semaphore {
+    state: Lock<State>,
+}
+
+impl Semaphore {
+    /// The maximum number of permits.
+    pub const MAX_PERMITS: usize = usize::MAX >> 3;
+
+    /// Create a semaphore with the number of permits.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `permits` exceeds [`Semaphore::MAX_PERMITS`].
+    pub fn new(permits: usize) -> Self {
+        assert!(permits <= Self::MAX_PERMITS, "too many permits");
+        Self {
+            state: Lock::new(State {
+                permits,
+                waiters: VecDeque::new(),
+                granted: Vec::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// The number of permits available now.
+    pub fn available_permits(&self) -> usize {
+        self.state.lock().permits
+    }
+
+    /// Add permits to the semaphore, and wake the waiting tasks.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the permits exceed [`Semaphore::MAX_PERMITS`].
+    pub fn add_permits(&self, n: usize) {
+        let mut state = self.state.lock();
+        state.permits = state
+            .permits
+            .checked_add(n)
+            .filter(|permits| *permits <= Self::MAX_PERMITS)
+            .expect("too many permits");
+        let wakers = state.grant();
+        drop(state);
+        wake_all(wakers);
+    }
+
+    /// Acquire a permit. The permit is released when the returned
+    /// [`SemaphorePermit`] is dropped.
+    pub fn acquire(&self) -> Acquire<'_> {
+        self.acquire_many(1)
+    }
+
+    /// Acquire `n` permits at once.
+    pub fn acquire_many(&self, n: usize) -> Acquire<'_> {
+        Acquire {
+            semaphore: self,
+            permits: n,
+            id: None,
+        }
+    }
+
+    /// Try to acquire a permit without waiting. It fails if there are tasks
+    /// waiting, even if a permit is available.
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire_many(1)
+    }
+
+    /// Try to acquire `n` permits at once without waiting.
+    pub fn try_acquire_many(&self, n: usize) -> Option<SemaphorePermit<'_>> {
+        let mut state = self.state.lock();
+        if state.waiters.is_empty() && state.permits >= n {
+            state.permits -= n;
+            Some(SemaphorePermit::new(self, n))
+        } else {
+            None
+        }
+    }
+
+    fn release(&self, n: usize) {
+        let mut state = self.state.lock();
+        state.permits += n;
+        let wakers = state.grant();
+        drop(state);
+        wake_all(wakers);
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Semaphore")
+            .field("permits", &state.permits)
+            .field("waiters", &state.waiters.len())
+            .finish()
+    }
+}
+
+/// Future for [`Semaphore::acquire`] and [`Semaphore::acquire_many`]. If it
+/// is dropped after the permits are assigned to it, they are passed on to the
+/// next waiting task.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+    id: Option<u64>,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = SemaphorePermit<'a>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut state = this.semaphore.state.lock();
+        match this.id {
+            None => {
+                if state.waiters.is_empty() && state.permits >= this.permits {
+                    state.permits -= this.permits;
+                    return Poll::Ready(SemaphorePermit::new(this.semaphore, this.permits));
+                }
+                let id = state.next_id;
+                state.next_id += 1;
+                state.waiters.push_back(Waiter {
+                    id,
+                    permits: this.permits,
+                    waker: cx.waker().clone(),
+                });
+                this.id = Some(id);
+                Poll::Pending
+            }
+            Some(id) => {
+                if let Some(pos) = state.granted.iter().position(|granted| *granted == id) {
+                    state.granted.swap_remove(pos);
+                    this.id = None;
+                    return Poll::Ready(SemaphorePermit::new(this.semaphore, this.permits));
+                }
+                let waiter = state
+                    .waiters
+                    .iter_mut()
+                    .find(|waiter| waiter.id == id)
+                    .expect("the waiter should be queued");
+                if !waiter.waker.will_wake(cx.waker()) {
+                    waiter.waker = cx.waker().clone();
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let mut state = self.semaphore.state.lock();
+        if let Some(pos) = state.granted.iter().position(|granted| *granted == id) {
+            state.granted.swap_remove(pos);
+            state.permits += self.permits;
+        } else if let Some(pos) = state.waiters.iter().position(|waiter| waiter.id == id) {
+            // The following waiters may be blocked by this one.
+            state.waiters.remove(pos);
+        }
+        let wakers = state.grant();
+        drop(state);
+        wake_all(wakers);
+    }
+}
+
+/// The permits acquired from a [`Semaphore`]. They are released when it is
+/// dropped.
+#[must_use]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+impl<'a> SemaphorePermit<'a> {
+    fn new(semaphore: &'a Semaphore, permits: usize) -> Self {
+        Self { semaphore, permits }
+    }
+
+    /// The number of permits held.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Forget the permits without releasing them to the semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits > 0 {
+            self.semaphore.release(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish()
+    }
+}
@@ -0,0 +1,81 @@
+//! Asynchronous synchronization primitives for the tasks on different
+//! threads.
+//!
+//! They behave the same as the ones in [`sync`](super), but their states are
+//! protected by [`std::sync::Mutex`], so that they are `Send` and `Sync`.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use compio_runtime::sync::send::Barrier;
+//!
+//! let barrier = Arc::new(Barrier::new(4));
+//! let threads = (0..4)
+//!     .map(|_| {
+//!         let barrier = barrier.clone();
+//!         std::thread::spawn(move || {
+//!             compio_runtime::Runtime::new()
+//!                 .unwrap()
+//!                 .block_on(barrier.wait())
+//!                 .is_leader()
+//!         })
+//!     })
+//!     .collect::<Vec<_>>();
+//! let leaders = threads
+//!     .into_iter()
+//!     .map(|t| t.join().unwrap())
+//!     .filter(|leader| *leader)
+//!     .count();
+//! assert_eq!(leaders, 1);
+//! ```
+
+use std::sync::{self, PoisonError};
+
+// The same primitives are built with a thread-safe `Lock`.
+#[allow(clippy::duplicate_mod)]
+#[path = "barrier.rs"]
+mod barrier;
+#[allow(clippy::duplicate_mod)]
+#[path = "mutex.rs"]
+mod mutex;
+#[allow(clippy::duplicate_mod)]
+#[path = "notify.rs"]
+mod notify;
+#[allow(clippy::duplicate_mod)]
+#[path = "rwlock.rs"]
+mod rwlock;
+#[allow(clippy::duplicate_mod)]
+#[path = "semaphore.rs"]
+mod semaphore;
+
+pub use barrier::*;
+pub use mutex::*;
+pub use notify::*;
+pub use rwlock::*;
+pub use semaphore::*;
+
+// The values are protected by the semaphores, whose states are protected by
+// the locks.
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+/// The lock of the state of a primitive.
+struct Lock<T: ?Sized> {
+    inner: sync::Mutex<T>,
+}
+
+impl<T> Lock<T> {
+    fn new(value: T) -> Self {
+        Self {
+            inner: sync::Mutex::new(value),
+        }
+    }
+}
+
+impl<T: ?Sized> Lock<T> {
+    // The state is never left inconsistent by a panic, and the wakers are
+    // woken after the lock is released.
+    fn lock(&self) -> sync::MutexGuard<'_, T> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
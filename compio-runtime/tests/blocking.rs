@@ -0,0 +1,123 @@
+use std::{
+    panic::resume_unwind,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+};
+
+use compio_driver::{AsyncifyPool, DispatchOptions, Priority, ProactorBuilder};
+use compio_runtime::Runtime;
+
+fn runtime(pool: &AsyncifyPool) -> Runtime {
+    let mut proactor = ProactorBuilder::new();
+    proactor.reuse_thread_pool(pool.clone());
+    Runtime::builder().with_proactor(proactor).build().unwrap()
+}
+
+async fn yield_now() {
+    compio_runtime::spawn(async {}).await.unwrap();
+}
+
+#[test]
+fn cancel_queued() {
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(1)
+        .build();
+    runtime(&pool).block_on(async {
+        let (tx, rx) = mpsc::channel::<()>();
+        let started = Arc::new(AtomicBool::new(false));
+        let flag = started.clone();
+        let blocker = compio_runtime::spawn_blocking(move || {
+            flag.store(true, Ordering::Release);
+            rx.recv().ok();
+        });
+        while !started.load(Ordering::Acquire) {
+            yield_now().await;
+        }
+
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = ran.clone();
+        let options = DispatchOptions::new().cancel_queued(true);
+        let queued = compio_runtime::spawn_blocking_with(options, move || {
+            flag.store(true, Ordering::Release)
+        });
+        while pool.queue_len() == 0 {
+            yield_now().await;
+        }
+
+        // Dropping the handle cancels the task waiting in the queue.
+        drop(queued);
+        for _ in 0..100 {
+            if pool.queue_len() == 0 {
+                break;
+            }
+            yield_now().await;
+        }
+        assert_eq!(pool.queue_len(), 0);
+
+        tx.send(()).unwrap();
+        blocker.await.unwrap_or_else(|e| resume_unwind(e));
+        compio_runtime::spawn_blocking(|| {})
+            .await
+            .unwrap_or_else(|e| resume_unwind(e));
+        assert!(!ran.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn drop_queued() {
+    let pool = AsyncifyPool::builder()
+        .thread_limit(1)
+        .queue_capacity(1)
+        .build();
+    runtime(&pool).block_on(async {
+        let (tx, rx) = mpsc::channel::<()>();
+        let (started_tx, started_rx) = mpsc::channel::<()>();
+        let blocker = compio_runtime::spawn_blocking(move || {
+            started_tx.send(()).unwrap();
+            rx.recv().ok();
+        });
+        started_rx.recv().unwrap();
+
+        let (ran_tx, ran_rx) = mpsc::channel::<()>();
+        let queued = compio_runtime::spawn_blocking(move || ran_tx.send(()).unwrap());
+        while pool.queue_len() == 0 {
+            yield_now().await;
+        }
+
+        // The task still runs after the handle is dropped.
+        drop(queued);
+        tx.send(()).unwrap();
+        blocker.await.unwrap_or_else(|e| resume_unwind(e));
+        ran_rx.recv().unwrap();
+    })
+}
+
+#[test]
+fn dedicated_pool() {
+    let pool = AsyncifyPool::builder().thread_limit(1).build();
+    runtime(&pool).block_on(async {
+        let (tx, rx) = mpsc::channel::<()>();
+        let blocker = compio_runtime::spawn_blocking(move || {
+            rx.recv().ok();
+        });
+
+        // The slow work in the default pool doesn't starve the others.
+        let dedicated = AsyncifyPool::builder().name("dedicated").build();
+        let options = DispatchOptions::new()
+            .pool(dedicated)
+            .priority(Priority::High);
+        let name = compio_runtime::spawn_blocking_with(options, || {
+            std::thread::current().name().map(String::from)
+        })
+        .await
+        .unwrap_or_else(|e| resume_unwind(e));
+        assert_eq!(name.as_deref(), Some("dedicated"));
+
+        tx.send(()).unwrap();
+        blocker.await.unwrap_or_else(|e| resume_unwind(e));
+    })
+}
@@ -0,0 +1,133 @@
+use std::{
+    future::{Future, poll_fn},
+    pin::{Pin, pin},
+    task::Poll,
+};
+
+use compio_runtime::{CancellationToken, Runtime};
+
+async fn poll_once<F: Future>(mut future: Pin<&mut F>) -> Poll<F::Output> {
+    poll_fn(|cx| Poll::Ready(future.as_mut().poll(cx))).await
+}
+
+#[test]
+fn token_tree() {
+    Runtime::new().unwrap().block_on(async {
+        let token = CancellationToken::new();
+        let child = token.child_token();
+        let grandchild = child.child_token();
+        let other = token.child_token();
+
+        // Cancelling a child doesn't affect the parent.
+        let mut wait = Box::pin(token.cancelled());
+        assert!(poll_once(wait.as_mut()).await.is_pending());
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(grandchild.is_cancelled());
+        assert!(!token.is_cancelled());
+        assert!(!other.is_cancelled());
+        drop(wait);
+
+        let task = compio_runtime::spawn({
+            let other = other.clone();
+            async move { other.cancelled().await }
+        });
+        compio_runtime::spawn(async {}).await.unwrap();
+        token.cancel();
+        task.await.unwrap();
+        assert!(other.is_cancelled());
+        assert!(token.child_token().is_cancelled());
+        token.cancelled().await;
+    })
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{io, os::unix::net::UnixStream};
+
+    use compio_buf::{BufResult, IntoInner};
+    use compio_driver::{
+        DriverType, ProactorBuilder, ToSharedFd,
+        op::{BufResultExt, Recv, Send},
+    };
+    use compio_runtime::{Attacher, CancellationToken, Runtime, submit, submit_with_cancel};
+
+    use super::*;
+
+    /// Run the future on the runtimes of all available drivers.
+    fn block_on<F: Future>(f: impl Fn() -> F) {
+        for driver in [DriverType::Poll, DriverType::IoUring] {
+            if driver.is_available() {
+                let mut proactor = ProactorBuilder::new();
+                proactor.driver(driver);
+                let runtime = Runtime::builder().with_proactor(proactor).build().unwrap();
+                runtime.block_on(f());
+            }
+        }
+    }
+
+    fn pair() -> (Attacher<UnixStream>, Attacher<UnixStream>) {
+        let (rx, tx) = UnixStream::pair().unwrap();
+        (Attacher::new(rx).unwrap(), Attacher::new(tx).unwrap())
+    }
+
+    #[test]
+    fn interrupt_recv() {
+        block_on(|| async {
+            let (rx, tx) = pair();
+            let token = CancellationToken::new();
+            let child = token.child_token();
+            let op = Recv::new(rx.to_shared_fd(), Vec::with_capacity(16));
+            let mut recv = pin!(submit_with_cancel(op, &child));
+            assert!(poll_once(recv.as_mut()).await.is_pending());
+
+            // The op is interrupted, and the buffer is returned.
+            token.cancel();
+            let BufResult(res, op) = recv.await;
+            assert_eq!(res.unwrap_err().kind(), io::ErrorKind::Interrupted);
+            assert_eq!(op.into_inner().capacity(), 16);
+
+            // Dropping an interrupted op cancels it again.
+            let token = CancellationToken::new();
+            let op = Recv::new(rx.to_shared_fd(), Vec::with_capacity(16));
+            let mut recv = Box::pin(submit_with_cancel(op, &token));
+            assert!(poll_once(recv.as_mut()).await.is_pending());
+            token.cancel();
+            let _ = poll_once(recv.as_mut()).await;
+            drop(recv);
+
+            // The socket is still usable.
+            let BufResult(res, _) = submit(Send::new(tx.to_shared_fd(), b"hello")).await;
+            assert_eq!(res.unwrap(), 5);
+            let op = Recv::new(rx.to_shared_fd(), Vec::with_capacity(16));
+            let BufResult(res, buf) = submit(op).await.into_inner().map_advanced();
+            assert_eq!(res.unwrap(), 5);
+            assert_eq!(buf, b"hello");
+        })
+    }
+
+    #[test]
+    fn cancel_before_submit() {
+        block_on(|| async {
+            let (rx, tx) = pair();
+            let BufResult(res, _) = submit(Send::new(tx.to_shared_fd(), b"hello")).await;
+            assert_eq!(res.unwrap(), 5);
+
+            // The op is not submitted, so the data is kept.
+            let token = CancellationToken::new();
+            token.cancel();
+            let op = Recv::new(rx.to_shared_fd(), Vec::with_capacity(16));
+            let BufResult(res, _) = submit_with_cancel(op, &token).await;
+            assert_eq!(res.unwrap_err().kind(), io::ErrorKind::Interrupted);
+
+            let token = CancellationToken::new();
+            let op = Recv::new(rx.to_shared_fd(), Vec::with_capacity(16));
+            let BufResult(res, buf) = submit_with_cancel(op, &token)
+                .await
+                .into_inner()
+                .map_advanced();
+            assert_eq!(res.unwrap(), 5);
+            assert_eq!(buf, b"hello");
+        })
+    }
+}
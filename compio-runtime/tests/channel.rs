@@ -0,0 +1,208 @@
+use std::{
+    future::{Future, poll_fn},
+    pin::{Pin, pin},
+    task::Poll,
+};
+
+use compio_runtime::{
+    Runtime,
+    channel::{RecvError, SendError, TryRecvError, TrySendError, broadcast, mpsc, oneshot, watch},
+};
+
+async fn poll_once<F: Future>(mut future: Pin<&mut F>) -> Poll<F::Output> {
+    poll_fn(|cx| Poll::Ready(future.as_mut().poll(cx))).await
+}
+
+#[test]
+fn mpsc_bounded() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx) = mpsc::channel(2);
+        tx.try_send(0).unwrap();
+        tx.send(1).await.unwrap();
+        assert_eq!(tx.try_send(2), Err(TrySendError::Full(2)));
+        assert_eq!(tx.capacity(), 0);
+
+        // The senders are given the slots in order.
+        let tasks = (2..5)
+            .map(|i| {
+                let tx = tx.clone();
+                compio_runtime::spawn(async move { tx.send(i).await.unwrap() })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+        let mut values = vec![];
+        while let Some(value) = rx.recv().await {
+            values.push(value);
+        }
+        assert_eq!(values, [0, 1, 2, 3, 4]);
+        for task in tasks {
+            task.await.unwrap();
+        }
+    })
+}
+
+#[test]
+fn mpsc_cancel() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx) = mpsc::channel(1);
+        tx.send(0).await.unwrap();
+        let mut first = Box::pin(tx.send(1));
+        let mut second = pin!(tx.send(2));
+        assert!(poll_once(first.as_mut()).await.is_pending());
+        assert!(poll_once(second.as_mut()).await.is_pending());
+
+        // The slot is given to the first one, and passed on when it is dropped.
+        assert_eq!(rx.recv().await, Some(0));
+        drop(first);
+        second.await.unwrap();
+        assert_eq!(rx.try_recv(), Ok(2));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        let permit = tx.reserve().await.unwrap();
+        assert_eq!(tx.capacity(), 0);
+        drop(permit);
+        assert_eq!(tx.capacity(), 1);
+    })
+}
+
+#[test]
+fn mpsc_closed() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx) = mpsc::channel(1);
+        tx.send(0).await.unwrap();
+        let task = compio_runtime::spawn({
+            let tx = tx.clone();
+            async move { tx.send(1).await }
+        });
+        compio_runtime::spawn(async {}).await.unwrap();
+
+        rx.close();
+        assert!(tx.is_closed());
+        assert_eq!(task.await.unwrap(), Err(SendError(1)));
+        assert_eq!(tx.try_send(2), Err(TrySendError::Closed(2)));
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv().await, None);
+
+        // The values holding the senders are dropped with the receiver.
+        struct Holder(#[allow(dead_code)] mpsc::UnboundedSender<Holder>);
+        let (tx, rx) = mpsc::unbounded();
+        tx.send(Holder(tx.clone())).unwrap();
+        drop(rx);
+        assert!(tx.send(Holder(tx.clone())).is_err());
+    })
+}
+
+#[test]
+fn mpsc_unbounded() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx) = mpsc::unbounded();
+        let task = compio_runtime::spawn(async move {
+            let mut sum = 0;
+            while let Some(value) = rx.recv().await {
+                sum += value;
+            }
+            sum
+        });
+        for i in 0..100 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(task.await.unwrap(), 4950);
+    })
+}
+
+#[test]
+fn oneshot() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, rx) = oneshot::channel();
+        let task = compio_runtime::spawn(rx);
+        compio_runtime::spawn(async {}).await.unwrap();
+        tx.send(1).unwrap();
+        assert_eq!(task.await.unwrap(), Ok(1));
+
+        let (tx, rx) = oneshot::channel::<i32>();
+        drop(tx);
+        assert_eq!(rx.await, Err(RecvError));
+
+        let (tx, mut rx) = oneshot::channel();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert!(!tx.is_closed());
+        drop(rx);
+        assert_eq!(tx.send(1), Err(1));
+    })
+}
+
+#[test]
+fn broadcast() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx1) = broadcast::channel(2);
+        let mut rx2 = tx.subscribe();
+        assert_eq!(tx.send(0), Ok(2));
+
+        let task = compio_runtime::spawn(async move {
+            let mut values = vec![];
+            while let Ok(value) = rx1.recv().await {
+                values.push(value);
+            }
+            values
+        });
+        compio_runtime::spawn(async {}).await.unwrap();
+        for i in 1..4 {
+            tx.send(i).unwrap();
+            compio_runtime::spawn(async {}).await.unwrap();
+        }
+
+        // The receiver falling behind skips the values not kept.
+        assert_eq!(rx2.len(), 2);
+        assert_eq!(rx2.recv().await, Err(broadcast::RecvError::Lagged(2)));
+        let mut rx3 = rx2.clone();
+        assert_eq!(rx2.recv().await, Ok(2));
+        assert_eq!(rx2.recv().await, Ok(3));
+        assert_eq!(rx2.try_recv(), Err(broadcast::TryRecvError::Empty));
+        assert_eq!(rx3.try_recv(), Ok(2));
+
+        drop(tx);
+        assert_eq!(task.await.unwrap(), [0, 1, 2, 3]);
+        assert_eq!(rx2.recv().await, Err(broadcast::RecvError::Closed));
+        drop((rx2, rx3));
+    })
+}
+
+#[test]
+fn watch() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx) = watch::channel(0);
+        assert_eq!(rx.has_changed(), Ok(false));
+        let task = compio_runtime::spawn({
+            let mut rx = rx.clone();
+            async move {
+                let mut values = vec![];
+                while rx.changed().await.is_ok() {
+                    values.push(*rx.borrow_and_update());
+                }
+                values
+            }
+        });
+        compio_runtime::spawn(async {}).await.unwrap();
+
+        tx.send(1).unwrap();
+        compio_runtime::spawn(async {}).await.unwrap();
+        // The intermediate values may be missed.
+        tx.send(2).unwrap();
+        tx.send_modify(|value| *value += 1);
+        assert_eq!(rx.has_changed(), Ok(true));
+        assert_eq!(*rx.borrow_and_update(), 3);
+        assert_eq!(*tx.borrow(), 3);
+        assert_eq!(tx.receiver_count(), 2);
+
+        drop(tx);
+        assert_eq!(task.await.unwrap(), [1, 3]);
+        assert_eq!(rx.changed().await, Err(RecvError));
+
+        let (tx, rx) = watch::channel(0);
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(1), Err(SendError(1)));
+        assert_eq!(tx.send_replace(2), 0);
+    })
+}
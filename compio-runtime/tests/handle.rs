@@ -0,0 +1,84 @@
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind, panic_any},
+    rc::Rc,
+    thread,
+};
+
+use compio_driver::{DriverType, ProactorBuilder};
+use compio_runtime::{Runtime, remote};
+
+/// Create the runtimes of all available drivers.
+fn runtimes() -> impl Iterator<Item = Runtime> {
+    [DriverType::Poll, DriverType::IoUring, DriverType::IOCP]
+        .into_iter()
+        .filter(|d| d.is_available())
+        .map(|driver| {
+            let mut proactor = ProactorBuilder::new();
+            proactor.driver(driver);
+            Runtime::builder().with_proactor(proactor).build().unwrap()
+        })
+}
+
+#[test]
+fn spawn() {
+    for runtime in runtimes() {
+        let handle = runtime.handle();
+        runtime.block_on(async {
+            let thread = thread::spawn(move || {
+                let tasks = (0..4)
+                    .map(|i| handle.spawn(async move { i }))
+                    .collect::<Vec<_>>();
+                // The future is created on the runtime thread.
+                let local = handle.spawn_local_fn(|| async {
+                    let value = Rc::new(10);
+                    compio_runtime::spawn(async move { *value }).await.unwrap()
+                });
+                (tasks, local)
+            });
+            let (tasks, local) = thread.join().unwrap();
+            let mut sum = 0;
+            for task in tasks {
+                sum += task.await.unwrap();
+            }
+            assert_eq!(sum + local.await.unwrap(), 16);
+        })
+    }
+}
+
+#[test]
+fn block_on_remote() {
+    for runtime in runtimes() {
+        let handle = runtime.handle();
+        runtime.block_on(async {
+            let (tx, rx) = remote::oneshot();
+            let thread = thread::spawn(move || {
+                let value = handle
+                    .block_on_remote(async { compio_runtime::spawn(async { 42 }).await.unwrap() });
+                // The panic is resumed on the blocking thread.
+                let payload = catch_unwind(AssertUnwindSafe(|| {
+                    handle.block_on_remote(async { panic_any(1) })
+                }))
+                .unwrap_err();
+                tx.send((value, payload)).unwrap();
+            });
+            let (value, payload) = rx.await.unwrap();
+            assert_eq!(value, 42);
+            assert_eq!(payload.downcast_ref::<i32>(), Some(&1));
+            thread.join().unwrap();
+        })
+    }
+}
+
+#[test]
+fn runtime_dropped() {
+    let runtime = Runtime::new().unwrap();
+    let handle = runtime.handle();
+    drop(runtime);
+    let res = thread::spawn(move || {
+        let task = handle.spawn(async { 1 });
+        assert!(task.is_finished());
+        handle.block_on_remote(async { 1 })
+    })
+    .join();
+    assert!(res.is_err());
+}
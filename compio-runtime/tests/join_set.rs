@@ -0,0 +1,193 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::{Future, pending, poll_fn},
+    rc::Rc,
+    task::Poll,
+};
+
+use compio_runtime::{JoinSet, Runtime, channel::oneshot};
+
+// Set the flag when dropped.
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+async fn yield_now() {
+    compio_runtime::spawn(async {}).await.unwrap();
+}
+
+#[test]
+fn join_next_order() {
+    Runtime::new().unwrap().block_on(async {
+        let mut set = JoinSet::new();
+        let senders = (0..3)
+            .map(|i| {
+                let (tx, rx) = oneshot::channel::<()>();
+                set.spawn(async move {
+                    rx.await.unwrap();
+                    i
+                });
+                tx
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(set.len(), 3);
+        for (i, tx) in senders.into_iter().enumerate().rev() {
+            tx.send(()).unwrap();
+            assert_eq!(set.join_next().await.unwrap().unwrap(), i);
+        }
+        assert!(set.join_next().await.is_none());
+        assert!(set.is_empty());
+    })
+}
+
+#[test]
+fn join_panic() {
+    Runtime::new().unwrap().block_on(async {
+        let mut set = JoinSet::<()>::new();
+        set.spawn(async { panic!("boom") });
+        let payload = set.join_next().await.unwrap().unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+    })
+}
+
+#[test]
+fn abort_and_shutdown() {
+    Runtime::new().unwrap().block_on(async {
+        let flag = Rc::new(Cell::new(false));
+        let mut set = JoinSet::new();
+        let guard = DropFlag(flag.clone());
+        set.spawn(async move {
+            let _guard = guard;
+            pending::<()>().await
+        });
+        yield_now().await;
+        set.abort_all();
+        assert!(set.is_empty());
+        yield_now().await;
+        assert!(flag.get());
+
+        flag.set(false);
+        let guard = DropFlag(flag.clone());
+        set.spawn(async move {
+            let _guard = guard;
+            pending::<()>().await
+        });
+        set.shutdown().await;
+        assert!(flag.get());
+
+        flag.set(false);
+        let guard = DropFlag(flag.clone());
+        set.spawn(async move {
+            let _guard = guard;
+            pending::<()>().await
+        });
+        drop(set);
+        yield_now().await;
+        assert!(flag.get());
+    })
+}
+
+#[test]
+fn scope_borrow() {
+    Runtime::new().unwrap().block_on(async {
+        let values = RefCell::new(vec![]);
+        let values_ref = &values;
+        let (tx, rx) = oneshot::channel();
+        let res = compio_runtime::scope(|s| async move {
+            s.spawn(async move {
+                rx.await.unwrap();
+                values_ref.borrow_mut().push(1);
+            });
+            s.spawn(async move {
+                values_ref.borrow_mut().push(0);
+                tx.send(()).unwrap();
+            });
+            assert_eq!(s.len(), 2);
+            42
+        })
+        .await;
+        // All tasks end before the scope returns.
+        assert_eq!(res, 42);
+        assert_eq!(*values.borrow(), [0, 1]);
+    })
+}
+
+#[test]
+fn scope_join_next() {
+    Runtime::new().unwrap().block_on(async {
+        let base = 10;
+        let base = &base;
+        let sum = compio_runtime::scope(|s| async move {
+            for i in 0..3 {
+                s.spawn(async move { base + i });
+            }
+            s.spawn(async { panic!("boom") });
+            let mut sum = 0;
+            let mut panics = 0;
+            while let Some(res) = s.join_next().await {
+                match res {
+                    Ok(value) => sum += value,
+                    Err(_) => panics += 1,
+                }
+            }
+            assert_eq!(panics, 1);
+            sum
+        })
+        .await;
+        assert_eq!(sum, 33);
+    })
+}
+
+#[test]
+fn scope_abort() {
+    Runtime::new().unwrap().block_on(async {
+        let flag = Rc::new(Cell::new(false));
+        let flag_ref = &flag;
+        compio_runtime::scope(|s| async move {
+            let s2 = s.clone();
+            s.spawn(async move {
+                let _guard = DropFlag(flag_ref.clone());
+                pending::<()>().await
+            });
+            // Aborted by a task.
+            s.spawn(async move { s2.abort_all() });
+            assert!(s.join_next().await.unwrap().is_ok());
+            assert!(s.join_next().await.is_none());
+        })
+        .await;
+        assert!(flag.get());
+
+        // The tasks are dropped with the scope.
+        flag.set(false);
+        let mut scope = Box::pin(compio_runtime::scope(|s| async move {
+            s.spawn(async move {
+                let _guard = DropFlag(flag_ref.clone());
+                pending::<()>().await
+            });
+            pending::<()>().await
+        }));
+        poll_fn(|cx| {
+            assert!(scope.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        assert!(!flag.get());
+        drop(scope);
+        assert!(flag.get());
+    })
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn scope_panic() {
+    Runtime::new().unwrap().block_on(async {
+        compio_runtime::scope(|s| async move {
+            s.spawn(async { panic!("boom") });
+        })
+        .await
+    })
+}
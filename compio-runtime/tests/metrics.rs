@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use compio_driver::ProactorBuilder;
+use compio_runtime::Runtime;
+
+#[test]
+fn metrics() {
+    let mut proactor = ProactorBuilder::new();
+    proactor.op_metrics(true);
+    let runtime = Runtime::builder().with_proactor(proactor).build().unwrap();
+    runtime.block_on(async {
+        let tasks = (0..10)
+            .map(|i| compio_runtime::spawn(async move { i }))
+            .collect::<Vec<_>>();
+        for (i, task) in tasks.into_iter().enumerate() {
+            assert_eq!(task.await.unwrap(), i);
+        }
+        let res = compio_runtime::spawn_blocking(|| 42).await.unwrap();
+        assert_eq!(res, 42);
+    });
+
+    let metrics = runtime.metrics();
+    assert!(metrics.ticks > 0);
+    assert!(metrics.tasks_polled >= 11);
+    assert!(metrics.max_tick_polled >= metrics.last_tick_polled);
+    assert_eq!(metrics.local_queue_len, 0);
+    assert_eq!(metrics.sync_queue_len, 0);
+
+    let driver = &metrics.driver;
+    let asyncify = driver
+        .ops
+        .iter()
+        .find_map(|(name, op)| name.ends_with("Asyncify").then_some(*op))
+        .unwrap();
+    assert_eq!(asyncify.pushed, 1);
+    assert_eq!(asyncify.completed, 1);
+    assert_eq!(asyncify.cancelled, 0);
+    assert_eq!(driver.pushed(), driver.completed());
+    assert_eq!(driver.blocking_dispatched, 1);
+    assert!(driver.pool_threads >= 1);
+    // The blocking operation notifies the driver when it completes.
+    assert!(driver.notified >= 1);
+    assert!(driver.polls > 0);
+
+    let polls = driver.polls;
+    let poll_timeouts = driver.poll_timeouts;
+    runtime.poll_with(Some(Duration::ZERO));
+    runtime.poll_with(Some(Duration::ZERO));
+    let driver = runtime.metrics().driver;
+    assert_eq!(driver.polls, polls + 2);
+    assert!(driver.poll_timeouts > poll_timeouts);
+    assert!(!driver.ops.keys().any(|name| name.contains('<')));
+}
+
+#[test]
+fn op_metrics_disabled() {
+    let runtime = Runtime::new().unwrap();
+    let res = runtime.block_on(async { compio_runtime::spawn_blocking(|| 42).await });
+    assert_eq!(res.unwrap(), 42);
+    let driver = runtime.metrics().driver;
+    assert!(driver.ops.is_empty());
+    assert_eq!(driver.pushed(), 1);
+    assert_eq!(driver.completed(), 1);
+}
@@ -0,0 +1,126 @@
+use std::{thread, time::Duration};
+
+use compio_runtime::{Runtime, remote};
+
+#[test]
+fn remote_channel() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, mut rx) = remote::channel();
+        let threads = (0..2)
+            .map(|i| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    Runtime::new().unwrap().block_on(async move {
+                        for j in 0..100 {
+                            tx.send(i * 100 + j).unwrap();
+                            // Let the receiver wait for the next value.
+                            compio_runtime::spawn(async {}).await.unwrap();
+                        }
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        // A sender outside of any runtime.
+        thread::spawn(move || tx.send(200).unwrap());
+
+        let mut values = vec![];
+        while let Some(value) = rx.recv().await {
+            values.push(value);
+        }
+        values.sort();
+        assert_eq!(values, (0..=200).collect::<Vec<_>>());
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    })
+}
+
+#[test]
+fn remote_closed() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, rx) = remote::channel();
+        assert!(!tx.is_closed());
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(1), Err(1));
+    })
+}
+
+#[test]
+fn remote_oneshot() {
+    Runtime::new().unwrap().block_on(async {
+        let (tx, rx) = remote::oneshot();
+        let handle = thread::spawn(move || {
+            Runtime::new()
+                .unwrap()
+                .block_on(async move { tx.send(42).unwrap() })
+        });
+        assert_eq!(rx.await, Ok(42));
+        handle.join().unwrap();
+
+        let (tx, rx) = remote::oneshot::<i32>();
+        thread::spawn(move || drop(tx)).join().unwrap();
+        assert!(rx.await.is_err());
+    })
+}
+
+#[test]
+fn remote_drop_elsewhere() {
+    let first = Runtime::new().unwrap();
+    let second = Runtime::new().unwrap();
+    let (_tx, first_rx) = first.enter(remote::channel::<i32>);
+    second.block_on(async {
+        let (tx, mut rx) = remote::channel();
+        // Dropped in another runtime, which may have a channel with the same id.
+        drop(first_rx);
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            tx.send(1).unwrap();
+        });
+        assert_eq!(rx.recv().await, Some(1));
+        handle.join().unwrap();
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn remote_runtime_dropped() {
+    use std::{
+        future::poll_fn,
+        os::fd::{AsRawFd, FromRawFd, OwnedFd},
+        task::Poll,
+    };
+
+    use compio_driver::DriverType;
+
+    let runtime = Runtime::new().unwrap();
+    let (tx, mut rx) = runtime.block_on(async {
+        let (tx, mut rx) = remote::channel::<i32>();
+        // Wait once, so that the sender tries to wake the receiver.
+        poll_fn(|cx| {
+            assert!(rx.poll_recv(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        (tx, rx)
+    });
+    let other = Runtime::new().unwrap();
+    let fd = runtime.as_raw_fd();
+    drop(runtime);
+
+    // Let the fd of the dropped ring refer to another ring.
+    let _fd = DriverType::is_iouring().then(|| {
+        assert_eq!(unsafe { libc::dup2(other.as_raw_fd(), fd) }, fd);
+        unsafe { OwnedFd::from_raw_fd(fd) }
+    });
+    thread::spawn(move || {
+        Runtime::new()
+            .unwrap()
+            .block_on(async move { tx.send(1).unwrap() })
+    })
+    .join()
+    .unwrap();
+    other.poll_with(Some(Duration::from_millis(10)));
+    assert!(other.pop_message().is_none());
+    assert_eq!(rx.try_recv(), Some(1));
+}
@@ -0,0 +1,28 @@
+use std::{
+    future::poll_fn,
+    sync::mpsc,
+    task::{Poll, Waker},
+    thread,
+};
+
+use compio_runtime::Runtime;
+
+#[test]
+fn drop_waker_after_runtime() {
+    let (tx, rx) = mpsc::channel::<Waker>();
+    let runtime = Runtime::new().unwrap();
+    runtime.block_on(async {
+        compio_runtime::spawn(poll_fn(move |cx| {
+            tx.send(cx.waker().clone()).unwrap();
+            Poll::Ready(())
+        }))
+        .await
+        .unwrap();
+    });
+    drop(runtime);
+
+    // The waker holds the last reference to the task queue, and drops it on
+    // another thread.
+    let waker = rx.recv().unwrap();
+    thread::spawn(move || drop(waker)).join().unwrap();
+}
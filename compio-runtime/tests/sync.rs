@@ -0,0 +1,258 @@
+use std::{
+    cell::RefCell,
+    future::{Future, poll_fn},
+    panic::resume_unwind,
+    pin::{Pin, pin},
+    rc::Rc,
+    sync::Arc,
+    task::Poll,
+};
+
+use compio_driver::{DriverType, ProactorBuilder};
+use compio_runtime::{
+    JoinHandle, Runtime,
+    sync::{Barrier, Mutex, Notify, RwLock, Semaphore, send},
+};
+
+/// Run the future on the runtimes of all available drivers.
+fn block_on<F: Future>(f: impl Fn() -> F) {
+    let drivers = [DriverType::Poll, DriverType::IoUring, DriverType::IOCP];
+    for driver in drivers.into_iter().filter(|d| d.is_available()) {
+        let mut proactor = ProactorBuilder::new();
+        proactor.driver(driver);
+        let runtime = Runtime::builder().with_proactor(proactor).build().unwrap();
+        runtime.block_on(f());
+    }
+}
+
+async fn poll_once<F: Future>(mut future: Pin<&mut F>) -> Poll<F::Output> {
+    poll_fn(|cx| Poll::Ready(future.as_mut().poll(cx))).await
+}
+
+async fn join<T>(task: JoinHandle<T>) -> T {
+    task.await.unwrap_or_else(|e| resume_unwind(e))
+}
+
+/// Let the spawned tasks run until they are blocked.
+async fn yield_now() {
+    compio_runtime::spawn(async {}).await.unwrap();
+}
+
+#[test]
+fn mutex_fair() {
+    block_on(|| async {
+        let mutex = Rc::new(Mutex::new(vec![]));
+        let guard = mutex.lock().await;
+        let tasks = (0..4)
+            .map(|i| {
+                let mutex = mutex.clone();
+                compio_runtime::spawn(async move { mutex.lock().await.push(i) })
+            })
+            .collect::<Vec<_>>();
+        yield_now().await;
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+        for task in tasks {
+            join(task).await;
+        }
+        assert_eq!(*mutex.lock().await, [0, 1, 2, 3]);
+    })
+}
+
+#[test]
+fn mutex_cancel() {
+    block_on(|| async {
+        let mutex = Mutex::new(0);
+        let guard = mutex.lock().await;
+        let mut first = Box::pin(mutex.lock());
+        let mut second = pin!(mutex.lock());
+        assert!(poll_once(first.as_mut()).await.is_pending());
+        assert!(poll_once(second.as_mut()).await.is_pending());
+
+        // The lock is given to the first one, and passed on when it is dropped.
+        drop(guard);
+        drop(first);
+        *second.await += 1;
+        assert_eq!(*mutex.lock().await, 1);
+    })
+}
+
+#[test]
+fn rwlock() {
+    block_on(|| async {
+        let lock = Rc::new(RwLock::new(0));
+        let read1 = lock.read().await;
+        let read2 = lock.read().await;
+        assert_eq!(*read1 + *read2, 0);
+
+        let writer = compio_runtime::spawn({
+            let lock = lock.clone();
+            async move { *lock.write().await += 1 }
+        });
+        yield_now().await;
+        // The waiting writer blocks the new readers.
+        assert!(lock.try_read().is_none());
+        let reader = compio_runtime::spawn({
+            let lock = lock.clone();
+            async move { *lock.read().await }
+        });
+        yield_now().await;
+
+        drop(read1);
+        drop(read2);
+        join(writer).await;
+        assert_eq!(join(reader).await, 1);
+        assert!(lock.try_write().is_some());
+    })
+}
+
+#[test]
+fn semaphore() {
+    block_on(|| async {
+        let semaphore = Rc::new(Semaphore::new(3));
+        let permit = semaphore.acquire_many(2).await;
+        assert_eq!(permit.num_permits(), 2);
+        assert_eq!(semaphore.available_permits(), 1);
+
+        let order = Rc::new(RefCell::new(vec![]));
+        let tasks = [(0, 3), (1, 1)]
+            .into_iter()
+            .map(|(i, n)| {
+                let semaphore = semaphore.clone();
+                let order = order.clone();
+                compio_runtime::spawn(async move {
+                    let _permit = semaphore.acquire_many(n).await;
+                    order.borrow_mut().push(i);
+                })
+            })
+            .collect::<Vec<_>>();
+        yield_now().await;
+        // The task waiting for 3 permits blocks the one waiting for 1.
+        assert!(order.borrow().is_empty());
+        assert!(semaphore.try_acquire().is_none());
+
+        drop(permit);
+        for task in tasks {
+            join(task).await;
+        }
+        assert_eq!(*order.borrow(), [0, 1]);
+
+        semaphore.acquire().await.forget();
+        assert_eq!(semaphore.available_permits(), 2);
+        semaphore.add_permits(1);
+        assert_eq!(semaphore.available_permits(), 3);
+    })
+}
+
+#[test]
+fn notify() {
+    block_on(|| async {
+        let notify = Rc::new(Notify::new());
+        notify.notify_one();
+        notify.notify_one();
+        // The permit is not accumulated.
+        notify.notified().await;
+        assert!(poll_once(pin!(notify.notified())).await.is_pending());
+
+        let tasks = (0..3)
+            .map(|_| {
+                let notify = notify.clone();
+                compio_runtime::spawn(async move { notify.notified().await })
+            })
+            .collect::<Vec<_>>();
+        yield_now().await;
+        notify.notify_waiters();
+        for task in tasks {
+            join(task).await;
+        }
+
+        // The notification of a dropped future is passed on.
+        let mut first = Box::pin(notify.notified());
+        let mut second = pin!(notify.notified());
+        assert!(poll_once(first.as_mut()).await.is_pending());
+        assert!(poll_once(second.as_mut()).await.is_pending());
+        notify.notify_one();
+        drop(first);
+        second.await;
+    })
+}
+
+#[test]
+fn barrier() {
+    block_on(|| async {
+        let barrier = Rc::new(Barrier::new(3));
+
+        // The dropped one is not counted.
+        assert!(poll_once(pin!(barrier.wait())).await.is_pending());
+
+        for _ in 0..2 {
+            let tasks = (0..3)
+                .map(|_| {
+                    let barrier = barrier.clone();
+                    compio_runtime::spawn(async move { barrier.wait().await.is_leader() })
+                })
+                .collect::<Vec<_>>();
+            let mut leaders = 0;
+            for task in tasks {
+                leaders += join(task).await as usize;
+            }
+            assert_eq!(leaders, 1);
+        }
+    })
+}
+
+#[test]
+fn send_mutex() {
+    let mutex = Arc::new(send::Mutex::new(0));
+    let notify = Arc::new(send::Notify::new());
+    let semaphore = Arc::new(send::Semaphore::new(2));
+    let threads = (0..4)
+        .map(|_| {
+            let mutex = mutex.clone();
+            let notify = notify.clone();
+            let semaphore = semaphore.clone();
+            std::thread::spawn(move || {
+                block_on(|| async {
+                    let _permit = semaphore.acquire().await;
+                    for _ in 0..100 {
+                        *mutex.lock().await += 1;
+                    }
+                    notify.notify_one();
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Runtime::new().unwrap().block_on(notify.notified());
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let runs = [DriverType::Poll, DriverType::IoUring, DriverType::IOCP]
+        .into_iter()
+        .filter(|d| d.is_available())
+        .count();
+    assert_eq!(Arc::into_inner(mutex).unwrap().into_inner(), 400 * runs);
+    assert_eq!(semaphore.available_permits(), 2);
+}
+
+#[test]
+fn send_rwlock_barrier() {
+    let lock = Arc::new(send::RwLock::new(0));
+    let barrier = Arc::new(send::Barrier::new(4));
+    let threads = (0..4)
+        .map(|_| {
+            let lock = lock.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                Runtime::new().unwrap().block_on(async {
+                    *lock.write().await += 1;
+                    barrier.wait().await;
+                    *lock.read().await
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 4);
+    }
+}
@@ -0,0 +1,25 @@
+use std::time::{Duration, Instant};
+
+use compio_runtime::time::{sleep, timeout};
+
+#[test]
+fn sleep_elapsed() {
+    compio_runtime::Runtime::new().unwrap().block_on(async {
+        let start = Instant::now();
+        sleep(Duration::from_millis(100)).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    })
+}
+
+#[test]
+fn timeout_cancel() {
+    compio_runtime::Runtime::new().unwrap().block_on(async {
+        let start = Instant::now();
+        let res = timeout(Duration::from_millis(50), sleep(Duration::from_secs(10))).await;
+        assert!(res.is_err());
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        let res = timeout(Duration::from_secs(10), sleep(Duration::from_millis(50))).await;
+        assert!(res.is_ok());
+    })
+}
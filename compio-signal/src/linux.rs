@@ -6,7 +6,7 @@ use std::{
 };
 
 use compio_buf::{BufResult, IntoInner, IoBuf, IoBufMut, SetBufInit};
-use compio_driver::{OwnedFd, SharedFd, op::Recv, syscall};
+use compio_driver::{DriverType, OwnedFd, SharedFd, op::Recv, syscall};
 
 thread_local! {
     static REG_MAP: RefCell<HashMap<i32, usize>> = RefCell::new(HashMap::new());
@@ -57,7 +57,7 @@ impl SignalFd {
     fn new(sig: i32) -> io::Result<Self> {
         let set = register_signal(sig)?;
         let mut flag = libc::SFD_CLOEXEC;
-        if cfg!(not(feature = "io-uring")) || compio_driver::DriverType::is_polling() {
+        if cfg!(not(feature = "io-uring")) || DriverType::current() == DriverType::Poll {
             flag |= libc::SFD_NONBLOCK;
         }
         let fd = syscall!(libc::signalfd(-1, &set, flag))?;